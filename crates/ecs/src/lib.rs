@@ -1,7 +1,7 @@
 use std::ops::{Deref, DerefMut};
 
 use essay_ecs::{prelude::*, core::{error::Result, Local, Store, Schedule, schedule::Executors}};
-use util::random::random_test;
+use util::random::{random_reseed, random_test, Rand32, Rand64, RandomSource};
 
 pub struct MindApp {
     app: App
@@ -18,16 +18,38 @@ impl MindApp {
     }
 
     pub fn test() -> Self {
-        let app = Self::new();
+        let mut app = Self::new();
 
         random_test();
 
+        // fixed streams, independent of the test thread scheduling
+        app.insert_resource(RandomSource::new(42));
+
         app
     }
 
     pub fn build(self) -> App {
         self.app
     }
+
+    ///
+    /// Runs the tick schedules for at least n_ticks without a window.
+    /// 
+    pub fn run_for(&mut self, n_ticks: u64) -> Result<u64> {
+        HeadlessRunner::run_app_for(&mut self.app, n_ticks)
+    }
+
+    ///
+    /// Runs the tick schedules until the predicate holds or max_ticks
+    /// have elapsed.
+    /// 
+    pub fn run_until(
+        &mut self, 
+        max_ticks: u64, 
+        pred: impl FnMut(&mut App) -> bool
+    ) -> Result<u64> {
+        HeadlessRunner::run_app_until(&mut self.app, max_ticks, pred)
+    }
 }
 
 impl Deref for MindApp {
@@ -45,6 +67,171 @@ impl DerefMut for MindApp {
     }
 }
 
+///
+/// HeadlessRunner drives an app's tick schedules directly, without
+/// the winit/wgpu main loop, for batch experiments on machines without
+/// a display.
+/// 
+/// Each app update runs TickSchedulePlugin's ticks_per_update ticks, so
+/// the run can overshoot the requested count by up to that many ticks.
+/// 
+pub struct HeadlessRunner {
+    app: App,
+}
+
+impl HeadlessRunner {
+    pub fn new(app: App) -> Self {
+        assert!(app.contains_plugin::<TickSchedulePlugin>(),
+            "HeadlessRunner requires TickSchedulePlugin");
+
+        Self {
+            app
+        }
+    }
+
+    #[inline]
+    pub fn ticks(&self) -> u64 {
+        self.app.resource::<AppTick>().ticks()
+    }
+
+    #[inline]
+    pub fn app(&mut self) -> &mut App {
+        &mut self.app
+    }
+
+    pub fn into_app(self) -> App {
+        self.app
+    }
+
+    ///
+    /// Runs for n_ticks and returns the number of ticks actually run.
+    /// 
+    pub fn run_for(&mut self, n_ticks: u64) -> Result<u64> {
+        Self::run_app_for(&mut self.app, n_ticks)
+    }
+
+    ///
+    /// Runs until pred returns true or max_ticks have elapsed, returning
+    /// the number of ticks actually run.
+    /// 
+    pub fn run_until(
+        &mut self, 
+        max_ticks: u64, 
+        pred: impl FnMut(&mut App) -> bool
+    ) -> Result<u64> {
+        Self::run_app_until(&mut self.app, max_ticks, pred)
+    }
+
+    fn run_app_for(app: &mut App, n_ticks: u64) -> Result<u64> {
+        Self::run_app_until(app, n_ticks, |_| false)
+    }
+
+    fn run_app_until(
+        app: &mut App, 
+        max_ticks: u64, 
+        mut pred: impl FnMut(&mut App) -> bool
+    ) -> Result<u64> {
+        let start = Self::app_ticks(app);
+
+        while Self::app_ticks(app) - start < max_ticks {
+            let prev = Self::app_ticks(app);
+
+            app.tick()?;
+
+            // paused TickConfig, or the app has no ticks
+            if Self::app_ticks(app) == prev {
+                break;
+            }
+
+            if pred(app) {
+                break;
+            }
+        }
+
+        Ok(Self::app_ticks(app) - start)
+    }
+
+    fn app_ticks(app: &App) -> u64 {
+        if app.contains_resource::<AppTick>() {
+            app.resource::<AppTick>().ticks()
+        } else {
+            0
+        }
+    }
+}
+
+///
+/// SeedPlugin owns the master seed for the app's random streams.
+/// 
+/// Plugins request a named stream with SeedPlugin::rand32(app, name).
+/// Without a SeedPlugin, streams use a master seed drawn from the 
+/// thread-local generator, which is logged so the run can be replayed.
+/// MindApp::test() uses the fixed master seed 42. Seeding is opt-in:
+/// only an added SeedPlugin reseeds the thread-local generator behind
+/// util::random::random_uniform.
+/// 
+pub struct SeedPlugin {
+    seed: Option<u64>,
+}
+
+impl SeedPlugin {
+    pub fn new() -> Self {
+        Self {
+            seed: None,
+        }
+    }
+
+    pub fn seed(self, seed: u64) -> Self {
+        Self {
+            seed: Some(seed),
+        }
+    }
+
+    ///
+    /// Named 32-bit stream from the app's RandomSource
+    /// 
+    pub fn rand32(app: &mut App, name: &str) -> Rand32 {
+        Self::source(app).rand32(name)
+    }
+
+    ///
+    /// Named 64-bit stream from the app's RandomSource
+    /// 
+    pub fn rand64(app: &mut App, name: &str) -> Rand64 {
+        Self::source(app).rand64(name)
+    }
+
+    fn source(app: &mut App) -> RandomSource {
+        if ! app.contains_resource::<RandomSource>() {
+            // without a SeedPlugin, leave the thread-local generator alone
+            let seed = Rand64::new().next();
+            log::info!("random seed {}", seed);
+
+            app.insert_resource(RandomSource::new(seed));
+        }
+
+        app.resource::<RandomSource>().clone()
+    }
+}
+
+impl Plugin for SeedPlugin {
+    fn build(&self, app: &mut App) {
+        assert!(! app.contains_resource::<RandomSource>(),
+            "SeedPlugin must be added before plugins that use random streams");
+
+        let seed = match self.seed {
+            Some(seed) => seed,
+            None => Rand64::new().next(),
+        };
+
+        log::info!("random seed {}", seed);
+
+        random_reseed(seed);
+
+        app.insert_resource(RandomSource::new(seed));
+    }
+}
+
 #[derive(ScheduleLabel, Clone, Copy, Debug, PartialEq, Eq, Hash)]
 pub struct PreTick;
 
@@ -223,4 +410,33 @@ fn tick_system(
     Ok(())
 }
 
+#[cfg(test)]
+mod test {
+    use essay_ecs::core::error::Result;
 
+    use crate::{AppTick, MindApp, TickSchedulePlugin, HeadlessRunner};
+
+    #[test]
+    fn run_for() -> Result<()> {
+        let mut app = MindApp::test();
+
+        assert_eq!(10, app.run_for(10)?);
+        assert_eq!(10, app.resource::<AppTick>().ticks());
+
+        Ok(())
+    }
+
+    #[test]
+    fn run_until() -> Result<()> {
+        let mut app = essay_ecs::prelude::App::new();
+        app.plugin(TickSchedulePlugin::new().ticks(2));
+
+        let mut runner = HeadlessRunner::new(app);
+
+        let n = runner.run_until(100, |app| app.resource::<AppTick>().ticks() >= 5)?;
+        assert_eq!(6, n);
+        assert_eq!(6, runner.ticks());
+
+        Ok(())
+    }
+}
@@ -4,6 +4,7 @@ use rand::RngCore;
 
 // LCG/PCG random number generator from
 // https://nullprogram.com/blog/2019/11/19/
+#[derive(Clone)]
 pub struct Rand32(pub u64);
 
 impl Rand32 {
@@ -39,6 +40,11 @@ impl Rand32 {
         // Box-Muller
         (-2. * rng_a.ln()).sqrt() * (std::f32::consts::TAU * rng_b).cos()
     }
+
+    #[inline]
+    pub fn next_pareto(&mut self, low: f32, high: f32, alpha: f32) -> f32 {
+        pareto(self.next_uniform(), low, high, alpha)
+    }
 }
 
 impl From<&str> for Rand32 {
@@ -127,8 +133,11 @@ pub fn random_normal() -> f32 {
 }
 
 pub fn random_pareto(low: f32, high: f32, alpha: f32) -> f32 {
-    let x = random();
+    pareto(random(), low, high, alpha)
+}
 
+// bounded pareto from a uniform sample x
+fn pareto(x: f32, low: f32, high: f32, alpha: f32) -> f32 {
     let h_a = high.powf(alpha);
     let l_a = low.powf(alpha);
 
@@ -143,6 +152,57 @@ pub fn random_seed(seed: u64) {
     SEED.store(seed, Ordering::Release);
 }
 
+/// Reseeds the current thread's generator, used by random_uniform and
+/// related functions, as well as the seed for new threads
+pub fn random_reseed(seed: u64) {
+    SEED.store(seed, Ordering::Release);
+
+    LOCAL_RNG.with(|x| { *x.borrow_mut() = Rand64(seed as u128) });
+}
+
+///
+/// RandomSource owns a simulation's master seed and hands out
+/// independent, named streams, so a run can be replayed from its seed.
+/// 
+/// The same seed and name always produce the same stream.
+/// 
+#[derive(Clone, Debug)]
+pub struct RandomSource {
+    seed: u64,
+}
+
+impl RandomSource {
+    pub fn new(seed: u64) -> Self {
+        Self {
+            seed
+        }
+    }
+
+    #[inline]
+    pub fn seed(&self) -> u64 {
+        self.seed
+    }
+
+    pub fn rand32(&self, name: &str) -> Rand32 {
+        Rand32(self.stream_seed(name))
+    }
+
+    pub fn rand64(&self, name: &str) -> Rand64 {
+        let mut rand = Rand64(self.stream_seed(name) as u128);
+
+        Rand64(((rand.next() as u128) << 64) | rand.next() as u128)
+    }
+
+    fn stream_seed(&self, name: &str) -> u64 {
+        let mut rand = Rand64::from(name);
+        rand.0 ^= self.seed as u128;
+
+        // mix so nearby seeds and names don't produce correlated streams
+        rand.next();
+        rand.next()
+    }
+}
+
 fn next_u64_local() -> u64 {
     LOCAL_RNG.with(|x| { x.borrow_mut().next() })
 }
@@ -183,7 +243,7 @@ static SEED : AtomicU64 = AtomicU64::new(0);
 mod test {
     use crate::random::{random_normal, random_uniform};
 
-    use super::Rand32;
+    use super::{Rand32, RandomSource};
 
     #[test]
     fn test_uniform() {
@@ -290,6 +350,20 @@ mod test {
         assert_eq!(0.21809709, rnd.next_uniform());
     }
 
+    #[test]
+    fn random_source_streams() {
+        let source = RandomSource::new(42);
+
+        let a = source.rand32("food").next();
+        assert_eq!(a, source.rand32("food").next());
+        assert_ne!(a, source.rand32("body").next());
+        assert_ne!(a, RandomSource::new(43).rand32("food").next());
+
+        let a = source.rand64("hippocampus").next();
+        assert_eq!(a, source.rand64("hippocampus").next());
+        assert_ne!(a, source.rand64("food").next());
+    }
+
     #[test]
     fn rand32_new() {
         let mut rnd = Rand32::new();
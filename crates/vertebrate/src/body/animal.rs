@@ -0,0 +1,25 @@
+use essay_ecs::prelude::*;
+
+///
+/// Animal tags an extra body in the world with a reflex hindbrain: an
+/// entity with its own Body, HindMove, OlfactoryBulb and optional Retina.
+/// 
+/// An extra body senses and moves on its own, seeking its sensed food
+/// odor and otherwise roaming, but it has no motives, mid-brain, eating,
+/// sleep or metabolism, and isn't shown in the UI. The full brain only
+/// drives the primary animal's Body, HindMove, OlfactoryBulb and Retina
+/// resources.
+/// 
+#[derive(Component, Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct Animal(usize);
+
+impl Animal {
+    pub(crate) fn new(id: usize) -> Self {
+        Self(id)
+    }
+
+    #[inline]
+    pub fn id(&self) -> usize {
+        self.0
+    }
+}
@@ -1,11 +1,11 @@
-use essay_ecs::prelude::*;
+use essay_ecs::{core::Query, prelude::*};
 
-use mind_ecs::Tick;
+use mind_ecs::{SeedPlugin, Tick};
 use test_log::{TestLog, TestLogPlugin};
-use util::random::random_uniform;
+use util::random::Rand32;
 use crate::body::touch::Touch;
 
-use crate::util::{Angle, Heading, Point, Seconds, Ticks, Turn};
+use crate::util::{Angle, Heading, Line, Point, Seconds, Ticks, Turn};
 use crate::world::World;
 
 ///
@@ -13,9 +13,14 @@ use crate::world::World;
 /// 
 fn body_update(
     mut body: ResMut<Body>,
+    animals: Query<&mut Body>,
     world: Res<World>,
 ) {
     body.update(world.get());
+
+    for body in animals.iter() {
+        body.update(world.get());
+    }
 }
 
 ///
@@ -30,6 +35,7 @@ fn body_update(
 /// Movement is mildly stochastic, meaning the speed and turn direction
 /// aren't precise or perfectly predictable. 
 /// 
+#[derive(Component)]
 pub struct Body {
     body_len: f32,
     middle_len: f32,
@@ -48,6 +54,10 @@ pub struct Body {
     collide_left: bool,
     collide_right: bool,
     collide_forward: bool,
+
+    is_dead: bool,
+
+    rand: Rand32,
 }
 
 impl Body {
@@ -79,9 +89,26 @@ impl Body {
             collide_left: false,
             collide_right: false,
             collide_forward: false,
+
+            is_dead: false,
+
+            rand: Rand32::new(),
         }
     }
 
+    #[inline]
+    pub fn is_dead(&self) -> bool {
+        self.is_dead
+    }
+
+    ///
+    /// A dead body stops and no longer moves.
+    /// 
+    pub(crate) fn set_dead(&mut self) {
+        self.is_dead = true;
+        self.action = Action::none();
+    }
+
     #[inline]
     pub fn len(&self) -> f32 {
         self.body_len
@@ -130,6 +157,11 @@ impl Body {
         self.dir + self.turn() + cast
     }
 
+    #[inline]
+    pub(crate) fn set_dir(&mut self, dir: Heading) {
+        self.dir = dir;
+    }
+
     #[inline]
     pub fn head_cast(&self) -> f32 {
         self.cast_pos.sin()
@@ -213,6 +245,10 @@ impl Body {
     /// Update the animal's position
     /// 
     pub fn update(&mut self, world: &World) {
+        if self.is_dead {
+            return;
+        }
+
         self.action.update();
 
         let speed = self.action.speed;
@@ -221,8 +257,8 @@ impl Body {
         dir += self.action.turn.to_unit();
 
         // random noise into direction
-        if speed > 0. && random_uniform() < self.noise_threshold {
-            if random_uniform() < 0.5 {
+        if speed > 0. && self.rand.next_uniform() < self.noise_threshold {
+            if self.rand.next_uniform() < 0.5 {
                 dir += 0.005;
             } else {
                 dir -= 0.005;
@@ -259,12 +295,59 @@ impl Body {
         x = (1. - speed) * x + speed * head.0;
         y = (1. - speed) * y + speed * head.1;
 
+        let delta = Point(x, y) - prev;
+
         self.pos = Point(x, y);
 
-        if world.is_collide(self.pos()) || world.is_collide(self.head_pos()) {
+        if self.is_collide_move(world, prev) {
+            let normal = world.collide_normal(self.pos)
+                .or_else(|| world.collide_normal(self.head_pos()));
+
+            self.pos = prev;
+
+            // slide along the wall by dropping the motion into it
+            let mut slides = Vec::new();
+
+            if let Some(n) = normal {
+                slides.push(delta - delta.dot(n) * n);
+            }
+
+            slides.push(Point(delta.x(), 0.));
+            slides.push(Point(0., delta.y()));
+
+            for slide in slides {
+                self.pos = prev + slide;
+
+                if ! self.is_collide_move(world, prev) {
+                    return;
+                }
+            }
+
             self.pos = prev;
         }
     }
+
+    ///
+    /// Passive displacement, like drift in a current, blocked by walls.
+    ///
+    pub(crate) fn displace(&mut self, world: &World, delta: Point) {
+        let prev = self.pos;
+
+        self.pos = prev + delta;
+
+        if self.is_collide_move(world, prev) {
+            self.pos = prev;
+        }
+    }
+
+    fn is_collide_move(&self, world: &World, prev: Point) -> bool {
+        let head = self.head_pos();
+
+        world.is_collide(self.pos)
+            || world.is_collide(head)
+            || world.is_cross(Line(self.pos, head))
+            || world.is_cross(Line(prev, self.pos))
+    }
 }
 
 
@@ -356,6 +439,10 @@ impl BodyPlugin {
         self
     }
 
+    pub(crate) fn set_pos(&mut self, pos: Point) {
+        self.pos = pos;
+    }
+
     //
     // Sets the animal's casting.
     //
@@ -368,19 +455,30 @@ impl BodyPlugin {
 
         self
     }
-}
-
-impl Plugin for BodyPlugin {
-    fn build(&self, app: &mut App) {
-        assert!(app.contains_resource::<World>(), "BodyPlugin requires World<Wall>");
 
-        let mut body = Body::new(Point(0.5, 0.5));
+    ///
+    /// Creates a body with this plugin's configuration and a named 
+    /// random stream, used for the primary body and animal entities.
+    /// 
+    pub(crate) fn create_body(&self, app: &mut App, pos: Point, name: &str) -> Body {
+        let mut body = Body::new(pos);
+        body.rand = SeedPlugin::rand32(app, name);
 
         if self.cast_period.0 >= 0. {
             body.set_cast_period(self.cast_period);
             body.set_cast_angle(Angle::Deg(20.));
         }
 
+        body
+    }
+}
+
+impl Plugin for BodyPlugin {
+    fn build(&self, app: &mut App) {
+        assert!(app.contains_resource::<World>(), "BodyPlugin requires World<Wall>");
+
+        let body = self.create_body(app, self.pos, "body");
+
         app.insert_resource(body);
 
         app.event::<Touch>();
@@ -2,12 +2,14 @@ use essay_ecs::{
     app::{App, Plugin},
     core::{Query, Res, ResMut},
 };
-use mind_ecs::Tick;
+use mind_ecs::{SeedPlugin, Tick};
+use util::random::Rand32;
 
 use crate::{
     body::BodyPlugin,
+    olfactory::olfactory_bulb::OlfactoryBulb,
     util::{DecayValue, Point, Seconds, Ticks, TimeoutValue},
-    world::{Food, FoodKind, World},
+    world::{Food, FoodKind, OdorKind, World},
 };
 
 use super::Body;
@@ -16,9 +18,10 @@ fn body_eat_update(
     mut body_eat: ResMut<BodyEat>, 
     body: Res<Body>, 
     world: Res<World>,
-    food: Query<&mut Food>
+    food: Query<&mut Food>,
+    bulb: Option<Res<OlfactoryBulb>>,
 ) {
-    body_eat.update(body.head_pos(), world.get(), food);
+    body_eat.update(body.head_pos(), world.get(), food, bulb.as_ref().map(|b| b.get()));
 }
 
 // BodyEat is physical simulation of eating and sensors
@@ -58,7 +61,11 @@ pub struct BodyEat {
 
     is_eating: TimeoutValue<bool>,
 
-    gut_delay: DelayRing<FoodKind>,
+    gut_delay: DelayRing<Meal>,
+    digested: FoodKind,
+    digested_odors: Vec<OdorKind>,
+
+    rand: Rand32,
 }
 
 impl BodyEat {
@@ -141,6 +148,18 @@ impl BodyEat {
         self.gut_glucose.value() < 0.1
     }
 
+    /// food leaving the gut delay this tick
+    #[inline]
+    pub fn digested(&self) -> FoodKind {
+        self.digested
+    }
+
+    /// odors at the head when the digested food was eaten
+    #[inline]
+    pub fn digested_odors(&self) -> &[OdorKind] {
+        &self.digested_odors
+    }
+
     #[inline]
     pub fn is_eating(&self) -> bool {
         self.is_eating.value_or(false)
@@ -160,7 +179,8 @@ impl BodyEat {
         &mut self, 
         head_pos: Point, 
         world: &World,
-        food: Query<&mut Food>
+        food: Query<&mut Food>,
+        bulb: Option<&OlfactoryBulb>,
     ) {
         self.pre_update();
 
@@ -168,10 +188,10 @@ impl BodyEat {
 
         if self.is_eating() {
             if let Some(food) = food.iter().find(|f| f.is_pos(head_pos)) {
-                self.update_food(food);
+                self.update_food(food, bulb);
             } else if let Some(food) = world.base_food() {
                 let mut food = food.clone();
-                self.update_food(&mut food);
+                self.update_food(&mut food, bulb);
             }
         }
 
@@ -200,8 +220,12 @@ impl BodyEat {
     }
 
     fn update_gut(&mut self) {
+        let meal = self.gut_delay.take();
+        self.digested = meal.kind;
+        self.digested_odors = meal.odors;
+
         // update gut values
-        match self.gut_delay.take() {
+        match self.digested {
             FoodKind::None => {}
             FoodKind::Poor => {}
             FoodKind::Plain => {
@@ -220,9 +244,16 @@ impl BodyEat {
         }
     }
 
-    fn update_food(&mut self, food: &mut Food) {
-        if food.eat_probability() {
-            self.gut_delay.set(food.kind());
+    fn update_food(&mut self, food: &mut Food, bulb: Option<&OlfactoryBulb>) {
+        if food.eat_probability(&mut self.rand) {
+            // the odors sensed while eating travel with the meal for
+            // delayed conditioning, falling back to the food's own odor
+            let odors = match bulb {
+                Some(bulb) => bulb.active_odors().collect(),
+                None => food.odor().into_iter().collect(),
+            };
+
+            self.gut_delay.set(Meal { kind: food.kind(), odors });
 
             match food.kind() {
                 FoodKind::None => {}
@@ -261,6 +292,8 @@ impl Default for BodyEat {
             sated_cck: DecayValue::new(Seconds(40.)).fill_time(Seconds(20.)),
 
             gut_delay: DelayRing::new(Seconds(30.)),
+            digested: FoodKind::None,
+            digested_odors: Vec::new(),
 
             gut_food: DecayValue::new(Seconds(40.)).fill_time(Seconds(20.)),
             gut_glucose: DecayValue::new(Seconds(40.)).fill_time(Seconds(20.)),
@@ -269,10 +302,18 @@ impl Default for BodyEat {
             gut_sickness: DecayValue::new(Seconds(60.)),
 
             is_eating: TimeoutValue::default(),
+
+            rand: Rand32::new(),
         }
     }
 }
 
+#[derive(Clone, Debug, Default)]
+struct Meal {
+    kind: FoodKind,
+    odors: Vec<OdorKind>,
+}
+
 struct DelayRing<V: Clone + Default> {
     vec: Vec<V>,
     i: usize,
@@ -330,6 +371,7 @@ impl Plugin for BodyEatPlugin {
 
         let mut body_eat = BodyEat::default();
         body_eat.gut_delay = DelayRing::new(self.gut_delay);
+        body_eat.rand = SeedPlugin::rand32(app, "body_eat");
 
         app.insert_resource(body_eat);
 
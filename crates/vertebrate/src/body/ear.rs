@@ -0,0 +1,354 @@
+use std::sync::Mutex;
+
+use audio::source::AudioFilter;
+use essay_ecs::{
+    app::{App, Plugin},
+    core::{Query, Res, ResMut},
+};
+use mind_ecs::Tick;
+
+use crate::{
+    util::{DecayValue, Point, Seconds},
+    world::SoundSource,
+};
+
+use super::{Body, BodyPlugin};
+
+fn update_ear(
+    mut ear: ResMut<Ear>,
+    body: Res<Body>,
+    sounds: Query<&SoundSource>,
+) {
+    let sounds: Vec<&SoundSource> = sounds.iter().collect();
+
+    ear.update(body.get(), &sounds);
+}
+
+///
+/// Ear is a pair of inner ears on either side of the head. Each tick the
+/// sound pressure at each ear passes through a bandpass filterbank into
+/// cochlear-like channel energies, like the hair cells of the zebrafish
+/// saccule and lagena.
+///
+/// The head shadows sounds from the opposite side, so the level
+/// difference between the ears localizes the source. A sudden rise in
+/// level over its recent average is an onset, which drives the Mauthner
+/// cell acoustic startle.
+///
+pub struct Ear {
+    freqs: Vec<(f32, f32)>,
+    offset: f32,
+    shadow: f32,
+
+    left: Cochlea,
+    right: Cochlea,
+}
+
+impl Ear {
+    ///
+    /// Frequency band of each channel in Hz.
+    ///
+    #[inline]
+    pub fn freqs(&self) -> &Vec<(f32, f32)> {
+        &self.freqs
+    }
+
+    ///
+    /// RMS energy of each filterbank channel.
+    ///
+    #[inline]
+    pub fn channels(&self, side: EarSide) -> &Vec<f32> {
+        &self.cochlea(side).energy
+    }
+
+    ///
+    /// Saturating loudness in [0, 1] of the strongest channel.
+    ///
+    #[inline]
+    pub fn level(&self, side: EarSide) -> f32 {
+        self.cochlea(side).level
+    }
+
+    ///
+    /// Level rise over the recent average in [0, 1].
+    ///
+    #[inline]
+    pub fn onset(&self, side: EarSide) -> f32 {
+        self.cochlea(side).onset
+    }
+
+    ///
+    /// Interaural level difference in [-1, 1], positive for a sound on
+    /// the right.
+    ///
+    pub fn direction(&self) -> f32 {
+        let left = self.left.level;
+        let right = self.right.level;
+
+        if left + right > 0. {
+            (right - left) / (right + left)
+        } else {
+            0.
+        }
+    }
+
+    fn cochlea(&self, side: EarSide) -> &Cochlea {
+        match side {
+            EarSide::Left => &self.left,
+            EarSide::Right => &self.right,
+        }
+    }
+
+    fn update(&mut self, body: &Body, sounds: &[&SoundSource]) {
+        let head = body.head_pos();
+        let (sin, cos) = body.head_dir().sin_cos();
+        // right is clockwise from forward
+        let right = Point(sin, -cos);
+
+        let left_pos = head - self.offset * right;
+        let right_pos = head + self.offset * right;
+
+        let n = SoundSource::SAMPLES_PER_TICK;
+        let mut left_signal = vec![0.; n];
+        let mut right_signal = vec![0.; n];
+
+        for sound in sounds {
+            let samples = sound.samples();
+
+            if samples.is_empty() {
+                continue;
+            }
+
+            let side = side(head, right, sound.pos());
+
+            let left_gain = sound.gain(left_pos) * (1. - 0.5 * self.shadow * (1. + side));
+            let right_gain = sound.gain(right_pos) * (1. - 0.5 * self.shadow * (1. - side));
+
+            for (i, sample) in samples.iter().enumerate().take(n) {
+                left_signal[i] += left_gain * sample;
+                right_signal[i] += right_gain * sample;
+            }
+        }
+
+        self.left.update(&left_signal);
+        self.right.update(&right_signal);
+    }
+}
+
+// lateral position of the source in [-1, 1], positive on the right
+fn side(head: Point, right: Point, pos: Point) -> f32 {
+    let dist = head.dist(pos);
+
+    if dist > 0. {
+        (pos - head).dot(right) / dist
+    } else {
+        0.
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum EarSide {
+    Left,
+    Right,
+}
+
+struct Cochlea {
+    // AudioFilter is Send but not Sync
+    filters: Vec<Mutex<Box<dyn AudioFilter>>>,
+    half_saturation: f32,
+
+    energy: Vec<f32>,
+    level: f32,
+    onset: f32,
+
+    average: DecayValue,
+}
+
+impl Cochlea {
+    fn new(freqs: &[(f32, f32)], half_saturation: f32, half_life: Seconds) -> Self {
+        let filters = freqs.iter().map(|(min, max)| {
+            let mut filter = audio::bandpass_4(*min, *max);
+            filter.reset(Some(SoundSource::SAMPLE_RATE));
+
+            Mutex::new(filter)
+        }).collect();
+
+        Self {
+            filters,
+            half_saturation,
+
+            energy: vec![0.; freqs.len()],
+            level: 0.,
+            onset: 0.,
+
+            average: DecayValue::new(half_life).fill_decay(),
+        }
+    }
+
+    fn update(&mut self, signal: &[f32]) {
+        for (filter, energy) in self.filters.iter_mut().zip(self.energy.iter_mut()) {
+            let filter = filter.get_mut().unwrap();
+
+            let sum: f32 = signal.iter()
+                .map(|x| filter.next(*x).unwrap_or(0.))
+                .map(|y| y * y)
+                .sum();
+
+            *energy = (sum / signal.len().max(1) as f32).sqrt();
+        }
+
+        let max = self.energy.iter().fold(0., |a: f32, b| a.max(*b));
+
+        self.level = max / (max + self.half_saturation);
+        self.onset = (self.level - self.average.value()).max(0.);
+
+        self.average.update();
+        self.average.add(self.level);
+    }
+}
+
+pub struct EarPlugin {
+    freqs: Vec<(f32, f32)>,
+    offset: f32,
+    shadow: f32,
+    half_saturation: f32,
+    half_life: Seconds,
+}
+
+impl EarPlugin {
+    pub fn new() -> Self {
+        let mut plugin = Self {
+            freqs: Vec::new(),
+            offset: 0.1,
+            shadow: 0.5,
+            half_saturation: 0.1,
+            half_life: Seconds(1.),
+        };
+
+        plugin.channels(6, 100., 3200.);
+
+        plugin
+    }
+
+    ///
+    /// Logarithmically spaced filterbank of n channels between the
+    /// frequencies in Hz.
+    ///
+    pub fn channels(&mut self, n: usize, freq_min: f32, freq_max: f32) -> &mut Self {
+        assert!(n > 0);
+        assert!(0. < freq_min && freq_min < freq_max);
+        assert!(freq_max < 0.5 * SoundSource::SAMPLE_RATE as f32);
+
+        let ratio = (freq_max / freq_min).powf(1. / n as f32);
+
+        self.freqs = (0..n).map(|i| {
+            let min = freq_min * ratio.powi(i as i32);
+
+            (min, min * ratio)
+        }).collect();
+
+        self
+    }
+
+    ///
+    /// Distance of each ear from the head midline.
+    ///
+    pub fn offset(&mut self, offset: f32) -> &mut Self {
+        assert!(offset >= 0.);
+
+        self.offset = offset;
+
+        self
+    }
+
+    ///
+    /// Attenuation in [0, 1] of a sound fully on the opposite side.
+    ///
+    pub fn shadow(&mut self, shadow: f32) -> &mut Self {
+        assert!(0. <= shadow && shadow <= 1.);
+
+        self.shadow = shadow;
+
+        self
+    }
+
+    ///
+    /// Channel energy giving half of the maximum level.
+    ///
+    pub fn half_saturation(&mut self, half_saturation: f32) -> &mut Self {
+        assert!(half_saturation > 0.);
+
+        self.half_saturation = half_saturation;
+
+        self
+    }
+
+    ///
+    /// Half life of the level average for onset detection.
+    ///
+    pub fn half_life(&mut self, half_life: impl Into<Seconds>) -> &mut Self {
+        self.half_life = half_life.into();
+
+        self
+    }
+
+    fn create_ear(&self) -> Ear {
+        Ear {
+            freqs: self.freqs.clone(),
+            offset: self.offset,
+            shadow: self.shadow,
+
+            left: Cochlea::new(&self.freqs, self.half_saturation, self.half_life),
+            right: Cochlea::new(&self.freqs, self.half_saturation, self.half_life),
+        }
+    }
+}
+
+impl Plugin for EarPlugin {
+    fn build(&self, app: &mut App) {
+        assert!(app.contains_plugin::<BodyPlugin>(), "EarPlugin requires BodyPlugin");
+
+        app.insert_resource(self.create_ear());
+
+        app.system(Tick, update_ear);
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use audio::AudioSource;
+
+    use crate::{util::Point, world::SoundSource};
+
+    use super::{side, EarPlugin};
+
+    #[test]
+    fn ear_onset() {
+        let mut ear = EarPlugin::new().create_ear();
+
+        let tone: Vec<f32> = {
+            let mut sine = audio::sine(400.);
+            sine.reset(Some(SoundSource::SAMPLE_RATE));
+
+            (0..SoundSource::SAMPLES_PER_TICK).map(|_| 0.5 * sine.next().unwrap()).collect()
+        };
+
+        ear.left.update(&vec![0.; SoundSource::SAMPLES_PER_TICK]);
+        assert_eq!(0., ear.left.onset);
+
+        ear.left.update(&tone);
+        assert!(ear.left.onset > 0.5);
+
+        // a sustained tone is no longer an onset
+        for _ in 0..50 {
+            ear.left.update(&tone);
+        }
+        assert!(ear.left.level > 0.5);
+        assert!(ear.left.onset < 0.1);
+
+        // heading north, right is east
+        let right = Point(1., 0.);
+        assert_eq!(1., side(Point(0., 0.), right, Point(2., 0.)));
+        assert_eq!(-1., side(Point(0., 0.), right, Point(-2., 0.)));
+    }
+}
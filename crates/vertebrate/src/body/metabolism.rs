@@ -0,0 +1,326 @@
+use essay_ecs::{
+    app::{App, Plugin},
+    core::{Res, ResMut},
+    prelude::{Event, Events},
+};
+use mind_ecs::Tick;
+
+use crate::{
+    body::{BodyEatPlugin, BodyPlugin},
+    util::{Seconds, Ticks},
+    world::FoodKind,
+};
+
+use super::{Body, BodyEat};
+
+fn update_metabolism(
+    mut metabolism: ResMut<Metabolism>,
+    mut body: ResMut<Body>,
+    body_eat: Res<BodyEat>,
+    mut events: ResMut<Events<MetabolismEvent>>,
+) {
+    if let Some(event) = metabolism.update(body.get(), body_eat.get()) {
+        if let MetabolismEvent::Death = event {
+            body.set_dead();
+        }
+
+        events.send(event);
+    }
+}
+
+///
+/// Metabolism is the animal's energy store. Energy drains at a basal rate
+/// and with movement and turning, and refills from food digested through
+/// BodyEat's gut delay.
+///
+/// Energy is a fraction of the store's capacity in [0, 1]. Below the
+/// starvation threshold the animal is starving, and at zero it dies.
+///
+pub struct Metabolism {
+    energy: f32,
+
+    basal: f32,
+    move_cost: f32,
+    turn_cost: f32,
+    starve_threshold: f32,
+
+    food_energy: [f32; 6],
+
+    is_starving: bool,
+    is_dead: bool,
+
+    ticks: usize,
+    ticks_eating: usize,
+}
+
+impl Metabolism {
+    #[inline]
+    pub fn energy(&self) -> f32 {
+        self.energy
+    }
+
+    /// Energy deficit in [0, 1] for hunger
+    #[inline]
+    pub fn deficit(&self) -> f32 {
+        1. - self.energy
+    }
+
+    #[inline]
+    pub fn is_starving(&self) -> bool {
+        self.is_starving
+    }
+
+    #[inline]
+    pub fn is_dead(&self) -> bool {
+        self.is_dead
+    }
+
+    /// Survival time
+    #[inline]
+    pub fn age(&self) -> Seconds {
+        Ticks(self.ticks).into()
+    }
+
+    /// Fraction of life spent eating, for foraging efficiency
+    #[inline]
+    pub fn eat_fraction(&self) -> f32 {
+        self.ticks_eating as f32 / self.ticks.max(1) as f32
+    }
+
+    fn update(&mut self, body: &Body, body_eat: &BodyEat) -> Option<MetabolismEvent> {
+        if self.is_dead {
+            return None;
+        }
+
+        self.ticks += 1;
+
+        if body_eat.is_eating() {
+            self.ticks_eating += 1;
+        }
+
+        let cost = self.basal
+            + self.move_cost * body.speed().abs()
+            + self.turn_cost * body.turn().to_unit().abs();
+
+        let gain = self.food_energy[food_index(body_eat.digested())];
+
+        self.energy = (self.energy - cost + gain).clamp(0., 1.);
+
+        if self.energy <= 0. {
+            self.is_dead = true;
+            Some(MetabolismEvent::Death)
+        } else if ! self.is_starving && self.energy < self.starve_threshold {
+            self.is_starving = true;
+            Some(MetabolismEvent::Starving)
+        } else if self.is_starving && self.energy >= self.starve_threshold {
+            self.is_starving = false;
+            Some(MetabolismEvent::Recovered)
+        } else {
+            None
+        }
+    }
+}
+
+fn food_index(kind: FoodKind) -> usize {
+    match kind {
+        FoodKind::None => 0,
+        FoodKind::Poor => 1,
+        FoodKind::Plain => 2,
+        FoodKind::Sweet => 3,
+        FoodKind::Bitter => 4,
+        FoodKind::Sick => 5,
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Event)]
+pub enum MetabolismEvent {
+    Starving,
+    Recovered,
+    Death,
+}
+
+pub struct MetabolismPlugin {
+    is_enable: bool,
+
+    energy: f32,
+    basal: Seconds,
+    move_cost: f32,
+    turn_cost: f32,
+    starve_threshold: f32,
+
+    food_energy: [f32; 6],
+}
+
+impl MetabolismPlugin {
+    pub fn new() -> Self {
+        Self {
+            is_enable: true,
+
+            energy: 1.,
+            basal: Seconds(1200.),
+            move_cost: 0.5,
+            turn_cost: 0.1,
+            starve_threshold: 0.2,
+
+            // energy per bite, where plain food fills the store in 50s
+            food_energy: [0., 0.0005, 0.002, 0.004, 0., 0.],
+        }
+    }
+
+    pub fn enable(&mut self, is_enable: bool) -> &mut Self {
+        self.is_enable = is_enable;
+
+        self
+    }
+
+    #[inline]
+    pub fn is_enable(&self) -> bool {
+        self.is_enable
+    }
+
+    ///
+    /// Initial energy as a fraction of capacity.
+    ///
+    pub fn energy(&mut self, energy: f32) -> &mut Self {
+        assert!(0. < energy && energy <= 1.);
+
+        self.energy = energy;
+
+        self
+    }
+
+    ///
+    /// Time for basal metabolism alone to drain a full store.
+    ///
+    pub fn basal(&mut self, time: impl Into<Seconds>) -> &mut Self {
+        let time = time.into();
+        assert!(time.0 > 0.);
+
+        self.basal = time;
+
+        self
+    }
+
+    ///
+    /// Additional cost at full speed as a multiple of basal.
+    ///
+    pub fn move_cost(&mut self, cost: f32) -> &mut Self {
+        assert!(cost >= 0.);
+
+        self.move_cost = cost;
+
+        self
+    }
+
+    ///
+    /// Additional cost at a full unit turn per tick as a multiple of basal.
+    ///
+    pub fn turn_cost(&mut self, cost: f32) -> &mut Self {
+        assert!(cost >= 0.);
+
+        self.turn_cost = cost;
+
+        self
+    }
+
+    pub fn starve_threshold(&mut self, threshold: f32) -> &mut Self {
+        assert!(0. <= threshold && threshold < 1.);
+
+        self.starve_threshold = threshold;
+
+        self
+    }
+
+    ///
+    /// Energy gained for each digested bite of the food.
+    ///
+    pub fn food_energy(&mut self, kind: FoodKind, energy: f32) -> &mut Self {
+        self.food_energy[food_index(kind)] = energy;
+
+        self
+    }
+
+    fn create_metabolism(&self) -> Metabolism {
+        let ticks: Ticks = self.basal.into();
+        let basal = 1. / ticks.ticks().max(1) as f32;
+
+        // body speed is per tick, so full speed is 1 / TICKS_PER_SECOND
+        let speed_scale = Ticks::TICKS_PER_SECOND as f32;
+
+        Metabolism {
+            energy: self.energy,
+
+            basal,
+            move_cost: basal * self.move_cost * speed_scale,
+            turn_cost: basal * self.turn_cost,
+            starve_threshold: self.starve_threshold,
+
+            food_energy: self.food_energy,
+
+            is_starving: false,
+            is_dead: false,
+
+            ticks: 0,
+            ticks_eating: 0,
+        }
+    }
+}
+
+impl Plugin for MetabolismPlugin {
+    fn build(&self, app: &mut App) {
+        if ! self.is_enable {
+            return;
+        }
+
+        assert!(app.contains_plugin::<BodyPlugin>(), "MetabolismPlugin requires BodyPlugin");
+        assert!(app.contains_plugin::<BodyEatPlugin>(), "MetabolismPlugin requires BodyEatPlugin");
+
+        app.insert_resource(self.create_metabolism());
+
+        app.event::<MetabolismEvent>();
+
+        app.system(Tick, update_metabolism);
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use essay_ecs::{core::error::Result, prelude::Res};
+    use mind_ecs::MindApp;
+
+    use crate::{
+        body::{BodyEatPlugin, BodyPlugin},
+        util::Seconds,
+        world::WorldPlugin,
+    };
+
+    use super::{Metabolism, MetabolismPlugin};
+
+    #[test]
+    fn starve_and_die() -> Result<()> {
+        let mut app = MindApp::test();
+        app.plugin(WorldPlugin::new(7, 13));
+        app.plugin(BodyPlugin::new());
+        app.plugin(BodyEatPlugin::new());
+
+        let mut metabolism = MetabolismPlugin::new();
+        metabolism.energy(0.5).basal(Seconds(1.));
+        app.plugin(metabolism);
+
+        for _ in 0..4 {
+            app.tick()?;
+        }
+
+        assert!(app.eval(|x: Res<Metabolism>| x.is_starving())?);
+        assert!(! app.eval(|x: Res<Metabolism>| x.is_dead())?);
+
+        for _ in 0..4 {
+            app.tick()?;
+        }
+
+        assert!(app.eval(|x: Res<Metabolism>| x.is_dead())?);
+        assert_eq!(0., app.eval(|x: Res<Metabolism>| x.energy())?);
+
+        Ok(())
+    }
+}
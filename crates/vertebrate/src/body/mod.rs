@@ -1,7 +1,15 @@
 pub mod touch;
+mod animal;
 mod body;
 mod body_eat;
+mod ear;
+mod metabolism;
+mod thermosensor;
 
+pub use animal::Animal;
 pub use body::{Body, BodyPlugin, BodyAction};
 pub use body_eat::{BodyEat, BodyEatPlugin};
+pub use ear::{Ear, EarPlugin, EarSide};
+pub use metabolism::{Metabolism, MetabolismEvent, MetabolismPlugin};
+pub use thermosensor::{Thermosensor, ThermosensorPlugin};
 //pub use locomotion::{BodyLocomotion, Action, ActionFactory};
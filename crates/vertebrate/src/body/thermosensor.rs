@@ -0,0 +1,68 @@
+use essay_ecs::{
+    app::{App, Plugin},
+    core::{Res, ResMut},
+};
+use mind_ecs::Tick;
+
+use crate::world::Temperature;
+
+use super::{Body, BodyPlugin};
+
+fn update_thermosensor(
+    mut thermosensor: ResMut<Thermosensor>,
+    body: Res<Body>,
+    temperature: Res<Temperature>,
+) {
+    thermosensor.update(temperature.temperature(body.head_pos()));
+}
+
+///
+/// Thermosensor samples the water temperature at the head, like the
+/// AFD neurons in C. elegans or the trigeminal thermosensors in the
+/// larval zebrafish.
+///
+pub struct Thermosensor {
+    value: f32,
+    delta: f32,
+    is_init: bool,
+}
+
+impl Thermosensor {
+    /// Temperature in degrees C
+    #[inline]
+    pub fn value(&self) -> f32 {
+        self.value
+    }
+
+    /// Change since the last tick
+    #[inline]
+    pub fn delta(&self) -> f32 {
+        self.delta
+    }
+
+    fn update(&mut self, value: f32) {
+        if self.is_init {
+            self.delta = value - self.value;
+        }
+
+        self.value = value;
+        self.is_init = true;
+    }
+}
+
+pub struct ThermosensorPlugin;
+
+impl Plugin for ThermosensorPlugin {
+    fn build(&self, app: &mut App) {
+        assert!(app.contains_plugin::<BodyPlugin>(), "ThermosensorPlugin requires BodyPlugin");
+        assert!(app.contains_resource::<Temperature>(), "ThermosensorPlugin requires Temperature");
+
+        app.insert_resource(Thermosensor {
+            value: 0.,
+            delta: 0.,
+            is_init: false,
+        });
+
+        app.system(Tick, update_thermosensor);
+    }
+}
@@ -1,34 +1,40 @@
-use essay_ecs::{app::App, core::{Res, ResMut}};
+use essay_ecs::{app::{App, Startup}, core::{Commands, Res, ResMut}};
 use log::warn;
 use mind_ecs::Tick;
 
 use crate::{
-    body::{BodyEatPlugin, BodyPlugin}, 
+    body::{Animal, Body, BodyEatPlugin, BodyPlugin, EarPlugin, MetabolismPlugin, ThermosensorPlugin}, 
     hind_brain::{
         lateral_line::LateralLine2Plugin, r1_thigmotaxis::HindThigmotaxisPlugin, 
-        HindAvoidPlugin, HindEat, HindEatPlugin, HindMovePlugin
+        AcousticStartlePlugin, HindAvoidPlugin, HindEat, HindEatPlugin, HindMove, HindMovePlugin
     }, 
-    hippocampus::HippocampusPlugin, 
+    hippocampus::{FoodMapPlugin, HippocampusPlugin, PlaceCellsPlugin}, 
     hypothalamus::{
         Dwell, FoodZonePlugin, Forage, HypForagePlugin, HypMovePlugin, Motive, MotiveAvoidPlugin, MotiveEatPlugin, MotiveSleepPlugin
     }, 
     mid_brain::{
         pretectum::{lateral_line::PretectumLateralLinePlugin, ObstaclePretectumPlugin, PretectumTouchPlugin}, 
-        taxis::{klinotaxis::KlinotaxisPlugin, TaxisAvoidPlugin}, 
+        taxis::{
+            chemotaxis::ChemotaxisPlugin, klinotaxis::KlinotaxisPlugin, phototaxis::PhototaxisPlugin, 
+            rheotaxis::RheotaxisPlugin, thermotaxis::ThermotaxisPlugin, TaxisAvoidPlugin
+        }, 
         tectum::{TectumLateralLinePlugin, TectumLoomingPlugin, TectumOrientPlugin, TectumPlugin}, 
-        MidMovePlugin, MidSeekContextPlugin, MidSeekPlugin
+        DopaminePlugin, MidMovePlugin, MidSeekContextPlugin, MidSeekPlugin
     }, 
     olfactory::{
-        odor_place::OdorPlace, 
+        odor_conditioning::OdorConditioningPlugin,
+        odor_place::{OdorPatternPlugin, OdorPlace}, 
         olfactory_bulb::{OlfactoryBulb, OlfactoryBulbPlugin}, 
         OlfactoryCortexPlugin
     }, 
-    retina::RetinaPlugin, util::Seconds 
+    retina::{Retina, RetinaPlugin}, 
+    util::{Heading, Point, Seconds}
 };
 
 pub struct AnimalBuilder {
     body: BodyPlugin,
     body_eat: BodyEatPlugin,
+    metabolism: Option<MetabolismPlugin>,
 
     hind_avoid: HindAvoidPlugin,
     hind_eat: HindEatPlugin,
@@ -57,7 +63,22 @@ pub struct AnimalBuilder {
     is_mid_seek: bool,
     is_mid_klinotaxis: bool,
 
+    phototaxis: Option<PhototaxisPlugin>,
+    chemotaxis: Option<ChemotaxisPlugin>,
+    thermotaxis: Option<ThermotaxisPlugin>,
+    rheotaxis: Option<RheotaxisPlugin>,
+
+    ear: Option<EarPlugin>,
+
+    dopamine: Option<DopaminePlugin<OdorPlace>>,
+    odor_conditioning: Option<OdorConditioningPlugin>,
+
+    place_cells: Option<PlaceCellsPlugin>,
+    food_map: Option<FoodMapPlugin>,
+
     dwell: Option<DwellMode>,
+
+    animals: Vec<(Point, Heading)>,
 }
 
 impl AnimalBuilder {
@@ -65,6 +86,7 @@ impl AnimalBuilder {
         Self {
             body: BodyPlugin::new(),
             body_eat: BodyEatPlugin::new(),
+            metabolism: None,
 
             hind_avoid: HindAvoidPlugin::new(),
             hind_eat: HindEatPlugin::new(),
@@ -93,14 +115,45 @@ impl AnimalBuilder {
             is_mid_seek: false,
             is_mid_klinotaxis: false,
 
+            phototaxis: None,
+            chemotaxis: None,
+            thermotaxis: None,
+            rheotaxis: None,
+
+            ear: None,
+
+            dopamine: None,
+            odor_conditioning: None,
+
+            place_cells: None,
+            food_map: None,
+
             dwell: None,
+
+            animals: Vec::new(),
         }
     }
 
+    ///
+    /// Sets the primary animal's initial position.
+    ///
+    pub fn pos(&mut self, pos: impl Into<Point>) -> &mut Self {
+        self.body.set_pos(pos.into());
+
+        self
+    }
+
     pub fn body_eat(&mut self) -> &mut BodyEatPlugin {
         &mut self.body_eat
     }
 
+    ///
+    /// Enables the energy metabolism, so the animal can starve and die.
+    ///
+    pub fn metabolism(&mut self) -> &mut MetabolismPlugin {
+        self.metabolism.get_or_insert_with(MetabolismPlugin::new)
+    }
+
     pub fn lateral_line(&mut self) -> &mut LateralLine2Plugin {
         &mut self.lateral_line
     }
@@ -169,14 +222,101 @@ impl AnimalBuilder {
         }
     }
 
+    ///
+    /// Enables phototaxis toward light, or away for a dark preference.
+    ///
+    pub fn phototaxis(&mut self) -> &mut PhototaxisPlugin {
+        self.phototaxis.get_or_insert_with(PhototaxisPlugin::new)
+    }
+
+    ///
+    /// Enables chemotaxis along the olfactory bulb's odor gradients.
+    ///
+    pub fn chemotaxis(&mut self) -> &mut ChemotaxisPlugin {
+        self.chemotaxis.get_or_insert_with(ChemotaxisPlugin::new)
+    }
+
+    ///
+    /// Enables thermotaxis toward a preferred temperature, which requires
+    /// a world Temperature.
+    ///
+    pub fn thermotaxis(&mut self) -> &mut ThermotaxisPlugin {
+        self.thermotaxis.get_or_insert_with(ThermotaxisPlugin::new)
+    }
+
+    ///
+    /// Enables rheotaxis into a water current sensed by the lateral line,
+    /// which requires a world FlowField.
+    ///
+    pub fn rheotaxis(&mut self) -> &mut RheotaxisPlugin {
+        self.rheotaxis.get_or_insert_with(RheotaxisPlugin::new)
+    }
+
+    ///
+    /// Enables hearing, where sound onsets drive the acoustic startle.
+    ///
+    pub fn ear(&mut self) -> &mut EarPlugin {
+        self.ear.get_or_insert_with(EarPlugin::new)
+    }
+
+    ///
+    /// Enables dopamine reward learning of odor contexts, which requires
+    /// the tegmental seek.
+    ///
+    pub fn dopamine(&mut self) -> &mut DopaminePlugin<OdorPlace> {
+        self.dopamine.get_or_insert_with(DopaminePlugin::new)
+    }
+
+    ///
+    /// Enables Pavlovian conditioning of odors with the delayed outcome
+    /// of the food eaten with them.
+    ///
+    pub fn odor_conditioning(&mut self) -> &mut OdorConditioningPlugin {
+        self.odor_conditioning.get_or_insert_with(OdorConditioningPlugin::new)
+    }
+
+    ///
+    /// Enables hippocampal place cells, which path integrate the body's
+    /// movement into a location context.
+    ///
+    pub fn place_cells(&mut self) -> &mut PlaceCellsPlugin {
+        self.place_cells.get_or_insert_with(PlaceCellsPlugin::new)
+    }
+
+    ///
+    /// Enables the map of remembered food locations, which steers a
+    /// foraging animal back to where it ate. The map enables place cells.
+    ///
+    pub fn food_map(&mut self) -> &mut FoodMapPlugin {
+        self.place_cells();
+        self.food_map.get_or_insert_with(FoodMapPlugin::new)
+    }
+
     pub fn dwell(&mut self, dwell: DwellMode) {
         self.dwell = Some(dwell);
     }
 
+    ///
+    /// Spawns an extra body with a reflex hindbrain and its own sensors.
+    /// Only the builder's primary animal has the full brain, so an extra
+    /// body only seeks odors and roams.
+    /// 
+    pub fn spawn(&mut self, pos: impl Into<Point>, dir: Heading) -> &mut Self {
+        self.animals.push((pos.into(), dir));
+
+        self
+    }
+
     pub fn build(self, app: &mut App) {
+        self.spawn_animals(app);
+
         app.plugin(self.body);
         app.plugin(self.body_eat);
 
+        if let Some(metabolism) = self.metabolism {
+            app.plugin(metabolism);
+        }
+
         app.plugin(self.lateral_line);
         app.plugin(self.olfactory_bulb);
         app.plugin(self.retina);
@@ -186,6 +326,11 @@ impl AnimalBuilder {
         app.plugin(self.hind_move);
         app.plugin(self.hind_thigmotaxis);
 
+        if let Some(ear) = self.ear {
+            app.plugin(ear);
+            app.plugin(AcousticStartlePlugin);
+        }
+
         app.plugin(self.pretectum_obstacle);
         app.plugin(self.pretectum_touch);
         app.plugin(self.pretectum_lateral_line);
@@ -200,6 +345,10 @@ impl AnimalBuilder {
 
         app.plugin(self.olfactory_cortex);
 
+        if let Some(odor_conditioning) = self.odor_conditioning {
+            app.plugin(odor_conditioning);
+        }
+
         app.plugin(self.hyp_food_zone);
         
         if self.is_motive_eating {
@@ -221,10 +370,21 @@ impl AnimalBuilder {
 
             let is_context = true;
             if is_context {
+                // without a place map, the context is the odor pattern
+                if ! app.contains_resource::<OdorPlace>() {
+                    app.plugin(OdorPatternPlugin);
+                }
+
+                if let Some(dopamine) = self.dopamine {
+                    app.plugin(dopamine);
+                }
+
                 app.plugin(MidSeekContextPlugin::<OlfactoryBulb, OdorPlace, Forage>::new().decay(Seconds(180.)));
             } else {
                 app.plugin(MidSeekPlugin::<OlfactoryBulb, Forage>::new());
             }
+        } else if self.dopamine.is_some() {
+            warn!("Dopamine requires tegmentum seek");
         }
 
         app.plugin(TaxisAvoidPlugin::new());
@@ -236,6 +396,27 @@ impl AnimalBuilder {
 
             app.plugin(KlinotaxisPlugin::<OlfactoryBulb, Forage>::new());
         }
+
+        if let Some(phototaxis) = self.phototaxis {
+            if phototaxis.is_enable() {
+                app.plugin(phototaxis);
+            }
+        }
+
+        if let Some(chemotaxis) = self.chemotaxis {
+            if chemotaxis.is_enable() {
+                app.plugin(chemotaxis);
+            }
+        }
+
+        if let Some(thermotaxis) = self.thermotaxis {
+            app.plugin(ThermosensorPlugin);
+            app.plugin(thermotaxis);
+        }
+
+        if let Some(rheotaxis) = self.rheotaxis {
+            app.plugin(rheotaxis);
+        }
             // app.plugin(LateralLinePlugin);
 
         //app.plugin(MidMotorPlugin);
@@ -258,8 +439,71 @@ impl AnimalBuilder {
         ehc.digits(4).radix(4).seq(2);
         app.plugin(ehc);
 
+        if let Some(place_cells) = self.place_cells {
+            app.plugin(place_cells);
+        }
+
+        if let Some(food_map) = self.food_map {
+            if app.contains_plugin::<HypForagePlugin>() {
+                app.plugin(food_map);
+            } else {
+                warn!("Food map requires foraging");
+            }
+        }
+
         app.plugin(MotiveAvoidPlugin);
     }
+
+    fn spawn_animals(&self, app: &mut App) {
+        if self.animals.len() == 0 {
+            return;
+        }
+
+        let mut animals = Vec::<AnimalItem>::new();
+
+        for (i, (pos, dir)) in self.animals.iter().enumerate() {
+            // the primary animal is 0
+            let id = i + 1;
+            let prefix = format!("animal.{}.", id);
+
+            let mut body = self.body.create_body(app, *pos, &format!("{}body", prefix));
+            body.set_dir(*dir);
+
+            let retina = if self.retina.is_enable() {
+                Some(self.retina.create_retina())
+            } else {
+                None
+            };
+
+            animals.push(AnimalItem {
+                animal: Animal::new(id),
+                body,
+                hind_move: self.hind_move.create_hind_move(app, &prefix),
+                olfactory_bulb: self.olfactory_bulb.create_bulb(),
+                retina,
+            });
+        }
+
+        app.system(Startup, move |mut cmd: Commands| {
+            for item in animals.drain(..) {
+                let AnimalItem { animal, body, hind_move, olfactory_bulb, retina } = item;
+
+                if let Some(retina) = retina {
+                    cmd.spawn((animal, body, hind_move, olfactory_bulb, retina));
+                } else {
+                    cmd.spawn((animal, body, hind_move, olfactory_bulb));
+                }
+            }
+        });
+    }
+}
+
+struct AnimalItem {
+    animal: Animal,
+    body: Body,
+    hind_move: HindMove,
+    olfactory_bulb: OlfactoryBulb,
+    retina: Option<Retina>,
 }
 
 #[derive(Clone, Copy, Debug, PartialEq)]
@@ -308,3 +552,70 @@ fn dwell_eat(
         dwell.set_max(1.);
     }
 }
+
+#[cfg(test)]
+mod test {
+    use essay_ecs::core::{error::Result, Query};
+    use mind_ecs::MindApp;
+
+    use crate::{
+        body::{Animal, Body, BodyEatPlugin, BodyPlugin}, 
+        hind_brain::{HindEatPlugin, HindMovePlugin}, 
+        hypothalamus::MotiveSleepPlugin, 
+        olfactory::olfactory_bulb::OlfactoryBulb, 
+        util::{Heading, Point}, 
+        world::{OdorKind, OdorPlugin, WorldPlugin}
+    };
+
+    use super::AnimalBuilder;
+
+    #[test]
+    fn spawn_animals_sense_and_move() -> Result<()> {
+        let mut app = MindApp::test();
+        app.plugin(WorldPlugin::new(15, 15));
+
+        let mut odor = OdorPlugin::new();
+        odor.odor(7, 5, OdorKind::FoodA);
+        app.plugin(odor);
+
+        let mut builder = AnimalBuilder::new();
+        builder.olfactory().odor(OdorKind::FoodA);
+        builder.retina().enable(false);
+        builder.spawn((5.5, 5.5), Heading::Unit(0.));
+        builder.spawn((12.5, 12.5), Heading::Unit(0.5));
+
+        builder.spawn_animals(&mut app);
+        app.plugin(BodyPlugin::new());
+        app.plugin(BodyEatPlugin::new());
+        app.plugin(HindEatPlugin::new());
+        app.plugin(MotiveSleepPlugin::new());
+        app.plugin(HindMovePlugin);
+        app.plugin(builder.olfactory_bulb);
+
+        for _ in 0..10 {
+            app.tick()?;
+        }
+
+        let mut animals = app.eval(|q: Query<(&Animal, &Body, &OlfactoryBulb)>| {
+            q.iter().map(|(animal, body, bulb)| {
+                (animal.id(), body.pos(), bulb.food_odor())
+            }).collect::<Vec<_>>()
+        })?;
+        animals.sort_by_key(|item| item.0);
+
+        assert_eq!(2, animals.len());
+
+        // only the animal near the odor senses it
+        let (_, pos_a, food_a) = animals[0];
+        assert_eq!(Some(OdorKind::FoodA), food_a);
+
+        let (_, pos_b, food_b) = animals[1];
+        assert_eq!(None, food_b);
+
+        // each animal moves its own body
+        assert_ne!(Point(5.5, 5.5), pos_a);
+        assert_ne!(Point(12.5, 12.5), pos_b);
+
+        Ok(())
+    }
+}
@@ -1,3 +1,5 @@
 mod animal;
+mod scenario;
 
-pub use animal::AnimalBuilder;
\ No newline at end of file
+pub use animal::AnimalBuilder;
+pub use scenario::Scenario;
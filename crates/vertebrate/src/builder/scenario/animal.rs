@@ -0,0 +1,431 @@
+use serde::Deserialize;
+
+use crate::{
+    builder::AnimalBuilder,
+    hind_brain::{r1_thigmotaxis::ThigmotaxisStrategy, EatStrategy},
+    mid_brain::taxis::{TaxisPriority, TaxisSign},
+    olfactory::olfactory_bulb::OlfactoryStrategy,
+    retina::RetinaStrategy,
+    util::{Angle, Heading, Point, Seconds},
+};
+
+use super::food::odor_kind;
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub(super) struct AnimalSpec {
+    pos: Option<[f32; 2]>,
+
+    #[serde(default)]
+    olfactory: Vec<String>,
+    olfactory_strategy: Option<String>,
+
+    gut_delay_s: Option<f32>,
+    hind_eat: Option<String>,
+
+    seek: Option<bool>,
+    klinotaxis: Option<bool>,
+    forage: Option<bool>,
+
+    #[serde(default)]
+    metabolism: Option<MetabolismSpec>,
+
+    #[serde(default)]
+    retina: Option<RetinaSpec>,
+
+    #[serde(default)]
+    pretectum: Option<PretectumSpec>,
+
+    #[serde(default)]
+    tectum: Option<TectumSpec>,
+
+    #[serde(default)]
+    thigmotaxis: Option<ThigmotaxisSpec>,
+
+    #[serde(default)]
+    phototaxis: Option<TaxisSpec>,
+
+    #[serde(default)]
+    chemotaxis: Option<TaxisSpec>,
+
+    #[serde(default)]
+    spawn: Vec<SpawnSpec>,
+}
+
+impl AnimalSpec {
+    pub(super) fn check(&self) -> Result<(), String> {
+        for odor in &self.olfactory {
+            odor_kind(odor)?;
+        }
+
+        if let Some(strategy) = &self.olfactory_strategy {
+            olfactory_strategy(strategy)?;
+        }
+
+        if let Some(hind_eat) = &self.hind_eat {
+            eat_strategy(hind_eat)?;
+        }
+
+        if let Some(metabolism) = &self.metabolism {
+            metabolism.check()?;
+        }
+
+        if let Some(retina) = &self.retina {
+            if let Some(strategy) = &retina.strategy {
+                retina_strategy(strategy)?;
+            }
+        }
+
+        if let Some(thigmotaxis) = &self.thigmotaxis {
+            if let Some(strategy) = &thigmotaxis.strategy {
+                thigmotaxis_strategy(strategy)?;
+            }
+        }
+
+        if let Some(phototaxis) = &self.phototaxis {
+            if phototaxis.odors.len() > 0 {
+                return Err("phototaxis doesn't use odors".to_string());
+            }
+
+            phototaxis.check()?;
+        }
+
+        if let Some(chemotaxis) = &self.chemotaxis {
+            chemotaxis.check()?;
+        }
+
+        Ok(())
+    }
+
+    pub(super) fn configure(&self, animal: &mut AnimalBuilder) {
+        if let Some(pos) = self.pos {
+            animal.pos(Point(pos[0], pos[1]));
+        }
+
+        for odor in &self.olfactory {
+            animal.olfactory().odor(odor_kind(odor).unwrap());
+        }
+
+        if let Some(strategy) = &self.olfactory_strategy {
+            animal.olfactory().strategy(olfactory_strategy(strategy).unwrap());
+        }
+
+        if let Some(gut_delay) = self.gut_delay_s {
+            animal.body_eat().gut_delay(Seconds(gut_delay));
+        }
+
+        if let Some(hind_eat) = &self.hind_eat {
+            animal.hind_eat().strategy(eat_strategy(hind_eat).unwrap());
+        }
+
+        if let Some(seek) = self.seek {
+            animal.seek().seek(seek);
+        }
+
+        if let Some(klinotaxis) = self.klinotaxis {
+            animal.seek().klinotaxis(klinotaxis);
+        }
+
+        if let Some(forage) = self.forage {
+            animal.hyp_forage().enable(forage);
+        }
+
+        if let Some(metabolism) = &self.metabolism {
+            metabolism.configure(animal);
+        }
+
+        if let Some(retina) = &self.retina {
+            retina.configure(animal);
+        }
+
+        if let Some(pretectum) = &self.pretectum {
+            if let Some(obstacle) = pretectum.obstacle {
+                animal.pretectum_obstacle().enable(obstacle);
+            }
+
+            if let Some(touch) = pretectum.touch {
+                animal.pretectum_touch().enable(touch);
+            }
+
+            if let Some(lateral_line) = pretectum.lateral_line {
+                animal.pretectum_lateral_line().enable(lateral_line);
+            }
+        }
+
+        if let Some(tectum) = &self.tectum {
+            if let Some(looming) = tectum.looming {
+                animal.tectum_looming().enable(looming);
+            }
+
+            if let Some(orient) = tectum.orient {
+                animal.tectum_orient().enable(orient);
+            }
+        }
+
+        if let Some(thigmotaxis) = &self.thigmotaxis {
+            if let Some(enable) = thigmotaxis.enable {
+                animal.hind_thigmotaxis().enable(enable);
+            }
+
+            if let Some(strategy) = &thigmotaxis.strategy {
+                animal.hind_thigmotaxis().strategy(thigmotaxis_strategy(strategy).unwrap());
+            }
+        }
+
+        if let Some(phototaxis) = &self.phototaxis {
+            let plugin = animal.phototaxis();
+
+            if let Some(enable) = phototaxis.enable {
+                plugin.enable(enable);
+            }
+
+            if let Some(sign) = &phototaxis.sign {
+                plugin.sign(taxis_sign(sign).unwrap());
+            }
+
+            if let Some(gain) = phototaxis.gain {
+                plugin.gain(gain);
+            }
+
+            if let Some(priority) = &phototaxis.priority {
+                plugin.priority(taxis_priority(priority).unwrap());
+            }
+        }
+
+        if let Some(chemotaxis) = &self.chemotaxis {
+            let plugin = animal.chemotaxis();
+
+            if let Some(enable) = chemotaxis.enable {
+                plugin.enable(enable);
+            }
+
+            for odor in &chemotaxis.odors {
+                plugin.odor(odor_kind(odor).unwrap());
+            }
+
+            if let Some(sign) = &chemotaxis.sign {
+                plugin.sign(taxis_sign(sign).unwrap());
+            }
+
+            if let Some(gain) = chemotaxis.gain {
+                plugin.gain(gain);
+            }
+
+            if let Some(priority) = &chemotaxis.priority {
+                plugin.priority(taxis_priority(priority).unwrap());
+            }
+        }
+
+        for spawn in &self.spawn {
+            animal.spawn(Point(spawn.pos[0], spawn.pos[1]), Heading::Unit(spawn.dir));
+        }
+    }
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct MetabolismSpec {
+    energy: Option<f32>,
+    basal_s: Option<f32>,
+    move_cost: Option<f32>,
+    turn_cost: Option<f32>,
+    starve_threshold: Option<f32>,
+}
+
+impl MetabolismSpec {
+    fn check(&self) -> Result<(), String> {
+        let in_range = |name: &str, value: Option<f32>, min: f32, max: f32| {
+            match value {
+                Some(v) if v < min || max < v => {
+                    Err(format!("metabolism {} {} is outside [{}, {}]", name, v, min, max))
+                }
+                _ => Ok(()),
+            }
+        };
+
+        in_range("energy", self.energy, f32::MIN_POSITIVE, 1.)?;
+        in_range("basal_s", self.basal_s, f32::MIN_POSITIVE, f32::MAX)?;
+        in_range("move_cost", self.move_cost, 0., f32::MAX)?;
+        in_range("turn_cost", self.turn_cost, 0., f32::MAX)?;
+        in_range("starve_threshold", self.starve_threshold, 0., 0.999)?;
+
+        Ok(())
+    }
+
+    fn configure(&self, animal: &mut AnimalBuilder) {
+        let metabolism = animal.metabolism();
+
+        if let Some(energy) = self.energy {
+            metabolism.energy(energy);
+        }
+
+        if let Some(basal) = self.basal_s {
+            metabolism.basal(Seconds(basal));
+        }
+
+        if let Some(cost) = self.move_cost {
+            metabolism.move_cost(cost);
+        }
+
+        if let Some(cost) = self.turn_cost {
+            metabolism.turn_cost(cost);
+        }
+
+        if let Some(threshold) = self.starve_threshold {
+            metabolism.starve_threshold(threshold);
+        }
+    }
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct RetinaSpec {
+    enable: Option<bool>,
+    size: Option<u32>,
+    fov_deg: Option<f32>,
+    eye_angle_deg: Option<f32>,
+    strategy: Option<String>,
+}
+
+impl RetinaSpec {
+    fn configure(&self, animal: &mut AnimalBuilder) {
+        let retina = animal.retina();
+
+        if let Some(enable) = self.enable {
+            retina.enable(enable);
+        }
+
+        if let Some(size) = self.size {
+            retina.size(size);
+        }
+
+        if let Some(fov) = self.fov_deg {
+            retina.fov(Angle::Deg(fov));
+        }
+
+        if let Some(eye_angle) = self.eye_angle_deg {
+            retina.eye_angle(Angle::Deg(eye_angle));
+        }
+
+        if let Some(strategy) = &self.strategy {
+            retina.strategy(retina_strategy(strategy).unwrap());
+        }
+    }
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct PretectumSpec {
+    obstacle: Option<bool>,
+    touch: Option<bool>,
+    lateral_line: Option<bool>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct TectumSpec {
+    looming: Option<bool>,
+    orient: Option<bool>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct ThigmotaxisSpec {
+    enable: Option<bool>,
+    strategy: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct TaxisSpec {
+    enable: Option<bool>,
+    sign: Option<String>,
+    gain: Option<f32>,
+    priority: Option<String>,
+
+    #[serde(default)]
+    odors: Vec<String>,
+}
+
+impl TaxisSpec {
+    fn check(&self) -> Result<(), String> {
+        if let Some(sign) = &self.sign {
+            taxis_sign(sign)?;
+        }
+
+        if let Some(gain) = self.gain {
+            if gain <= 0. {
+                return Err(format!("taxis gain {} must be positive", gain));
+            }
+        }
+
+        if let Some(priority) = &self.priority {
+            taxis_priority(priority)?;
+        }
+
+        for odor in &self.odors {
+            odor_kind(odor)?;
+        }
+
+        Ok(())
+    }
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct SpawnSpec {
+    pos: [f32; 2],
+
+    #[serde(default)]
+    dir: f32,
+}
+
+fn eat_strategy(name: &str) -> Result<EatStrategy, String> {
+    match name {
+        "filter_feed" => Ok(EatStrategy::FilterFeed),
+        "mammal" => Ok(EatStrategy::Mammal),
+        _ => Err(format!("unknown hind_eat strategy '{}'", name)),
+    }
+}
+
+fn olfactory_strategy(name: &str) -> Result<OlfactoryStrategy, String> {
+    match name {
+        "exact" => Ok(OlfactoryStrategy::Exact),
+        "tropotaxis" => Ok(OlfactoryStrategy::Tropotaxis),
+        "klinotaxis" => Ok(OlfactoryStrategy::Klinotaxis),
+        _ => Err(format!("unknown olfactory strategy '{}'", name)),
+    }
+}
+
+fn retina_strategy(name: &str) -> Result<RetinaStrategy, String> {
+    match name {
+        "wgpu" => Ok(RetinaStrategy::Wgpu),
+        "ray_cast" => Ok(RetinaStrategy::RayCast),
+        _ => Err(format!("unknown retina strategy '{}'", name)),
+    }
+}
+
+fn thigmotaxis_strategy(name: &str) -> Result<ThigmotaxisStrategy, String> {
+    match name {
+        "direct" => Ok(ThigmotaxisStrategy::Direct),
+        "artr" => Ok(ThigmotaxisStrategy::Artr),
+        _ => Err(format!("unknown thigmotaxis strategy '{}'", name)),
+    }
+}
+
+fn taxis_sign(name: &str) -> Result<TaxisSign, String> {
+    match name {
+        "toward" => Ok(TaxisSign::Toward),
+        "away" => Ok(TaxisSign::Away),
+        _ => Err(format!("unknown taxis sign '{}'", name)),
+    }
+}
+
+fn taxis_priority(name: &str) -> Result<TaxisPriority, String> {
+    match name {
+        "roam" => Ok(TaxisPriority::Roam),
+        "seek" => Ok(TaxisPriority::Seek),
+        "avoid" => Ok(TaxisPriority::Avoid),
+        _ => Err(format!("unknown taxis priority '{}'", name)),
+    }
+}
@@ -0,0 +1,282 @@
+use serde::Deserialize;
+
+use crate::{
+    util::Seconds,
+    world::{FoodKind, FoodPlugin, Odor, OdorFieldPlugin, OdorKind, OdorPlugin},
+};
+
+use super::world::in_world;
+
+#[derive(Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub(super) struct FoodSpec {
+    #[serde(default)]
+    items: Vec<FoodItemSpec>,
+
+    #[serde(default)]
+    gen: Option<FoodGenSpec>,
+
+    #[serde(default)]
+    base: Option<String>,
+}
+
+impl FoodSpec {
+    pub(super) fn check(&self, extent: (usize, usize)) -> Result<(), String> {
+        for item in &self.items {
+            in_world("food", item.pos, [1, 1], extent)?;
+            food_kind(&item.kind)?;
+
+            if let Some(odor) = &item.odor {
+                odor_kind(odor)?;
+            }
+        }
+
+        if let Some(gen) = &self.gen {
+            food_kind(&gen.kind)?;
+
+            if let Some(odor) = &gen.odor {
+                odor_kind(odor)?;
+            }
+        }
+
+        if let Some(base) = &self.base {
+            food_kind(base)?;
+        }
+
+        Ok(())
+    }
+
+    pub(super) fn food_plugin(&self) -> FoodPlugin {
+        let mut food = FoodPlugin::new();
+
+        for item in &self.items {
+            food.food(item.pos[0], item.pos[1])
+                .kind(food_kind(&item.kind).unwrap());
+
+            if let Some(radius) = item.radius {
+                food.radius(radius);
+            }
+
+            if let Some(probability) = item.probability {
+                food.probability(probability);
+            }
+
+            if let Some(value) = item.value {
+                food.value(value);
+            }
+
+            if let Some(odor) = &item.odor {
+                let kind = odor_kind(odor).unwrap();
+
+                match item.odor_r {
+                    Some(r) => { food.odor_r(r, kind); }
+                    None => { food.odor(kind); }
+                }
+            }
+        }
+
+        if let Some(gen) = &self.gen {
+            food.gen_count(gen.count)
+                .gen_kind(food_kind(&gen.kind).unwrap());
+
+            if let Some(radius) = gen.radius {
+                food.gen_radius(radius);
+            }
+
+            if let Some(value_s) = gen.value_s {
+                food.gen_value(Seconds(value_s));
+            }
+
+            if let Some(odor) = &gen.odor {
+                let kind = odor_kind(odor).unwrap();
+
+                match gen.odor_r {
+                    Some(r) => { food.gen_odor_r(r, kind); }
+                    None => { food.gen_odor(kind); }
+                }
+            }
+        }
+
+        if let Some(base) = &self.base {
+            food.base_food(food_kind(base).unwrap());
+        }
+
+        food
+    }
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct FoodItemSpec {
+    pos: [usize; 2],
+
+    #[serde(default = "default_food_kind")]
+    kind: String,
+
+    radius: Option<f32>,
+    probability: Option<f32>,
+    value: Option<f32>,
+
+    odor: Option<String>,
+    odor_r: Option<usize>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct FoodGenSpec {
+    count: usize,
+
+    #[serde(default = "default_food_kind")]
+    kind: String,
+
+    radius: Option<f32>,
+    value_s: Option<f32>,
+
+    odor: Option<String>,
+    odor_r: Option<usize>,
+}
+
+fn default_food_kind() -> String {
+    String::from("plain")
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub(super) struct OdorSpec {
+    pos: [usize; 2],
+    r: Option<usize>,
+    kind: String,
+
+    // mixed with the primary kind at weight 1
+    #[serde(default)]
+    mix: Vec<OdorMixSpec>,
+}
+
+impl OdorSpec {
+    pub(super) fn check(&self, extent: (usize, usize)) -> Result<(), String> {
+        in_world("odor", self.pos, [1, 1], extent)?;
+        odor_kind(&self.kind)?;
+
+        for item in &self.mix {
+            odor_kind(&item.kind)?;
+
+            if item.weight <= 0. {
+                return Err(format!("odor mix weight {} must be positive", item.weight));
+            }
+        }
+
+        Ok(())
+    }
+}
+
+pub(super) fn odor_plugin(specs: &[OdorSpec]) -> OdorPlugin {
+    let mut odors = OdorPlugin::new();
+
+    for odor in specs {
+        let kind = odor_kind(&odor.kind).unwrap();
+
+        if odor.mix.len() > 0 {
+            let mut mixture = vec![(kind, 1.)];
+
+            for item in &odor.mix {
+                mixture.push((odor_kind(&item.kind).unwrap(), item.weight));
+            }
+
+            let r = odor.r.unwrap_or(Odor::<OdorKind>::RADIUS as usize);
+
+            odors.mixture(odor.pos[0], odor.pos[1], r, &mixture);
+            continue;
+        }
+
+        match odor.r {
+            Some(r) => { odors.odor_r(odor.pos[0], odor.pos[1], r, kind); }
+            None => { odors.odor(odor.pos[0], odor.pos[1], kind); }
+        }
+    }
+
+    odors
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct OdorMixSpec {
+    kind: String,
+    weight: f32,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub(super) struct OdorFieldSpec {
+    scale: Option<usize>,
+    diffusion: Option<f32>,
+    half_life_s: Option<f32>,
+    rate: Option<f32>,
+    saturation: Option<f32>,
+    flow: Option<[f32; 2]>,
+}
+
+impl OdorFieldSpec {
+    pub(super) fn plugin(&self) -> OdorFieldPlugin {
+        let mut plugin = OdorFieldPlugin::new();
+
+        if let Some(scale) = self.scale {
+            plugin = plugin.scale(scale);
+        }
+
+        if let Some(diffusion) = self.diffusion {
+            plugin = plugin.diffusion(diffusion);
+        }
+
+        if let Some(half_life) = self.half_life_s {
+            plugin = plugin.half_life(Seconds(half_life));
+        }
+
+        if let Some(rate) = self.rate {
+            plugin = plugin.rate(rate);
+        }
+
+        if let Some(saturation) = self.saturation {
+            plugin = plugin.saturation(saturation);
+        }
+
+        if let Some(flow) = self.flow {
+            plugin = plugin.flow(flow[0], flow[1]);
+        }
+
+        plugin
+    }
+
+    pub(super) fn check(&self) -> Result<(), String> {
+        match (self.scale, self.diffusion, self.rate, self.saturation) {
+            (Some(0), _, _, _) => Err(String::from("odor_field scale must be positive")),
+            (_, Some(d), _, _) if d < 0. => Err(String::from("odor_field diffusion must be non-negative")),
+            (_, _, Some(r), _) if r < 0. => Err(String::from("odor_field rate must be non-negative")),
+            (_, _, _, Some(s)) if s <= 0. => Err(String::from("odor_field saturation must be positive")),
+            _ => Ok(()),
+        }
+    }
+}
+
+fn food_kind(name: &str) -> Result<FoodKind, String> {
+    match name {
+        "none" => Ok(FoodKind::None),
+        "poor" => Ok(FoodKind::Poor),
+        "plain" => Ok(FoodKind::Plain),
+        "sweet" => Ok(FoodKind::Sweet),
+        "bitter" => Ok(FoodKind::Bitter),
+        "sick" => Ok(FoodKind::Sick),
+        _ => Err(format!("unknown food kind '{}'", name)),
+    }
+}
+
+pub(super) fn odor_kind(name: &str) -> Result<OdorKind, String> {
+    match name {
+        "none" => Ok(OdorKind::None),
+        "food_a" => Ok(OdorKind::FoodA),
+        "food_b" => Ok(OdorKind::FoodB),
+        "avoid_a" => Ok(OdorKind::AvoidA),
+        "avoid_b" => Ok(OdorKind::AvoidB),
+        "other_a" => Ok(OdorKind::OtherA),
+        _ => Err(format!("unknown odor kind '{}'", name)),
+    }
+}
@@ -0,0 +1,322 @@
+use std::{collections::HashMap, fs, path::Path};
+
+use essay_ecs::app::App;
+use serde::Deserialize;
+
+use crate::world::{WorldHexPlugin, WorldHexTrait, WorldMap, WorldPlugin};
+
+use super::AnimalBuilder;
+
+mod animal;
+mod food;
+mod world;
+
+use animal::AnimalSpec;
+use food::{odor_plugin, FoodSpec, OdorFieldSpec, OdorSpec};
+use world::{floor_type, PlaceSpec, WorldSpec};
+
+///
+/// Scenario is a declarative arena and animal configuration loaded from
+/// a TOML file, so experiments can change the world without a recompile.
+///
+/// ```toml
+/// [world]
+/// width = 21
+/// height = 13
+/// walls = [{ pos = [6, 0], extent = [1, 6] }]
+/// obstacles = [{ circle = [15.0, 9.0], r = 1.5 }]
+///
+/// [food]
+/// items = [{ pos = [5, 5], kind = "sweet" }]
+/// gen = { count = 2, radius = 3.0, value_s = 240.0, kind = "plain" }
+///
+/// [[odor]]
+/// pos = [15, 5]
+/// r = 4
+/// kind = "food_a"
+///
+/// [animal]
+/// olfactory = ["food_a", "food_b"]
+/// retina = { fov_deg = 150.0, eye_angle_deg = 45.0 }
+/// ```
+///
+#[derive(Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct Scenario {
+    world: WorldSpec,
+
+    #[serde(default)]
+    food: Option<FoodSpec>,
+
+    #[serde(default)]
+    odor: Vec<OdorSpec>,
+
+    #[serde(default)]
+    odor_field: Option<OdorFieldSpec>,
+
+    #[serde(default)]
+    place: Vec<PlaceSpec>,
+
+    #[serde(default)]
+    animal: Option<AnimalSpec>,
+
+    #[serde(skip)]
+    map: Option<WorldMap>,
+
+    #[serde(skip)]
+    place_map: Option<WorldMap>,
+}
+
+impl Scenario {
+    pub fn load(path: impl AsRef<Path>) -> Result<Self, String> {
+        let path = path.as_ref();
+
+        let text = fs::read_to_string(path)
+            .map_err(|err| format!("can't read scenario {:?}: {}", path, err))?;
+
+        let dir = path.parent().unwrap_or(Path::new("."));
+
+        Self::parse_in(&text, dir)
+            .map_err(|err| format!("scenario {:?}: {}", path, err))
+    }
+
+    pub fn parse(text: &str) -> Result<Self, String> {
+        Self::parse_in(text, Path::new("."))
+    }
+
+    ///
+    /// Parses the scenario, loading map files relative to dir.
+    ///
+    fn parse_in(text: &str, dir: &Path) -> Result<Self, String> {
+        let mut scenario: Scenario = toml::from_str(text)
+            .map_err(|err| err.to_string())?;
+
+        if let Some(path) = &scenario.world.map {
+            scenario.map = Some(WorldMap::load(dir.join(path))?);
+        }
+
+        if let Some(place_map) = &scenario.world.place_map {
+            scenario.place_map = Some(WorldMap::load(dir.join(&place_map.path))?);
+        }
+
+        scenario.check()?;
+
+        Ok(scenario)
+    }
+
+    #[inline]
+    pub fn extent(&self) -> (usize, usize) {
+        match &self.map {
+            Some(map) => map.extent(),
+            None => (self.world.width, self.world.height),
+        }
+    }
+
+    ///
+    /// Adds the WorldPlugin, FoodPlugin, OdorPlugin and OdorFieldPlugin
+    ///
+    pub fn build_world(&self, app: &mut App) {
+        app.plugin(self.world_plugin());
+
+        if let Some(food) = &self.food {
+            app.plugin(food.food_plugin());
+        }
+
+        if self.odor.len() > 0 {
+            app.plugin(odor_plugin(&self.odor));
+        }
+
+        if let Some(field) = &self.odor_field {
+            app.plugin(field.plugin());
+        }
+    }
+
+    ///
+    /// Adds a WorldHexPlugin with the scenario's place circles. Place
+    /// kinds are application types, so the caller maps their names.
+    ///
+    pub fn build_places<K>(
+        &self,
+        app: &mut App,
+        kind: impl Fn(&str) -> Option<K>
+    ) -> Result<(), String>
+    where
+        K: WorldHexTrait + Default
+    {
+        let (width, height) = self.extent();
+
+        let mut places = WorldHexPlugin::<K>::new(width, height);
+
+        if let (Some(map), Some(spec)) = (&self.place_map, &self.world.place_map) {
+            let mut kinds = HashMap::new();
+
+            for (symbol, name) in &spec.kinds {
+                let value = kind(name)
+                    .ok_or_else(|| format!("unknown place kind '{}'", name))?;
+
+                kinds.insert(symbol.chars().next().unwrap(), value);
+            }
+
+            places.fill_map(map, |symbol| kinds.get(&symbol).cloned());
+        }
+
+        for place in &self.place {
+            let value = kind(&place.kind)
+                .ok_or_else(|| format!("unknown place kind '{}'", place.kind))?;
+
+            places.circle((place.pos[0], place.pos[1]), place.r, value);
+        }
+
+        app.plugin(places);
+
+        Ok(())
+    }
+
+    pub fn place_kinds(&self) -> Vec<&str> {
+        self.place.iter().map(|p| p.kind.as_str()).collect()
+    }
+
+    ///
+    /// Configures an AnimalBuilder from the scenario's [animal] section.
+    ///
+    pub fn animal(&self) -> AnimalBuilder {
+        let mut animal = AnimalBuilder::new();
+
+        if let Some(spec) = &self.animal {
+            spec.configure(&mut animal);
+        }
+
+        animal
+    }
+
+    fn world_plugin(&self) -> WorldPlugin {
+        let mut world = match &self.map {
+            Some(map) => WorldPlugin::from_map(map),
+            None => WorldPlugin::new(self.world.width, self.world.height),
+        };
+
+        for wall in &self.world.walls {
+            world = world.wall((wall.pos[0], wall.pos[1]), (wall.extent[0], wall.extent[1]));
+        }
+
+        for floor in &self.world.floors {
+            let kind = floor_type(&floor.kind).unwrap();
+
+            world = world.floor(
+                (floor.pos[0], floor.pos[1]),
+                (floor.extent[0], floor.extent[1]),
+                kind
+            );
+        }
+
+        for obstacle in &self.world.obstacles {
+            world = world.obstacle(obstacle.obstacle().unwrap());
+        }
+
+        world
+    }
+
+    ///
+    /// Validates names and extents so building the scenario can't panic.
+    ///
+    fn check(&self) -> Result<(), String> {
+        let (width, height) = self.extent();
+
+        if width == 0 || height == 0 {
+            return Err(format!("world extent ({}, {}) is empty", width, height));
+        }
+
+        if self.map.is_some() && (self.world.width != 0 || self.world.height != 0) {
+            return Err(String::from("world map and width/height are exclusive"));
+        }
+
+        if let (Some(map), Some(spec)) = (&self.place_map, &self.world.place_map) {
+            if width < map.width() || height < map.height() {
+                return Err(format!("place map extent {:?} is larger than the world", map.extent()));
+            }
+
+            for symbol in spec.kinds.keys() {
+                if symbol.chars().count() != 1 {
+                    return Err(format!("place map symbol '{}' must be one character", symbol));
+                }
+            }
+        }
+
+        self.world.check((width, height))?;
+
+        if let Some(food) = &self.food {
+            food.check((width, height))?;
+        }
+
+        for odor in &self.odor {
+            odor.check((width, height))?;
+        }
+
+        if let Some(field) = &self.odor_field {
+            field.check()?;
+        }
+
+        if let Some(animal) = &self.animal {
+            animal.check()?;
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::Scenario;
+
+    #[test]
+    fn parse_scenario() {
+        let scenario = Scenario::parse(r#"
+            [world]
+            width = 21
+            height = 13
+            walls = [{ pos = [6, 0], extent = [1, 6] }]
+            floors = [{ pos = [0, 0], extent = [10, 13], kind = "dark" }]
+            obstacles = [
+                { segment = [[8.0, 2.0], [12.0, 6.0]] },
+                { circle = [15.0, 9.0], r = 1.5 },
+            ]
+
+            [food]
+            items = [{ pos = [5, 5], kind = "sweet", odor = "food_a", odor_r = 4 }]
+            gen = { count = 2, radius = 3.0, value_s = 240.0, odor = "food_b" }
+
+            [[odor]]
+            pos = [15, 5]
+            r = 4
+            kind = "food_a"
+            mix = [{ kind = "avoid_a", weight = 0.5 }]
+
+            [[place]]
+            pos = [14.0, 4.0]
+            r = 3.0
+            kind = "avoid_a"
+
+            [animal]
+            olfactory = ["food_a", "food_b"]
+            olfactory_strategy = "tropotaxis"
+            hind_eat = "filter_feed"
+            retina = { fov_deg = 150.0, eye_angle_deg = 45.0, strategy = "ray_cast" }
+            tectum = { orient = false }
+            phototaxis = { sign = "away", priority = "roam" }
+            chemotaxis = { odors = ["food_a"], gain = 2.0 }
+        "#).unwrap();
+
+        assert_eq!((21, 13), scenario.extent());
+        assert_eq!(vec!["avoid_a"], scenario.place_kinds());
+    }
+
+    #[test]
+    fn scenario_errors() {
+        assert!(Scenario::parse("[world]\nwidth = 5\nheight = 5\nwalls = [{ pos = [4, 0], extent = [2, 1] }]").is_err());
+        assert!(Scenario::parse("[world]\nwidth = 5\nheight = 5\n[[odor]]\npos = [1, 1]\nkind = \"bogus\"").is_err());
+        assert!(Scenario::parse("[world]\nwidth = 5\nheight = 5\n[animal]\nhind_eat = \"grazer\"").is_err());
+        assert!(Scenario::parse("[world]\nwidth = 5\nheight = 5\nunknown = 3").is_err());
+        assert!(Scenario::parse("[world]\nwidth = 5\nheight = 5\n[animal]\nphototaxis = { sign = \"up\" }").is_err());
+        assert!(Scenario::parse("[world]\nwidth = 5\nheight = 5\nobstacles = [{ circle = [1.0, 1.0] }]").is_err());
+    }
+}
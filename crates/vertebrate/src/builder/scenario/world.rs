@@ -0,0 +1,124 @@
+use std::collections::HashMap;
+
+use serde::Deserialize;
+
+use crate::world::{FloorType, Obstacle};
+
+#[derive(Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub(super) struct WorldSpec {
+    #[serde(default)]
+    pub(super) width: usize,
+
+    #[serde(default)]
+    pub(super) height: usize,
+
+    // ASCII or PNG map, replacing width and height
+    pub(super) map: Option<String>,
+    pub(super) place_map: Option<PlaceMapSpec>,
+
+    #[serde(default)]
+    pub(super) walls: Vec<RectSpec>,
+
+    #[serde(default)]
+    pub(super) floors: Vec<FloorSpec>,
+
+    #[serde(default)]
+    pub(super) obstacles: Vec<ObstacleSpec>,
+}
+
+impl WorldSpec {
+    pub(super) fn check(&self, extent: (usize, usize)) -> Result<(), String> {
+        for wall in &self.walls {
+            in_world("wall", wall.pos, wall.extent, extent)?;
+        }
+
+        for floor in &self.floors {
+            in_world("floor", floor.pos, floor.extent, extent)?;
+            floor_type(&floor.kind)?;
+        }
+
+        for obstacle in &self.obstacles {
+            obstacle.obstacle()?;
+        }
+
+        Ok(())
+    }
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub(super) struct PlaceMapSpec {
+    pub(super) path: String,
+    pub(super) kinds: HashMap<String, String>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub(super) struct RectSpec {
+    pub(super) pos: [usize; 2],
+    pub(super) extent: [usize; 2],
+}
+
+// exactly one of segment, polygon or circle, where circle needs r
+#[derive(Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub(super) struct ObstacleSpec {
+    pub(super) segment: Option<[[f32; 2]; 2]>,
+    pub(super) polygon: Option<Vec<[f32; 2]>>,
+    pub(super) circle: Option<[f32; 2]>,
+    pub(super) r: Option<f32>,
+}
+
+impl ObstacleSpec {
+    pub(super) fn obstacle(&self) -> Result<Obstacle, String> {
+        match (&self.segment, &self.polygon, &self.circle, self.r) {
+            (Some([p0, p1]), None, None, None) => Ok(Obstacle::segment(*p0, *p1)),
+            (None, Some(points), None, None) if points.len() >= 3 => {
+                Ok(Obstacle::polygon(points))
+            }
+            (None, Some(_), None, None) => {
+                Err(String::from("obstacle polygon requires at least 3 points"))
+            }
+            (None, None, Some(center), Some(r)) if r > 0. => Ok(Obstacle::circle(*center, r)),
+            _ => Err(String::from("obstacle requires one of segment, polygon, or circle with positive r")),
+        }
+    }
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub(super) struct FloorSpec {
+    pub(super) pos: [usize; 2],
+    pub(super) extent: [usize; 2],
+    pub(super) kind: String,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub(super) struct PlaceSpec {
+    pub(super) pos: [f32; 2],
+    pub(super) r: f32,
+    pub(super) kind: String,
+}
+
+pub(super) fn in_world(
+    name: &str,
+    pos: [usize; 2],
+    extent: [usize; 2],
+    (width, height): (usize, usize)
+) -> Result<(), String> {
+    if width < pos[0] + extent[0] || height < pos[1] + extent[1] {
+        Err(format!("{} at {:?} extent {:?} is outside the world", name, pos, extent))
+    } else {
+        Ok(())
+    }
+}
+
+pub(super) fn floor_type(name: &str) -> Result<FloorType, String> {
+    match name {
+        "light" => Ok(FloorType::Light),
+        "dark" => Ok(FloorType::Dark),
+        _ => Err(format!("unknown floor kind '{}'", name)),
+    }
+}
@@ -183,6 +183,15 @@ impl HindEat {
         self.is_stop_request.set(true);
     }
 
+    ///
+    /// Conditioned gaping from a learned aversive odor, expelling the
+    /// food before its taste.
+    ///
+    pub fn gape(&mut self) {
+        self.is_gaping.set(true);
+        self.is_eating.set(false);
+    }
+
     fn is_stop_request(&self) -> bool {
         self.is_stop_request.value_or(false)
     } 
@@ -1,9 +1,10 @@
-use essay_ecs::{app::{App, Plugin}, core::{Res, ResMut}};
-use mind_ecs::Tick;
-use util::random::random_uniform;
+use essay_ecs::{app::{App, Plugin}, core::{Component, Query, Res, ResMut}};
+use mind_ecs::{SeedPlugin, Tick};
+use util::random::Rand32;
 
 use crate::{
-    body::{Body, BodyPlugin}, hind_brain::Serotonin, hypothalamus::{Motive, Wake}, util::{DecayValue, Seconds, Ticks, TimeoutValue, Turn}
+    body::{Body, BodyPlugin}, hind_brain::Serotonin, hypothalamus::{Motive, Wake}, 
+    olfactory::olfactory_bulb::OlfactoryBulb, util::{DecayValue, Seconds, Ticks, TimeoutValue, Turn}
 };
 
 use super::{r4_startle::StartleR4, ArtrR2, HindEat};
@@ -99,6 +100,29 @@ fn update_hind_move(
     }
 }
 
+///
+/// Extra bodies only have a reflex hindbrain without motive or mid-brain
+/// circuits, so it seeks the food odor sensed by their own olfactory
+/// bulb and otherwise defaults to an ARTR roaming random walk.
+/// 
+fn update_hind_move_animals(
+    animals: Query<(&mut HindMove, &mut Body, &OlfactoryBulb)>,
+) {
+    for (hind_move, body, bulb) in animals.iter() {
+        hind_move.pre_update();
+
+        if ! hind_move.update_startle(body) {
+            if let Some(food) = bulb.food_vector() {
+                hind_move.seek();
+                hind_move.turn(food.dir().to_turn().to_unit() * 0.5);
+            } else {
+                hind_move.roam();
+            }
+
+            hind_move.update_voluntary_move(body);
+        }
+    }
+}
 
 /// Currently using ARS, MRS, PRS similar to Lamprey naming 
 /// convention: ARRN, MRRN, PRRN
@@ -125,6 +149,7 @@ fn update_hind_move(
 /// r4 startle: Mauthner cells
 /// r5-6 MLR/DLR and OT turn: MRRN
 /// 
+#[derive(Component)]
 pub struct HindMove {
     // mid - nMLF - opto-motor, OKR, OMR, visual hunting, phototaxis, looming
     optic_mb: OpticMid,
@@ -161,6 +186,8 @@ pub struct HindMove {
     mo_forward: f32,
     mo_left: f32,
     mo_right: f32,
+
+    rand: Rand32,
 }
 
 impl HindMove {
@@ -194,6 +221,8 @@ impl HindMove {
             mo_forward: 0.0,
             mo_left: 0.0,
             mo_right: 0.0,
+
+            rand: Rand32::new(),
         }
     }
 
@@ -360,7 +389,7 @@ impl HindMove {
         // thigmotaxis - R1.a
         kind = self.ante().action().unwrap_or(kind);
 
-        if random_uniform() < 0.5 {
+        if self.rand.next_uniform() < 0.5 {
             turn = Turn::Unit(0.);
         }
 
@@ -888,17 +917,31 @@ impl MoveKind {
 
 pub struct HindMovePlugin;
 
+impl HindMovePlugin {
+    ///
+    /// Creates a HindMove with random streams named by prefix, used for
+    /// the primary animal and animal entities.
+    /// 
+    pub(crate) fn create_hind_move(&self, app: &mut App, prefix: &str) -> HindMove {
+        let mut hind_move = HindMove::new();
+        hind_move.rand = SeedPlugin::rand32(app, &format!("{}hind_move", prefix));
+        // hind_move.artr_r2 = Some(OscillatorArs::new());
+        hind_move.startle_r4 = StartleR4::new();
+
+        hind_move
+    }
+}
+
 impl Plugin for HindMovePlugin {
     fn build(&self, app: &mut App) {
         assert!(app.contains_plugin::<BodyPlugin>(), "HindLocomotionPlugin requires BodyPlugin");
 
-        let mut hind_move = HindMove::new();
-        // hind_move.artr_r2 = Some(OscillatorArs::new());
-        hind_move.startle_r4 = StartleR4::new();
+        let hind_move = self.create_hind_move(app, "");
 
         app.insert_resource(hind_move);
         app.init_resource::<Serotonin<ArtrR2>>();
 
         app.system(Tick, update_hind_move);
+        app.system(Tick, update_hind_move_animals);
     }
 }
@@ -6,16 +6,21 @@ use mind_ecs::Tick;
 
 use crate::{
     body::Body, 
-    util::{Heading, Point}, 
-    world::World
+    util::{Heading, Line, Point}, 
+    world::{FlowField, World}
 };
 
 fn update_lateral_line(
     mut lateral_line: ResMut<LateralLine>,
     body: Res<Body>,
     world: Res<World>,
+    flow: Option<Res<FlowField>>,
 ) {
     lateral_line.update(body.as_ref(), world.as_ref());
+
+    if let Some(flow) = flow {
+        lateral_line.update_flow(body.as_ref(), flow.get());
+    }
 }
 
 pub struct LateralLine {
@@ -35,6 +40,10 @@ pub struct LateralLine {
 
     tail_left_sensors: Vec<f32>,
     tail_right_sensors: Vec<f32>,
+
+    // water flow in body coordinates (forward, right)
+    head_flow: Point,
+    tail_flow: Point,
 }
 
 impl LateralLine {
@@ -65,6 +74,44 @@ impl LateralLine {
         self.tail_right_sensors = update_sensors(&self.tail_right_points, &affine, world);
     }
 
+    ///
+    /// Water flow at the head in body coordinates (forward, right).
+    ///
+    #[inline]
+    pub fn head_flow(&self) -> Point {
+        self.head_flow
+    }
+
+    #[inline]
+    pub fn tail_flow(&self) -> Point {
+        self.tail_flow
+    }
+
+    ///
+    /// Cross-flow pressing on a segment's side, where flow from the left
+    /// stimulates the left neuromasts.
+    ///
+    pub fn flow(&self, segment: Segment) -> f32 {
+        match segment {
+            Segment::HeadLeft => self.head_flow.y().max(0.),
+            Segment::HeadRight => (- self.head_flow.y()).max(0.),
+            Segment::TailLeft => self.tail_flow.y().max(0.),
+            Segment::TailRight => (- self.tail_flow.y()).max(0.),
+        }
+    }
+
+    fn update_flow(&mut self, body: &Body, flow: &FlowField) {
+        let (sin, cos) = body.dir().sin_cos();
+        let head = body.head_pos();
+        let tail = body.pos() - (0.5 * body.len()) * Point(cos, sin);
+
+        // forward is (cos, sin) and right is clockwise from forward
+        let to_body = |v: Point| Point(v.x() * cos + v.y() * sin, v.x() * sin - v.y() * cos);
+
+        self.head_flow = to_body(flow.velocity(head));
+        self.tail_flow = to_body(flow.velocity(tail));
+    }
+
     pub fn sensors(&self, segment: Segment) -> &Vec<f32> {
         match segment {
             Segment::HeadLeft => &self.head_left_sensors,
@@ -91,6 +138,7 @@ fn update_sensors(rays: &Tensor, affine: &Affine2d, world: &World) -> Vec<f32> {
     let n_rays = rays.rows();
     for ray in 0..rays.dim(0) {
         let mut value: f32 = 0.;
+        let mut prev: Option<Point> = None;
 
         for sensor in 0..n_rays {
             let x = slice[2 * (ray * n_rays + sensor) + 0];
@@ -102,6 +150,15 @@ fn update_sensors(rays: &Tensor, affine: &Affine2d, world: &World) -> Vec<f32> {
             if world.is_collide(pos) {
                 value = value.max(((sensor + 1) as f32) / n_rays as f32);
             }
+
+            // a thin obstacle between samples hides the outer sample
+            if let Some(prev) = prev {
+                if world.is_cross(Line(prev, pos)) {
+                    value = value.max(sensor as f32 / n_rays as f32);
+                }
+            }
+
+            prev = Some(pos);
         }
 
         sensors.push(value);
@@ -201,6 +258,9 @@ impl From<(SensorBuilder, SensorBuilder)> for LateralLine {
 
             tail_left_sensors,
             tail_right_sensors,
+
+            head_flow: Point(0., 0.),
+            tail_flow: Point(0., 0.),
         }
     }
 }
@@ -1,6 +1,6 @@
 use essay_ecs::{app::{App, Plugin}, core::{Res, ResMut}};
-use mind_ecs::Tick;
-use util::random::{random_pareto, Rand32};
+use mind_ecs::{SeedPlugin, Tick};
+use util::random::Rand32;
 
 use crate::{body::BodyEatPlugin, hind_brain::SerotoninManager, util::{Seconds, Ticks, Turn}};
 
@@ -26,6 +26,7 @@ fn update_artr(
 }
 
 pub struct ArtrR2 {
+    rand: Rand32,
 }
 
 impl ArtrR2 {
@@ -37,17 +38,17 @@ impl ArtrR2 {
     
     const ALPHA : f32 = 2.;
 
-    pub(super) fn new() -> Self {
+    pub(super) fn new(rand: Rand32) -> Self {
         Self {
+            rand,
         }
     }
 
     pub(super) fn next_turn(&mut self) -> Option<Turn> {
-        let mut rand = Rand32::new();
         // semi-brownian
-        if rand.next_uniform() <= 0.5 {
+        if self.rand.next_uniform() <= 0.5 {
             Some(Turn::Deg(0.))
-        } else if rand.next_uniform() <= 0.5 {
+        } else if self.rand.next_uniform() <= 0.5 {
             Some(Turn::Deg(-30.))
         } else {
             Some(Turn::Deg(30.))
@@ -55,11 +56,11 @@ impl ArtrR2 {
     }
 
     #[allow(unused)]
-    fn levy_run_len(&self, is_dwell: bool) -> f32 {
+    fn levy_run_len(&mut self, is_dwell: bool) -> f32 {
         if is_dwell {
-            random_pareto(Self::DWELL_LOW, Self::DWELL_HIGH, Self::ALPHA)
+            self.rand.next_pareto(Self::DWELL_LOW, Self::DWELL_HIGH, Self::ALPHA)
         } else {
-            random_pareto(Self::ROAM_LOW, Self::ROAM_HIGH, Self::ALPHA)
+            self.rand.next_pareto(Self::ROAM_LOW, Self::ROAM_HIGH, Self::ALPHA)
         }
     }
 }
@@ -68,6 +69,7 @@ impl SerotoninTrait for ArtrR2 {}
 
 // Karpenko et al 2020 - ARTR oscillator 20s period
 pub struct OscillatorArs {
+    rand: Rand32,
 }
 
 impl OscillatorArs {
@@ -79,17 +81,17 @@ impl OscillatorArs {
     
     const ALPHA : f32 = 2.;
 
-    pub(super) fn _new() -> Self {
+    pub(super) fn _new(rand: Rand32) -> Self {
         Self {
+            rand,
         }
     }
 
     pub(super) fn _next_turn(&mut self) -> Option<Turn> {
-        let mut rand = Rand32::new();
         // semi-brownian
-        if rand.next_uniform() <= 0.5 {
+        if self.rand.next_uniform() <= 0.5 {
             Some(Turn::Deg(0.))
-        } else if rand.next_uniform() <= 0.5 {
+        } else if self.rand.next_uniform() <= 0.5 {
             Some(Turn::Deg(-30.))
         } else {
             Some(Turn::Deg(30.))
@@ -97,11 +99,11 @@ impl OscillatorArs {
     }
 
     #[allow(unused)]
-    fn levy_run_len(&self, is_dwell: bool) -> f32 {
+    fn levy_run_len(&mut self, is_dwell: bool) -> f32 {
         if is_dwell {
-            random_pareto(Self::DWELL_LOW, Self::DWELL_HIGH, Self::ALPHA)
+            self.rand.next_pareto(Self::DWELL_LOW, Self::DWELL_HIGH, Self::ALPHA)
         } else {
-            random_pareto(Self::ROAM_LOW, Self::ROAM_HIGH, Self::ALPHA)
+            self.rand.next_pareto(Self::ROAM_LOW, Self::ROAM_HIGH, Self::ALPHA)
         }
     }
 }
@@ -124,7 +126,7 @@ impl Plugin for HindSearchPlugin {
 
         SerotoninManager::insert::<ArtrR2>(app, self.search_time);
 
-        let hind_search = ArtrR2::new();
+        let hind_search = ArtrR2::new(SeedPlugin::rand32(app, "hind_search"));
 
         // hind_search.is_eating = TimeoutValue::new(self.search_time);
 
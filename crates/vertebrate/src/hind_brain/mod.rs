@@ -27,6 +27,8 @@ pub use hind_locomotor::{
 
 pub use r2_artr::ArtrR2;
 
+pub use r4_startle::AcousticStartlePlugin;
+
 pub use rpb_avoid_place::{AvoidHere, AvoidHerePlugin};
 
 pub use rpb_avoid::{
@@ -1,25 +1,115 @@
-use crate::{body::Body, util::{DecayValue, Seconds, Turn}};
+use essay_ecs::{
+    app::{App, Plugin},
+    core::{Res, ResMut},
+};
+use mind_ecs::Tick;
+
+use crate::{
+    body::{Body, Ear, EarPlugin, EarSide},
+    hypothalamus::HypEat,
+    util::{DecayValue, Habituation, HalfLife, Seconds, Turn},
+};
+
+use super::{hind_locomotor::{Action, MoveKind}, HindMove, HindMovePlugin};
+
+fn update_acoustic_startle(
+    ear: Res<Ear>,
+    mut hind_move: ResMut<HindMove>,
+    hyp_eat: Option<Res<HypEat>>,
+) {
+    // aversive events sensitize the startle
+    if hyp_eat.map_or(false, |hyp_eat| hyp_eat.is_alarm()) {
+        hind_move.startle().sensitize(1.);
+    }
 
-use super::hind_locomotor::{Action, MoveKind};
+    hind_move.startle().acoustic(ear.onset(EarSide::Left), ear.onset(EarSide::Right));
+}
 
 pub struct StartleR4 {
     ss_forward: f32,
     ss_left: DecayValue,
     ss_right: DecayValue,
 
+    // prepulse inhibition from recent acoustic onsets
+    prepulse: DecayValue,
+
+    // habituation to repeated onsets on each side
+    habituation: Habituation,
+
     next_action: Option<Action>,
 }
 
 impl StartleR4 {
+    // fraction of the startle removed by a full prepulse
+    pub const PREPULSE_INHIBITION : f32 = 0.7;
+    // onset giving a full prepulse
+    pub const PREPULSE_FULL : f32 = 0.1;
+    // onsets below the minimum are noise
+    pub const PREPULSE_MIN : f32 = 0.02;
+
+    const LEFT : usize = 0;
+    const RIGHT : usize = 1;
+
     pub(super) fn new() -> Self {
         Self {
             ss_forward: 0.,
             ss_left: DecayValue::default(),
             ss_right: DecayValue::default(),
+            prepulse: DecayValue::new(HalfLife(0.5)),
+            habituation: Habituation::new(2),
             next_action: None,
         }
     }
 
+    ///
+    /// Acoustic onsets in [0, 1] from the left and right ears. Mutual
+    /// inhibition between the Mauthner cells lets only the louder side
+    /// escape, and a recent weaker onset, the prepulse, inhibits the
+    /// startle. Repeated onsets on the same side habituate.
+    ///
+    pub fn acoustic(&mut self, left: f32, right: f32) {
+        let onset = left.max(right).clamp(0., 1.);
+
+        if onset < Self::PREPULSE_MIN {
+            return;
+        }
+
+        let inhibition = 1. - Self::PREPULSE_INHIBITION * self.prepulse.value();
+
+        let side = if left >= right { Self::LEFT } else { Self::RIGHT };
+        let response = self.habituation.stimulus(side, onset);
+
+        let value = (inhibition * response).clamp(0., 1.);
+
+        self.prepulse.set_max((onset / Self::PREPULSE_FULL).min(1.));
+
+        if side == Self::LEFT {
+            self.escape_left(value);
+        } else {
+            self.escape_right(value);
+        }
+    }
+
+    ///
+    /// Current prepulse inhibition level in [0, 1].
+    ///
+    #[inline]
+    pub fn prepulse(&self) -> f32 {
+        self.prepulse.value()
+    }
+
+    ///
+    /// Sensitization in [0, 1] from an aversive event.
+    ///
+    pub fn sensitize(&mut self, value: f32) {
+        self.habituation.sensitize(value);
+    }
+
+    #[inline]
+    pub fn habituation(&self) -> &Habituation {
+        &self.habituation
+    }
+
     pub fn escape_left(&mut self, value: f32) {
         self.ss_left.set_max(value);
     }
@@ -36,6 +126,8 @@ impl StartleR4 {
 
         self.ss_left.update();
         self.ss_right.update();
+        self.prepulse.update();
+        self.habituation.update();
 
         self.next_action = if forward > 0.5 {
             Some(Action::new(MoveKind::Startle, 1., Turn::Unit(0.5), Seconds(2.0)))
@@ -78,3 +170,71 @@ impl StartleR4 {
         self.next_action.clone()
     }
 }
+
+///
+/// Acoustic startle connects the ear's onsets to the r4 Mauthner cells.
+///
+pub struct AcousticStartlePlugin;
+
+impl Plugin for AcousticStartlePlugin {
+    fn build(&self, app: &mut App) {
+        assert!(app.contains_plugin::<HindMovePlugin>(), "AcousticStartlePlugin requires HindMovePlugin");
+        assert!(app.contains_plugin::<EarPlugin>(), "AcousticStartlePlugin requires EarPlugin");
+
+        app.system(Tick, update_acoustic_startle);
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::StartleR4;
+
+    #[test]
+    fn startle_prepulse_inhibition() {
+        // a loud onset on the left escapes
+        let mut startle = StartleR4::new();
+        startle.acoustic(0.8, 0.6);
+        assert!(startle.ss_left() > 0.5);
+        assert_eq!(0., startle.ss_right());
+
+        // a weak prepulse shortly before inhibits the startle
+        let mut startle = StartleR4::new();
+        startle.acoustic(0.2, 0.1);
+        assert!(startle.ss_left() < 0.5);
+
+        for _ in 0..3 {
+            startle.prepulse.update();
+        }
+
+        startle.acoustic(0.8, 0.6);
+        assert!(startle.ss_left() < 0.5);
+    }
+
+    #[test]
+    fn startle_habituation() {
+        let mut startle = StartleR4::new();
+
+        let mut escapes = Vec::new();
+
+        for _ in 0..10 {
+            startle.acoustic(0.8, 0.);
+            escapes.push(startle.ss_left() > 0.5);
+
+            // inter-stimulus interval past the prepulse and escape decay
+            for _ in 0..20 {
+                startle.ss_left.update();
+                startle.prepulse.update();
+                startle.habituation.update();
+            }
+        }
+
+        // the startle habituates to the repeated sound
+        assert!(escapes[0]);
+        assert!(! escapes[9]);
+
+        // sensitization restores the startle to a loud sound
+        startle.sensitize(1.);
+        startle.acoustic(1., 0.);
+        assert!(startle.ss_left() > 0.5);
+    }
+}
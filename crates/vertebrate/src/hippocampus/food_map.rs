@@ -0,0 +1,382 @@
+use essay_ecs::{app::{App, Plugin}, core::{Res, ResMut}};
+use mind_ecs::Tick;
+
+use crate::{
+    body::{Body, BodyEat, BodyEatPlugin},
+    hind_brain::{HindMove, HindMovePlugin},
+    hypothalamus::{FoodZone, Forage, HypForagePlugin, Motive},
+    olfactory::olfactory_bulb::OlfactoryBulb,
+    util::{DecayValue, Heading, HalfLife, Point, Seconds, Ticks, Turn},
+};
+
+use super::{PlaceCells, PlaceCellsPlugin};
+
+//
+// Hippocampal cognitive map of food locations
+//
+// [Broglio et al 2010] goldfish Dl lesions impair place learning
+// [Morris 1984] water maze return to a remembered hidden platform
+// [Rodriguez et al 2002] fish Dl is a hippocampal homolog for spatial memory
+// [Tolman 1948] cognitive maps in rats
+//
+
+fn update_food_map(
+    mut food_map: ResMut<FoodMap>,
+    place: Res<PlaceCells>,
+    body: Res<Body>,
+    body_eat: Res<BodyEat>,
+    forage: Res<Motive<Forage>>,
+    mut hind_move: ResMut<HindMove>,
+    bulb: Option<Res<OlfactoryBulb>>,
+    food_zone: Option<Res<FoodZone>>,
+) {
+    let pos = place.estimate();
+    let is_food = body_eat.taste_food() >= FoodMap::TASTE;
+
+    food_map.update(pos, is_food);
+
+    // the map only steers when foraging without a local odor or food zone
+    if ! forage.is_active() {
+        return;
+    }
+
+    if bulb.map_or(false, |bulb| bulb.food_vector().is_some()) {
+        // a sensed odor steers with olfactory seek and klinotaxis
+        return;
+    }
+
+    if food_zone.map_or(false, |zone| zone.is_food_zone()) {
+        return;
+    }
+
+    if let Some(goal) = food_map.goal_heading(pos) {
+        let turn = Turn::Unit(goal.to_unit() - body.dir().to_unit());
+
+        hind_move.seek();
+        hind_move.turn(turn);
+    }
+}
+
+///
+/// FoodMap remembers where the animal ate, using the place cells'
+/// estimated position. Each memory decays, so older food locations are
+/// less attractive, and the planner picks the best remaining location
+/// weighted by its distance.
+///
+/// Returning to a remembered location without finding food for the
+/// search time invalidates the memory, so a depleted patch is forgotten.
+///
+pub struct FoodMap {
+    half_life: HalfLife,
+    capacity: usize,
+    merge_radius: f32,
+    arrive_radius: f32,
+    distance: f32,
+    search_time: usize,
+
+    items: Vec<FoodMemory>,
+}
+
+impl FoodMap {
+    // taste for a food event
+    const TASTE : f32 = 0.5;
+    // forget a decayed memory
+    const FORGET : f32 = 0.05;
+
+    #[inline]
+    pub fn len(&self) -> usize {
+        self.items.len()
+    }
+
+    ///
+    /// Remembered food location and its value.
+    ///
+    pub fn memory(&self, i: usize) -> (Point, f32) {
+        let item = &self.items[i];
+
+        (item.pos, item.value.value())
+    }
+
+    ///
+    /// Best remembered food location from the position, weighted by
+    /// the memory's value and its distance.
+    ///
+    pub fn goal(&self, pos: Point) -> Option<Point> {
+        let mut best: Option<(Point, f32)> = None;
+
+        for item in &self.items {
+            let score = item.value.value() / (1. + pos.dist(item.pos) / self.distance);
+
+            if best.map_or(true, |(_, best_score)| best_score < score) {
+                best = Some((item.pos, score));
+            }
+        }
+
+        best.map(|(goal, _)| goal)
+    }
+
+    ///
+    /// Heading to the best location, None when already there.
+    ///
+    pub fn goal_heading(&self, pos: Point) -> Option<Heading> {
+        match self.goal(pos) {
+            Some(goal) if pos.dist(goal) > self.arrive_radius => {
+                Some(pos.heading_to(goal))
+            }
+            _ => None,
+        }
+    }
+
+    fn update(&mut self, pos: Point, is_food: bool) {
+        for item in &mut self.items {
+            item.value.update();
+
+            if pos.dist(item.pos) > self.arrive_radius {
+                item.search = 0;
+            } else if is_food {
+                item.search = 0;
+            } else {
+                item.search += 1;
+            }
+        }
+
+        if is_food {
+            self.food(pos);
+        }
+
+        // searching a remembered location without food means it's depleted
+        let search_time = self.search_time;
+        self.items.retain(|item| {
+            item.value.value() >= Self::FORGET && item.search < search_time
+        });
+    }
+
+    fn food(&mut self, pos: Point) {
+        let merge_radius = self.merge_radius;
+
+        if let Some(item) = self.items.iter_mut()
+            .find(|item| item.pos.dist(pos) <= merge_radius) {
+            // move toward the center of the patch
+            item.pos = item.pos + 0.1 * (pos - item.pos);
+            item.value.set(1.);
+            return;
+        }
+
+        if self.items.len() >= self.capacity {
+            // replace the weakest memory
+            let (i, _) = self.items.iter().enumerate()
+                .min_by(|a, b| a.1.value.value().total_cmp(&b.1.value.value()))
+                .unwrap();
+
+            self.items.remove(i);
+        }
+
+        let mut value = DecayValue::new(self.half_life);
+        value.set(1.);
+
+        self.items.push(FoodMemory {
+            pos,
+            value,
+            search: 0,
+        });
+    }
+}
+
+struct FoodMemory {
+    pos: Point,
+    value: DecayValue,
+    search: usize,
+}
+
+pub struct FoodMapPlugin {
+    half_life: HalfLife,
+    capacity: usize,
+    merge_radius: f32,
+    arrive_radius: f32,
+    distance: f32,
+    search_time: Ticks,
+}
+
+impl FoodMapPlugin {
+    pub fn new() -> Self {
+        Self {
+            half_life: Seconds(300.).into(),
+            capacity: 8,
+            merge_radius: 1.5,
+            arrive_radius: 1.,
+            distance: 10.,
+            search_time: Seconds(10.).into(),
+        }
+    }
+
+    ///
+    /// Memory half-life of a food location.
+    ///
+    pub fn half_life(&mut self, half_life: impl Into<HalfLife>) -> &mut Self {
+        self.half_life = half_life.into();
+
+        self
+    }
+
+    ///
+    /// Number of remembered food locations.
+    ///
+    pub fn capacity(&mut self, capacity: usize) -> &mut Self {
+        assert!(capacity > 0);
+
+        self.capacity = capacity;
+
+        self
+    }
+
+    ///
+    /// Food events within the radius are the same location.
+    ///
+    pub fn merge_radius(&mut self, radius: f32) -> &mut Self {
+        assert!(radius >= 0.);
+
+        self.merge_radius = radius;
+
+        self
+    }
+
+    ///
+    /// Distance to a remembered location to stop navigating and search.
+    ///
+    pub fn arrive_radius(&mut self, radius: f32) -> &mut Self {
+        assert!(radius > 0.);
+
+        self.arrive_radius = radius;
+
+        self
+    }
+
+    ///
+    /// Distance where a location's value is halved for planning.
+    ///
+    pub fn distance(&mut self, distance: f32) -> &mut Self {
+        assert!(distance > 0.);
+
+        self.distance = distance;
+
+        self
+    }
+
+    ///
+    /// Search time at a location without food before it's forgotten.
+    ///
+    pub fn search_time(&mut self, time: impl Into<Ticks>) -> &mut Self {
+        let time = time.into();
+        assert!(time.ticks() > 0);
+
+        self.search_time = time;
+
+        self
+    }
+
+    fn create_food_map(&self) -> FoodMap {
+        FoodMap {
+            half_life: self.half_life,
+            capacity: self.capacity,
+            merge_radius: self.merge_radius,
+            arrive_radius: self.arrive_radius,
+            distance: self.distance,
+            search_time: self.search_time.ticks(),
+
+            items: Vec::new(),
+        }
+    }
+}
+
+impl Plugin for FoodMapPlugin {
+    fn build(&self, app: &mut App) {
+        assert!(app.contains_plugin::<PlaceCellsPlugin>(), "FoodMap requires PlaceCellsPlugin");
+        assert!(app.contains_plugin::<BodyEatPlugin>(), "FoodMap requires BodyEatPlugin");
+        assert!(app.contains_plugin::<HindMovePlugin>(), "FoodMap requires HindMovePlugin");
+        assert!(app.contains_plugin::<HypForagePlugin>(), "FoodMap requires HypForagePlugin");
+
+        app.insert_resource(self.create_food_map());
+
+        app.system(Tick, update_food_map);
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use essay_ecs::{core::error::Result, prelude::{Res, ResMut}};
+    use mind_ecs::MindApp;
+
+    use crate::{
+        body::Body,
+        builder::AnimalBuilder,
+        util::{Point, Ticks},
+        world::{OdorKind, OdorPlugin, WorldPlugin},
+    };
+
+    use super::{FoodMap, FoodMapPlugin};
+
+    #[test]
+    fn food_map_return_and_deplete() {
+        let mut plugin = FoodMapPlugin::new();
+        plugin.search_time(Ticks(5));
+
+        let mut food_map = plugin.create_food_map();
+        assert_eq!(None, food_map.goal(Point(5., 5.)));
+
+        // eating at a patch
+        food_map.update(Point(2., 2.), true);
+        food_map.update(Point(2.5, 2.), true);
+        assert_eq!(1, food_map.len());
+
+        // away from the patch, the planner heads back
+        let pos = Point(8., 2.);
+        for _ in 0..20 {
+            food_map.update(pos, false);
+        }
+        assert!(food_map.goal_heading(pos).is_some());
+
+        let heading = food_map.goal_heading(pos).unwrap().to_unit();
+        assert!((heading - 0.75).abs() < 0.01, "heading {}", heading);
+
+        // at the patch, the planner stops
+        assert!(food_map.goal_heading(Point(2., 2.5)).is_none());
+
+        // searching the patch without food forgets it
+        for _ in 0..5 {
+            food_map.update(Point(2., 2.), false);
+        }
+        assert_eq!(0, food_map.len());
+        assert_eq!(None, food_map.goal(pos));
+    }
+
+    #[test]
+    fn food_map_yields_to_odor() -> Result<()> {
+        let mut app = MindApp::test();
+        app.plugin(WorldPlugin::new(15, 15));
+
+        let mut odor = OdorPlugin::new();
+        odor.odor(5, 9, OdorKind::FoodA);
+        app.plugin(odor);
+
+        let mut builder = AnimalBuilder::new();
+        builder.pos((5.5, 5.5));
+        builder.olfactory().odor(OdorKind::FoodA);
+        builder.retina().enable(false);
+        builder.food_map();
+        builder.build(&mut app);
+
+        // a remembered patch behind the north-facing animal
+        app.eval(|mut x: ResMut<FoodMap>| x.food(Point(5.5, 1.5)))?;
+
+        for _ in 0..30 {
+            app.tick()?;
+        }
+
+        assert_eq!(1, app.eval(|x: Res<FoodMap>| x.len())?);
+
+        // the odor ahead steers, not the remembered patch
+        assert!(app.eval(|x: Res<Body>| x.pos().y())? > 5.5);
+
+        Ok(())
+    }
+}
@@ -1,6 +1,5 @@
 use essay_ecs::{app::{App, Plugin}, core::ResMut};
-use mind_ecs::Tick;
-use util::random::Rand64;
+use mind_ecs::{SeedPlugin, Tick};
 
 use crate::{
     mid_brain::taxis::{TaxisAvoid, TaxisAvoidPlugin}, 
@@ -70,7 +69,6 @@ fn update_hippocampus(
 }
 
 pub struct HippocampusPlugin {
-    rand: Rand64,
     digits: usize,
     radix: usize,
     seq: usize,
@@ -78,14 +76,7 @@ pub struct HippocampusPlugin {
 
 impl HippocampusPlugin {
     pub fn new() -> Self {
-        let rand = if cfg!(test) {
-            Rand64(42)
-        } else {
-            Rand64::new()
-        };
-
         Self {
-            rand,
             digits: 5,
             radix: 4,
             seq: 2,
@@ -121,8 +112,10 @@ impl HippocampusPlugin {
 impl Plugin for HippocampusPlugin {
     fn build(&self, app: &mut App) {
         assert!(app.contains_plugin::<TaxisAvoidPlugin>(), "MidMove requires HindMove");
+        let rand = SeedPlugin::rand64(app, "hippocampus");
+
         let seq_builder = Sequence128Builder::new(
-            self.rand.clone(), self.digits, self.radix, self.seq
+            rand, self.digits, self.radix, self.seq
         );
 
         app.insert_resource(Hippocampus::new(seq_builder));
@@ -1,7 +1,11 @@
 mod hippocampus;
 mod engram;
+mod food_map;
+mod place;
 mod sequence;
 
 pub use engram::{Engram64, Engram128};
+pub use food_map::{FoodMap, FoodMapPlugin};
 pub use hippocampus::{Hippocampus, HippocampusPlugin};
+pub use place::{PlaceCells, PlaceCellsPlugin, PlaceLandmarkPlugin};
 pub use sequence::{Sequence128, Sequence128Builder};
\ No newline at end of file
@@ -0,0 +1,447 @@
+use std::marker::PhantomData;
+
+use essay_ecs::{app::{App, Plugin}, core::{Res, ResMut}};
+use mind_ecs::{SeedPlugin, Tick};
+use util::random::Rand32;
+
+use crate::{
+    body::{Body, BodyPlugin},
+    mid_brain::SeekContext,
+    util::Point,
+    world::{World, WorldHex, WorldHexTrait},
+};
+
+use super::Engram64;
+
+//
+// Hippocampal place cells with path integration
+//
+// [Hafting et al 2005] MEC grid cells integrate self-motion
+// [McNaughton et al 2006] Path integration drift reset by landmarks
+// [O'Keefe and Dostrovsky 1971] Place cells with spatial receptive fields
+// [Solstad et al 2008] Border cells fire along walls
+//
+
+fn update_place_cells(
+    mut place: ResMut<PlaceCells>,
+    body: Res<Body>,
+    world: Res<World>,
+) {
+    place.update(body.get(), world.get());
+}
+
+fn update_place_landmark<K: WorldHexTrait + PartialEq>(
+    mut place: ResMut<PlaceCells>,
+    body: Res<Body>,
+    hex: Res<WorldHex<K>>,
+) {
+    // the place kind under the animal, like a floor texture
+    let kind = &hex[body.pos()];
+
+    place.correct_landmark(|pos| &hex[pos] == kind);
+}
+
+///
+/// PlaceCells tile the arena with gaussian receptive fields around an
+/// estimated position. The estimate is path integrated from the body's
+/// commanded speed and heading, so noise and blocked or sliding moves
+/// along walls drift it from the true position.
+///
+/// Wall contacts correct the drift along the contact direction, and
+/// landmark place kinds pull the estimate to the nearest matching place.
+/// Each cell has an engram code, so the most active cell is a location
+/// context for learning.
+///
+pub struct PlaceCells {
+    centers: Vec<Point>,
+    engrams: Vec<Engram64>,
+    sigma: f32,
+
+    activity: Vec<f32>,
+    peak: Option<usize>,
+
+    estimate: Point,
+    is_init: bool,
+
+    noise: f32,
+    wall_correction: f32,
+    landmark_correction: f32,
+    landmark_radius: f32,
+
+    rand: Rand32,
+}
+
+impl PlaceCells {
+    // minimum activity for a peak cell
+    const THRESHOLD : f32 = 0.1;
+    // step for correction searches
+    const SEARCH_STEP : f32 = 0.05;
+    // maximum drift a wall contact corrects
+    const WALL_RANGE : f32 = 1.;
+
+    #[inline]
+    pub fn len(&self) -> usize {
+        self.centers.len()
+    }
+
+    #[inline]
+    pub fn center(&self, i: usize) -> Point {
+        self.centers[i]
+    }
+
+    #[inline]
+    pub fn engram(&self, i: usize) -> Engram64 {
+        self.engrams[i]
+    }
+
+    #[inline]
+    pub fn activity(&self) -> &Vec<f32> {
+        &self.activity
+    }
+
+    ///
+    /// Most active cell.
+    ///
+    #[inline]
+    pub fn peak(&self) -> Option<usize> {
+        self.peak
+    }
+
+    ///
+    /// Path integrated position.
+    ///
+    #[inline]
+    pub fn estimate(&self) -> Point {
+        self.estimate
+    }
+
+    fn update(&mut self, body: &Body, world: &World) {
+        if ! self.is_init {
+            // the starting place is known, like a home
+            self.is_init = true;
+            self.estimate = body.pos();
+            self.update_activity();
+            return;
+        }
+
+        let (sin, cos) = body.dir().sin_cos();
+        let forward = Point(cos, sin);
+
+        // self-motion from the speed command, the body moving that fraction
+        // of the way to its head, without feedback from the actual move
+        let reach = body.head_pos().dist(body.pos());
+        let step = body.speed() * reach * (1. + self.noise * self.rand.next_normal());
+
+        self.estimate = self.estimate + step * forward;
+
+        self.correct_walls(body, world);
+
+        self.update_activity();
+    }
+
+    fn correct_walls(&mut self, body: &Body, world: &World) {
+        let (sin, cos) = body.dir().sin_cos();
+        let s = 0.1 * body.len();
+
+        // contact sensors ahead of the head, matching the body's sensors
+        let head = body.head_pos() - body.pos();
+        let diag = 0.707 * s;
+
+        let sensors = [
+            (body.is_collide_forward(), head + s * Point(cos, sin)),
+            (body.is_collide_left(), head + diag * Point(cos - sin, sin + cos)),
+            (body.is_collide_right(), head + diag * Point(cos + sin, sin - cos)),
+        ];
+
+        for (is_collide, offset) in sensors {
+            if is_collide {
+                if let Some(shift) = wall_shift(world, self.estimate, offset) {
+                    self.estimate = self.estimate + self.wall_correction * shift;
+                }
+            }
+        }
+    }
+
+    ///
+    /// Pulls the estimate toward the nearest place matching the sensed
+    /// landmark.
+    ///
+    fn correct_landmark(&mut self, is_match: impl Fn(Point) -> bool) {
+        if is_match(self.estimate) {
+            return;
+        }
+
+        let r = self.landmark_radius;
+        let n = (r / Self::SEARCH_STEP).ceil() as i32;
+
+        let mut best: Option<(Point, f32)> = None;
+
+        for i in -n..=n {
+            for j in -n..=n {
+                let delta = Point(i as f32 * Self::SEARCH_STEP, j as f32 * Self::SEARCH_STEP);
+                let dist = delta.dist(Point(0., 0.));
+
+                if dist <= r
+                && best.map_or(true, |(_, best_dist)| dist < best_dist)
+                && is_match(self.estimate + delta) {
+                    best = Some((delta, dist));
+                }
+            }
+        }
+
+        if let Some((delta, _)) = best {
+            self.estimate = self.estimate + self.landmark_correction * delta;
+            self.update_activity();
+        }
+    }
+
+    fn update_activity(&mut self) {
+        let scale = 0.5 / (self.sigma * self.sigma);
+        let estimate = self.estimate;
+
+        for (activity, center) in self.activity.iter_mut().zip(&self.centers) {
+            *activity = (- scale * center.dist_square(estimate)).exp();
+        }
+
+        self.peak = None;
+        let mut max = Self::THRESHOLD;
+
+        for (i, activity) in self.activity.iter().enumerate() {
+            if *activity > max {
+                max = *activity;
+                self.peak = Some(i);
+            }
+        }
+    }
+}
+
+// shift along the contact so the estimated sensor touches the wall
+fn wall_shift(world: &World, estimate: Point, offset: Point) -> Option<Point> {
+    if world.is_collide(estimate + offset) {
+        return None;
+    }
+
+    let len = offset.dist(Point(0., 0.));
+
+    if len <= 0. {
+        return None;
+    }
+
+    let dir = (1. / len) * offset;
+    let n = (PlaceCells::WALL_RANGE / PlaceCells::SEARCH_STEP) as usize;
+
+    for i in 1..=n {
+        let d = i as f32 * PlaceCells::SEARCH_STEP;
+
+        if world.is_collide(estimate + offset + d * dir) {
+            return Some(d * dir);
+        }
+    }
+
+    None
+}
+
+impl SeekContext for PlaceCells {
+    fn context(&self) -> Engram64 {
+        self.peak.map_or(Engram64::default(), |i| self.engrams[i])
+    }
+}
+
+pub struct PlaceCellsPlugin {
+    spacing: f32,
+    sigma: f32,
+    noise: f32,
+    wall_correction: f32,
+    landmark_correction: f32,
+    landmark_radius: f32,
+}
+
+impl PlaceCellsPlugin {
+    pub fn new() -> Self {
+        Self {
+            spacing: 1.,
+            sigma: 0.5,
+            noise: 0.05,
+            wall_correction: 0.5,
+            landmark_correction: 0.2,
+            landmark_radius: 2.,
+        }
+    }
+
+    ///
+    /// Distance between place field centers.
+    ///
+    pub fn spacing(&mut self, spacing: f32) -> &mut Self {
+        assert!(spacing > 0.);
+
+        self.spacing = spacing;
+
+        self
+    }
+
+    ///
+    /// Width of the gaussian place fields.
+    ///
+    pub fn sigma(&mut self, sigma: f32) -> &mut Self {
+        assert!(sigma > 0.);
+
+        self.sigma = sigma;
+
+        self
+    }
+
+    ///
+    /// Odometry noise as a fraction of the step length.
+    ///
+    pub fn noise(&mut self, noise: f32) -> &mut Self {
+        assert!(noise >= 0.);
+
+        self.noise = noise;
+
+        self
+    }
+
+    ///
+    /// Fraction of the drift a wall contact corrects.
+    ///
+    pub fn wall_correction(&mut self, correction: f32) -> &mut Self {
+        assert!(0. <= correction && correction <= 1.);
+
+        self.wall_correction = correction;
+
+        self
+    }
+
+    ///
+    /// Fraction of the drift a landmark corrects and the search radius.
+    ///
+    pub fn landmark_correction(&mut self, correction: f32, radius: f32) -> &mut Self {
+        assert!(0. <= correction && correction <= 1.);
+        assert!(radius > 0.);
+
+        self.landmark_correction = correction;
+        self.landmark_radius = radius;
+
+        self
+    }
+
+    fn create_place_cells(&self, width: usize, height: usize, mut rand: Rand32) -> PlaceCells {
+        let nx = ((width as f32 / self.spacing).ceil() as usize).max(1);
+        let ny = ((height as f32 / self.spacing).ceil() as usize).max(1);
+
+        let mut centers = Vec::new();
+        let mut engrams = Vec::new();
+
+        for j in 0..ny {
+            for i in 0..nx {
+                centers.push(Point(
+                    (i as f32 + 0.5) * self.spacing,
+                    (j as f32 + 0.5) * self.spacing,
+                ));
+
+                engrams.push(Engram64::rand(&mut rand, 4, 6, 0));
+            }
+        }
+
+        let n = centers.len();
+
+        PlaceCells {
+            centers,
+            engrams,
+            sigma: self.sigma,
+
+            activity: vec![0.; n],
+            peak: None,
+
+            estimate: Point(0., 0.),
+            is_init: false,
+
+            noise: self.noise,
+            wall_correction: self.wall_correction,
+            landmark_correction: self.landmark_correction,
+            landmark_radius: self.landmark_radius,
+
+            rand,
+        }
+    }
+}
+
+impl Plugin for PlaceCellsPlugin {
+    fn build(&self, app: &mut App) {
+        assert!(app.contains_plugin::<BodyPlugin>(), "PlaceCells requires BodyPlugin");
+        assert!(app.contains_resource::<World>(), "PlaceCells requires World");
+
+        let (width, height) = app.resource::<World>().extent();
+        let rand = SeedPlugin::rand32(app, "place_cells");
+
+        app.insert_resource(self.create_place_cells(width, height, rand));
+
+        app.system(Tick, update_place_cells);
+    }
+}
+
+///
+/// Corrects place cell drift with the place kinds of a WorldHex.
+///
+pub struct PlaceLandmarkPlugin<K: WorldHexTrait + PartialEq> {
+    marker: PhantomData<fn(K)>,
+}
+
+impl<K: WorldHexTrait + PartialEq> PlaceLandmarkPlugin<K> {
+    pub fn new() -> Self {
+        Self {
+            marker: Default::default(),
+        }
+    }
+}
+
+impl<K: WorldHexTrait + PartialEq> Plugin for PlaceLandmarkPlugin<K> {
+    fn build(&self, app: &mut App) {
+        assert!(app.contains_plugin::<PlaceCellsPlugin>(), "PlaceLandmark requires PlaceCellsPlugin");
+        assert!(app.contains_resource::<WorldHex<K>>(), "PlaceLandmark requires WorldHex");
+
+        app.system(Tick, update_place_landmark::<K>);
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use util::random::Rand32;
+
+    use crate::{
+        mid_brain::SeekContext,
+        util::Point,
+        world::World,
+    };
+
+    use super::{wall_shift, PlaceCellsPlugin};
+
+    #[test]
+    fn place_cells_tiling() {
+        let mut place = PlaceCellsPlugin::new().create_place_cells(4, 3, Rand32::new());
+        assert_eq!(12, place.len());
+
+        place.estimate = Point(2.4, 1.6);
+        place.update_activity();
+
+        let peak = place.peak().unwrap();
+        assert_eq!(Point(2.5, 1.5), place.center(peak));
+        assert_eq!(place.engram(peak), place.context());
+
+        // landmark pulls the estimate toward the matching place
+        place.correct_landmark(|pos| pos.x() < 1.);
+        assert!(place.estimate().x() < 2.4);
+    }
+
+    #[test]
+    fn place_wall_shift() {
+        let world = World::new(10, 10);
+
+        // sensor contacts the east wall, but the estimate lags behind
+        let shift = wall_shift(&world, Point(8.9, 5.), Point(0.6, 0.)).unwrap();
+        assert!((shift.x() - 0.5).abs() < 0.06);
+        assert_eq!(0., shift.y());
+
+        assert!(wall_shift(&world, Point(9.5, 5.), Point(0.6, 0.)).is_none());
+    }
+}
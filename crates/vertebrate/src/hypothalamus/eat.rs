@@ -2,7 +2,7 @@ use essay_ecs::{app::{App, Plugin}, core::{Res, ResMut}};
 use mind_ecs::Tick;
 
 use crate::{
-    body::BodyEat, 
+    body::{BodyEat, Metabolism}, 
     hind_brain::{HindEat, HindEatPlugin, HindMove, Serotonin}, 
     hypothalamus::{Dwell, Eat, Motives, Sated}, 
     util::{Seconds, TimeoutValue}
@@ -237,11 +237,16 @@ fn update_eat(
     mut serotonin_eat: ResMut<Serotonin<HindEat>>,
     body_eat: Res<BodyEat>,
     sleep: Res<Sleep>,
+    metabolism: Option<Res<Metabolism>>,
 ) {
     motive_eat.pre_update();
 
     motive_eat.update_hunger(body_eat.get(), hind_eat.get(), hind_move.get(), sleep.get());
 
+    if let Some(metabolism) = metabolism {
+        motive_eat.update_energy(metabolism.get());
+    }
+
     if sleep.is_sleep()
     || ! motive_eat.is_food_zone()
     || motive_eat.is_alarm() {
@@ -362,6 +367,21 @@ impl HypEat {
         self.is_cgrp_sick.update();
     }
 
+    ///
+    /// H.arc AgRP also responds to the body's energy deficit, which
+    /// overrides the glucose-based satiety when starving.
+    ///
+    fn update_energy(&mut self, metabolism: &Metabolism) {
+        if metabolism.is_dead() {
+            self.is_arc_agrp.set(false);
+        } else if metabolism.is_starving() {
+            self.is_arc_agrp.set(true);
+            self.is_pv_sated.set(false);
+        }
+
+        self.sated = self.sated.min(1. - metabolism.deficit());
+    }
+
     fn update_hunger(
         &mut self,
         body_eat: &BodyEat,
@@ -6,6 +6,7 @@ pub mod hippocampus;
 pub mod hypothalamus;
 pub mod mid_brain;
 pub mod olfactory;
+pub mod recorder;
 pub mod retina;
 pub mod subpallium;
 pub mod ui;
@@ -0,0 +1,344 @@
+use std::{any::type_name, marker::PhantomData};
+
+use essay_ecs::{app::{App, Plugin}, core::{Res, ResMut}};
+use mind_ecs::Tick;
+
+use crate::{
+    body::{BodyEat, BodyEatPlugin},
+    hippocampus::Engram64,
+    hypothalamus::HypEat,
+    subpallium::StriatumReward,
+};
+
+use super::SeekContext;
+
+//
+// Dopamine reward prediction error: fish V.pt, mammal Snc/Vta
+//
+// [Ahn et al 2020] Gut (fat) -> N10 -> R.nts -> R.pb -> Snc.da
+// [Han W et al 2018] Gut -> N10 -> R.nts -> R.pb.dl -> Snc
+// [Schultz et al 1997] DA phasic burst for unexpected reward, dip for
+//   omitted reward, and response shifts to the predicting cue
+// [Tay et al 2011] fish V.pt DA neurons ascending to the subpallium
+//
+
+fn update_dopamine<C: SeekContext>(
+    mut dopamine: ResMut<Dopamine<C>>,
+    mut striatum: ResMut<StriatumReward<C>>,
+    body_eat: Res<BodyEat>,
+    hyp_eat: Option<Res<HypEat>>,
+    context: Res<C>,
+) {
+    let hunger = hyp_eat.map_or(1., |hyp_eat| hunger(hyp_eat.get()));
+
+    let reward = dopamine.reward(body_eat.get(), hunger);
+
+    dopamine.update(reward, context.context(), striatum.get_mut());
+}
+
+// H.arc AgRP is full hunger, otherwise hunger is the lack of satiety
+fn hunger(hyp_eat: &HypEat) -> f32 {
+    if hyp_eat.is_arc_agrp() {
+        1.
+    } else {
+        (1. - hyp_eat.sated()).clamp(0., 1.)
+    }
+}
+
+///
+/// Dopamine computes a temporal-difference reward prediction error from
+/// the primary reward of taste and gut signals and the striatum's learned
+/// value of the current and previous context.
+///
+/// The error is positive for an unexpected reward or a context better
+/// than predicted, and negative for bitter taste, sickness or a context
+/// that failed to deliver its predicted reward.
+///
+pub struct Dopamine<C> {
+    taste_gain: f32,
+    gut_gain: f32,
+    bitter_gain: f32,
+    sick_gain: f32,
+    sated_gain: f32,
+    discount: f32,
+
+    reward: f32,
+    rpe: f32,
+
+    last_context: Engram64,
+
+    marker: PhantomData<fn(C)>,
+}
+
+impl<C> Dopamine<C> {
+    pub const BURST : f32 = 0.1;
+
+    ///
+    /// Primary reward in [-1, 1] of the last tick.
+    ///
+    #[inline]
+    pub fn primary_reward(&self) -> f32 {
+        self.reward
+    }
+
+    ///
+    /// Reward prediction error of the last tick.
+    ///
+    #[inline]
+    pub fn rpe(&self) -> f32 {
+        self.rpe
+    }
+
+    ///
+    /// Phasic burst for a better than predicted outcome.
+    ///
+    #[inline]
+    pub fn is_burst(&self) -> bool {
+        self.rpe > Self::BURST
+    }
+
+    ///
+    /// Phasic dip for a worse than predicted outcome.
+    ///
+    #[inline]
+    pub fn is_dip(&self) -> bool {
+        self.rpe < - Self::BURST
+    }
+
+    fn reward(&self, body_eat: &BodyEat, hunger: f32) -> f32 {
+        let taste = body_eat.taste_food()
+            .max(body_eat.taste_sweet())
+            .max(body_eat.taste_umami());
+
+        let appetitive = self.taste_gain * taste + self.gut_gain * body_eat.gut_food();
+
+        let aversive = self.bitter_gain * body_eat.taste_bitter()
+            + self.sick_gain * body_eat.sickness();
+
+        // a sated animal finds food less rewarding
+        let hunger = 1. - self.sated_gain * (1. - hunger.clamp(0., 1.));
+
+        (hunger * appetitive - aversive).clamp(-1., 1.)
+    }
+
+    fn update(&mut self, reward: f32, context: Engram64, striatum: &mut StriatumReward<C>) {
+        self.reward = reward;
+
+        let value = striatum.value(context);
+
+        let last_context = self.last_context;
+        self.last_context = context;
+
+        // no context, such as no odor, has no learned value
+        if last_context == Engram64::default() {
+            self.rpe = reward;
+            return;
+        }
+
+        let last_value = striatum.value(last_context);
+
+        self.rpe = reward + self.discount * value - last_value;
+
+        striatum.learn(last_context, self.rpe);
+    }
+}
+
+pub struct DopaminePlugin<C: SeekContext> {
+    taste_gain: f32,
+    gut_gain: f32,
+    bitter_gain: f32,
+    sick_gain: f32,
+    sated_gain: f32,
+    discount: f32,
+
+    ltp: f32,
+    ltd: f32,
+    capacity: usize,
+
+    marker: PhantomData<fn(C)>,
+}
+
+impl<C: SeekContext> DopaminePlugin<C> {
+    pub fn new() -> Self {
+        Self {
+            taste_gain: 0.5,
+            gut_gain: 0.5,
+            bitter_gain: 1.,
+            sick_gain: 1.,
+            sated_gain: 0.75,
+            discount: 0.9,
+
+            ltp: 0.1,
+            ltd: 0.05,
+            capacity: 64,
+
+            marker: Default::default(),
+        }
+    }
+
+    ///
+    /// Reward for food taste.
+    ///
+    pub fn taste(&mut self, gain: f32) -> &mut Self {
+        assert!(gain >= 0.);
+
+        self.taste_gain = gain;
+
+        self
+    }
+
+    ///
+    /// Reward for food in the gut.
+    ///
+    pub fn gut(&mut self, gain: f32) -> &mut Self {
+        assert!(gain >= 0.);
+
+        self.gut_gain = gain;
+
+        self
+    }
+
+    ///
+    /// Punishment for bitter taste.
+    ///
+    pub fn bitter(&mut self, gain: f32) -> &mut Self {
+        assert!(gain >= 0.);
+
+        self.bitter_gain = gain;
+
+        self
+    }
+
+    ///
+    /// Punishment for gut sickness.
+    ///
+    pub fn sick(&mut self, gain: f32) -> &mut Self {
+        assert!(gain >= 0.);
+
+        self.sick_gain = gain;
+
+        self
+    }
+
+    ///
+    /// Reduction in [0, 1] of food reward when fully sated.
+    ///
+    pub fn sated(&mut self, gain: f32) -> &mut Self {
+        assert!(0. <= gain && gain <= 1.);
+
+        self.sated_gain = gain;
+
+        self
+    }
+
+    ///
+    /// Temporal-difference discount of the next context's value.
+    ///
+    pub fn discount(&mut self, discount: f32) -> &mut Self {
+        assert!(0. <= discount && discount < 1.);
+
+        self.discount = discount;
+
+        self
+    }
+
+    pub fn ltp(&mut self, rate: f32) -> &mut Self {
+        assert!(0. <= rate && rate <= 1.);
+
+        self.ltp = rate;
+
+        self
+    }
+
+    pub fn ltd(&mut self, rate: f32) -> &mut Self {
+        assert!(0. <= rate && rate <= 1.);
+
+        self.ltd = rate;
+
+        self
+    }
+
+    ///
+    /// Number of context values in the striatum, a power of 2.
+    ///
+    pub fn capacity(&mut self, capacity: usize) -> &mut Self {
+        assert!(capacity.count_ones() == 1, "capacity must be a power of 2: {}", capacity);
+
+        self.capacity = capacity;
+
+        self
+    }
+
+    fn create_dopamine(&self) -> Dopamine<C> {
+        Dopamine {
+            taste_gain: self.taste_gain,
+            gut_gain: self.gut_gain,
+            bitter_gain: self.bitter_gain,
+            sick_gain: self.sick_gain,
+            sated_gain: self.sated_gain,
+            discount: self.discount,
+
+            reward: 0.,
+            rpe: 0.,
+
+            last_context: Engram64::default(),
+
+            marker: Default::default(),
+        }
+    }
+
+    fn create_striatum(&self) -> StriatumReward<C> {
+        let mut striatum = StriatumReward::new(self.capacity);
+
+        striatum.ltp(self.ltp).ltd(self.ltd);
+
+        striatum
+    }
+}
+
+impl<C: SeekContext> Plugin for DopaminePlugin<C> {
+    fn build(&self, app: &mut App) {
+        assert!(app.contains_plugin::<BodyEatPlugin>(), "Dopamine requires BodyEatPlugin");
+        assert!(app.contains_resource::<C>(), "Dopamine requires context resource {}", type_name::<C>());
+
+        app.insert_resource(self.create_dopamine());
+        app.insert_resource(self.create_striatum());
+
+        app.system(Tick, update_dopamine::<C>);
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::{hippocampus::Engram64, olfactory::odor_place::OdorPlace};
+
+    use super::DopaminePlugin;
+
+    #[test]
+    fn dopamine_rpe_shifts_to_cue() {
+        let plugin = DopaminePlugin::<OdorPlace>::new();
+        let mut dopamine = plugin.create_dopamine();
+        let mut striatum = plugin.create_striatum();
+
+        let cue = Engram64(1);
+        let food = Engram64(2);
+
+        // first pairing: the food reward is unexpected
+        dopamine.update(0., cue, &mut striatum);
+        dopamine.update(1., food, &mut striatum);
+        assert!(dopamine.is_burst());
+
+        for _ in 0..50 {
+            dopamine.update(0., cue, &mut striatum);
+            dopamine.update(1., food, &mut striatum);
+        }
+
+        // the cue predicts the reward
+        assert!(striatum.value(cue) > 0.5);
+
+        // omitting the predicted reward is a dip
+        dopamine.update(0., cue, &mut striatum);
+        dopamine.update(0., Engram64(3), &mut striatum);
+        assert!(dopamine.is_dip());
+    }
+}
@@ -1,3 +1,4 @@
+mod dopamine;
 pub mod pretectum;
 mod mid_locomotor;
 mod seek;
@@ -5,6 +6,7 @@ mod seek_context;
 pub mod taxis;
 pub mod tectum;
 
+pub use dopamine::{Dopamine, DopaminePlugin};
 pub use mid_locomotor::{MidLocomotor, MidMovePlugin};
 pub use seek::{MidSeek, SeekInput, MidSeekPlugin};
 pub use seek_context::{SeekContext, MidSeekContext, MidSeekContextPlugin};
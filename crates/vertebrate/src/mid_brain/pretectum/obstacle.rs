@@ -1,15 +1,21 @@
-use essay_ecs::{app::{App, Plugin}, core::ResMut};
+use essay_ecs::{app::{App, Plugin}, core::{Res, ResMut}};
 use mind_ecs::Tick;
 
 use crate::{
-    hind_brain::HindMove, mid_brain::tectum::OrientTectum, util::{DecayValue, Ticks, Turn}
+    hind_brain::HindMove, hypothalamus::HypEat, mid_brain::tectum::OrientTectum, 
+    util::{DecayValue, Habituation, Seconds, Ticks, Turn}
 };
 
 fn obstacle_update(
     mut obstacle: ResMut<ObstaclePretectum>,
     mut orient: ResMut<OrientTectum>,
-    mut hind_move: ResMut<HindMove>
+    mut hind_move: ResMut<HindMove>,
+    hyp_eat: Option<Res<HypEat>>,
 ) {
+    if hyp_eat.map_or(false, |hyp_eat| hyp_eat.is_alarm()) {
+        obstacle.habituation.sensitize(1.);
+    }
+
     obstacle.update(orient.get_mut(), hind_move.get_mut());
 }
 
@@ -20,6 +26,9 @@ pub struct ObstaclePretectum {
     obstacle_left: DecayValue,
     obstacle_right: DecayValue,
 
+    // repeated obstacles on the same side habituate the escape
+    habituation: Habituation,
+
     is_enable: bool,
 }
 
@@ -30,6 +39,9 @@ impl ObstaclePretectum {
     const TURN : Turn = Turn::Unit(0.10);
     const _U_TURN : Turn = Turn::Unit(0.40);
 
+    const LEFT : usize = 0;
+    const RIGHT : usize = 1;
+
     fn new() -> Self {
         Self {
             threshold: Self::THRESHOLD,
@@ -38,6 +50,8 @@ impl ObstaclePretectum {
             obstacle_left: DecayValue::new(Ticks(2)),
             obstacle_right: DecayValue::new(Ticks(2)),
 
+            habituation: Habituation::new(2).decrement(0.1).recovery(Seconds(10.)),
+
             is_enable: false,
         }
     }
@@ -77,6 +91,9 @@ impl ObstaclePretectum {
         orient.set_obstacle_left(left);
         orient.set_obstacle_right(right);
 
+        let left = self.habituation.stimulus(Self::LEFT, left).min(1.);
+        let right = self.habituation.stimulus(Self::RIGHT, right).min(1.);
+
         if self.startle <= left {
             hind_move.startle().escape_left(left);
         }
@@ -97,6 +114,12 @@ impl ObstaclePretectum {
 
         self.obstacle_left.update();
         self.obstacle_right.update();
+        self.habituation.update();
+    }
+
+    #[inline]
+    pub fn habituation(&self) -> &Habituation {
+        &self.habituation
     }
 }
 
@@ -11,7 +11,7 @@ use crate::{
         MidSeekPlugin, 
     },
     hypothalamus::{Motive, MotiveAvoid, MotiveTrait, Motives}, 
-    subpallium::{StriatumTimeout, StriatumValue}, 
+    subpallium::{StriatumReward, StriatumTimeout, StriatumValue}, 
     
     util::{Seconds, Ticks}
 };
@@ -41,6 +41,7 @@ fn update_seek<I: SeekInput, C: SeekContext, M: MotiveTrait>(
     motive: Res<Motive<M>>,
     tick: Res<AppTick>,
     mut motive_seek: ResMut<Motive<Seek>>,
+    mut striatum: Option<ResMut<StriatumReward<C>>>,
 ) {
     // only act if motivated, such as Foraging
     if ! motive.is_active() {
@@ -50,8 +51,11 @@ fn update_seek<I: SeekInput, C: SeekContext, M: MotiveTrait>(
     if let Some(dir) = input.seek_dir() {
         let context = context.context();
 
+        // value learned from dopamine reward prediction error
+        let reward = striatum.as_mut().map_or(0., |striatum| striatum.value(context));
+
         // seek until timeout
-        match seek.update(context, tick.get()) {
+        match seek.update(context, reward, tick.get()) {
             StriatumValue::Active => {
                 motive_seek.set_max(1.);
         
@@ -77,6 +81,9 @@ pub struct MidSeekContext<I: SeekInput, C: SeekContext> {
 }
 
 impl<I: SeekInput, C: SeekContext> MidSeekContext<I, C> {
+    // learned value to prefer or avoid a context
+    const PREFER : f32 = 0.25;
+
     fn new() -> Self {
         Self {
             decay: Seconds(120.).into(),
@@ -91,7 +98,24 @@ impl<I: SeekInput, C: SeekContext> MidSeekContext<I, C> {
         self
     }
 
-    fn update(&mut self, context: Engram64, tick: &AppTick) -> StriatumValue {
+    fn update(&mut self, context: Engram64, reward: f32, tick: &AppTick) -> StriatumValue {
+        // contexts predicting bitter taste or sickness are avoided
+        if reward <= - Self::PREFER {
+            return StriatumValue::Avoid;
+        }
+
+        let value = self.update_timeout(context, tick);
+
+        // a context that paid off before overrides the give-up timeout
+        // until dopamine dips extinguish its value
+        if reward >= Self::PREFER {
+            StriatumValue::Active
+        } else {
+            value
+        }
+    }
+
+    fn update_timeout(&mut self, context: Engram64, tick: &AppTick) -> StriatumValue {
         if let Some(item) = self.items.iter_mut().find(|i| i.context == context) {
             if item.retain(tick) {
                 let value = item.timeout.active(tick);
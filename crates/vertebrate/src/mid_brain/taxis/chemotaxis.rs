@@ -2,15 +2,16 @@
 /// chemotaxis
 ///
 
-use essay_ecs::{prelude::{Plugin, App, ResMut, Res}, app::event::InEvent};
+use essay_ecs::prelude::{Plugin, App, ResMut, Res};
 use mind_ecs::Tick;
 
 use crate::{
     body::Body, 
     hind_brain::{HindMove, HindMovePlugin}, 
     hypothalamus::{Motive, MotiveTrait, Motives, Sated, Wake}, 
-    olfactory::olfactory_bulb::{ObEvent, OlfactoryBulb}, 
-    util::{Heading, Seconds} 
+    olfactory::olfactory_bulb::OlfactoryBulb, 
+    util::{EgoVector, Heading, Seconds}, 
+    world::{OdorInnate, OdorKind, OdorType}
 };
 
 pub struct Seek;
@@ -20,11 +21,17 @@ pub struct Avoid;
 impl MotiveTrait for Avoid {}
 
 
-use super::{habenula_seek::HabenulaSeekItem, Taxis};
+use super::{habenula_seek::HabenulaSeekItem, Taxis, TaxisPriority, TaxisSign};
 
 pub struct Chemotaxis {
     habenula: HabenulaSeekItem,
 
+    odors: Vec<OdorKind>,
+    sign: TaxisSign,
+    gain: f32,
+    threshold: f32,
+    priority: TaxisPriority,
+
     value: f32,
 }
 
@@ -34,16 +41,33 @@ impl Chemotaxis {
     pub fn new() -> Self {
         Self {
             habenula: HabenulaSeekItem::default(),
+            odors: Vec::new(),
+            sign: TaxisSign::Toward,
+            gain: 1.,
+            threshold: 0.01,
+            priority: TaxisPriority::Seek,
             value: 0.,
         }
     }
 
+    #[inline]
+    pub fn sign(&self) -> TaxisSign {
+        self.sign
+    }
+
+    #[inline]
+    pub fn odors(&self) -> &Vec<OdorKind> {
+        &self.odors
+    }
+
     pub fn pre_update(&mut self) {
         self.habenula.pre_update();
         self.value = 0.;
     }
 
     pub fn toward(&mut self, value: f32) {
+        let value = (self.gain * value).clamp(0., 1.);
+
         self.value += value;
         self.habenula.add(value);
     }
@@ -58,64 +82,198 @@ impl Chemotaxis {
         self.habenula.value()
     }
 
+    ///
+    /// Goal direction, up the odor gradient for positive chemotaxis and
+    /// down the gradient for negative chemotaxis.
+    ///
+    pub fn goal_vector(&self) -> EgoVector {
+        let vector = self.habenula.goal_vector();
+
+        match self.sign {
+            TaxisSign::Toward => vector,
+            TaxisSign::Away => {
+                EgoVector::new(Heading::Unit(vector.dir().to_unit() + 0.5), vector.value())
+            }
+        }
+    }
+
     pub fn update(
         &mut self, 
         head_dir: Heading,
-        _hind_move: &HindMove,
-        taxis: &mut ResMut<Taxis>,
+        hind_move: &mut HindMove,
+        taxis: &mut Taxis,
         seek_motive: &mut Motive<Seek>,
+        avoid_motive: &mut Motive<Avoid>,
     ) {
-        // update the light average
         self.habenula.update(head_dir);
     
-        let approach_vector = self.habenula.goal_vector();
-        let approach_ego = approach_vector.to_approach(head_dir);
+        let goal_vector = self.goal_vector();
+        let goal_ego = goal_vector.to_approach(head_dir);
 
-        if self.habenula.value() > 0.01 || approach_ego.value() > 0.05 {
-            seek_motive.set_max(1.);
-            // hind_move.turn(approach_ego.turn()); // TurnCommand::ApproachVector(approach_ego));
+        if self.habenula.value() > self.threshold || goal_ego.value() > 0.05 {
+            match self.sign {
+                TaxisSign::Toward => {
+                    seek_motive.set_max(1.);
+                    taxis.set_approach_dir(goal_vector);
+                }
+                TaxisSign::Away => {
+                    avoid_motive.set_max(1.);
+                    taxis.set_avoid_dir(goal_vector);
+                }
+            }
 
-            taxis.set_approach_dir(approach_vector);
-            todo!();
+            self.priority.turn(hind_move, goal_ego.turn());
+            self.priority.forward(hind_move);
         }
      }
 }
 
 fn update_chemotaxis(
     mut chemotaxis: ResMut<Chemotaxis>,
-    mut ob: InEvent<ObEvent>,
+    ob: Res<OlfactoryBulb>,
     body: Res<Body>,
-    hind_move: Res<HindMove>,
+    mut hind_move: ResMut<HindMove>,
     sated: Res<Motive<Sated>>,
-    wake: Res<Motive<Wake>>,
+    wake: Option<Res<Motive<Wake>>>,
     mut taxis: ResMut<Taxis>,
     mut seek_motive: ResMut<Motive<Seek>>,
+    mut avoid_motive: ResMut<Motive<Avoid>>,
 ) {
     chemotaxis.pre_update();
 
-    if ! wake.is_active() {
-        return;
+    if let Some(wake) = wake {
+        if ! wake.is_active() {
+            return;
+        }
     }
 
-    if sated.is_active() {
+    // a sated animal doesn't seek food odors, but still avoids
+    if chemotaxis.sign == TaxisSign::Toward && sated.is_active() {
         return;
     }
 
-    for event in ob.iter() {
-        match event {
-            ObEvent::Odor(_odor, vector) => {
-                chemotaxis.toward(vector.value());
-            },
-        }
+    for i in 0..chemotaxis.odors.len() {
+        let odor = chemotaxis.odors[i];
+
+        chemotaxis.toward(ob.value(odor));
     }
 
-    chemotaxis.update(body.head_dir(), hind_move.get(), &mut taxis, seek_motive.get_mut());
+    chemotaxis.update(
+        body.head_dir(), 
+        hind_move.get_mut(), 
+        taxis.get_mut(), 
+        seek_motive.get_mut(),
+        avoid_motive.get_mut(),
+    );
+}
+
+pub struct ChemotaxisPlugin {
+    is_enable: bool,
+    odors: Vec<OdorKind>,
+    sign: TaxisSign,
+    gain: f32,
+    threshold: f32,
+    priority: TaxisPriority,
 }
 
-pub struct ChemotaxisPlugin;
+impl ChemotaxisPlugin {
+    pub fn new() -> Self {
+        Self {
+            is_enable: true,
+            odors: Vec::new(),
+            sign: TaxisSign::Toward,
+            gain: 1.,
+            threshold: 0.01,
+            priority: TaxisPriority::Seek,
+        }
+    }
+
+    pub fn enable(&mut self, is_enable: bool) -> &mut Self {
+        self.is_enable = is_enable;
+
+        self
+    }
+
+    pub fn is_enable(&self) -> bool {
+        self.is_enable
+    }
+
+    ///
+    /// Odor to follow. Without odors, positive chemotaxis follows the
+    /// innate food odors and negative chemotaxis the innate avoid odors.
+    ///
+    pub fn odor(&mut self, odor: OdorKind) -> &mut Self {
+        self.odors.push(odor);
+
+        self
+    }
+
+    pub fn sign(&mut self, sign: TaxisSign) -> &mut Self {
+        self.sign = sign;
+
+        self
+    }
+
+    ///
+    /// Gain on the bulb's odor value.
+    ///
+    pub fn gain(&mut self, gain: f32) -> &mut Self {
+        assert!(gain > 0.);
+
+        self.gain = gain;
+
+        self
+    }
+
+    ///
+    /// Minimum odor value to orient.
+    ///
+    pub fn threshold(&mut self, threshold: f32) -> &mut Self {
+        assert!(threshold >= 0.);
+
+        self.threshold = threshold;
+
+        self
+    }
+
+    pub fn priority(&mut self, priority: TaxisPriority) -> &mut Self {
+        self.priority = priority;
+
+        self
+    }
+
+    fn create_chemotaxis(&self) -> Chemotaxis {
+        let odors = if self.odors.len() > 0 {
+            self.odors.clone()
+        } else {
+            let innate = match self.sign {
+                TaxisSign::Toward => OdorInnate::Food,
+                TaxisSign::Away => OdorInnate::Avoid,
+            };
+
+            [OdorKind::FoodA, OdorKind::FoodB, OdorKind::AvoidA, OdorKind::AvoidB]
+                .into_iter()
+                .filter(|odor| odor.innate() == innate)
+                .collect()
+        };
+
+        Chemotaxis {
+            odors,
+            sign: self.sign,
+            gain: self.gain,
+            threshold: self.threshold,
+            priority: self.priority,
+            ..Chemotaxis::new()
+        }
+    }
+}
 
 impl Plugin for ChemotaxisPlugin {
     fn build(&self, app: &mut App) {
+        if ! self.is_enable {
+            return;
+        }
+
         assert!(app.contains_plugin::<HindMovePlugin>(), "chemotaxis requires HindMovePlugin");
         
         assert!(app.contains_resource::<OlfactoryBulb>(), "chemotaxis requires OlfactoryBulb");
@@ -123,12 +281,76 @@ impl Plugin for ChemotaxisPlugin {
         app.init_resource::<Taxis>();
 
         Motives::insert::<Seek>(app, Seconds(0.5));
+        Motives::insert::<Avoid>(app, Seconds(0.2));
         Motives::init::<Sated>(app);
 
-        let chemotaxis = Chemotaxis::new();
-
-        app.insert_resource(chemotaxis);
+        app.insert_resource(self.create_chemotaxis());
 
         app.system(Tick, update_chemotaxis);
     }
 }
+
+#[cfg(test)]
+mod test {
+    use essay_ecs::{core::error::Result, prelude::Res};
+    use mind_ecs::MindApp;
+
+    use crate::{
+        body::{BodyEatPlugin, BodyPlugin}, 
+        hind_brain::{HindEatPlugin, HindMovePlugin}, 
+        hypothalamus::{Motive, MotiveSleepPlugin}, 
+        mid_brain::taxis::{Taxis, TaxisSign}, 
+        olfactory::olfactory_bulb::OlfactoryBulbPlugin, 
+        world::{OdorKind, OdorPlugin, WorldPlugin}
+    };
+
+    use super::{Avoid, Chemotaxis, ChemotaxisPlugin, Seek};
+
+    #[test]
+    fn chemotaxis_away() -> Result<()> {
+        let mut app = MindApp::test();
+        app.plugin(WorldPlugin::new(15, 15));
+        app.plugin(BodyPlugin::new().pos((5.5, 5.5)));
+        app.plugin(BodyEatPlugin::new());
+        app.plugin(HindEatPlugin::new());
+        app.plugin(MotiveSleepPlugin::new());
+        app.plugin(HindMovePlugin);
+
+        let mut odor = OdorPlugin::new();
+        odor.odor(7, 5, OdorKind::AvoidA);
+        app.plugin(odor);
+
+        let mut bulb = OlfactoryBulbPlugin::new();
+        bulb.odor(OdorKind::AvoidA);
+        app.plugin(bulb);
+
+        // negative chemotaxis defaults to the innate avoid odors
+        let mut chemotaxis = ChemotaxisPlugin::new();
+        chemotaxis.sign(TaxisSign::Away);
+        app.plugin(chemotaxis);
+
+        for _ in 0..5 {
+            app.tick()?;
+        }
+
+        assert!(app.eval(|x: Res<Motive<Avoid>>| x.is_active())?);
+        assert!(! app.eval(|x: Res<Motive<Seek>>| x.is_active())?);
+
+        assert!(app.eval(|x: Res<Taxis>| x.avoid_dir().value())? > 0.);
+        assert_eq!(0., app.eval(|x: Res<Taxis>| x.approach_dir().value())?);
+
+        Ok(())
+    }
+
+    #[test]
+    fn chemotaxis_disable() {
+        let mut app = MindApp::test();
+
+        // a disabled plugin doesn't require its dependencies
+        let mut chemotaxis = ChemotaxisPlugin::new();
+        chemotaxis.enable(false);
+        app.plugin(chemotaxis);
+
+        assert!(! app.contains_resource::<Chemotaxis>());
+    }
+}
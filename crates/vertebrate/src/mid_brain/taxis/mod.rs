@@ -6,10 +6,12 @@ pub mod habenula_seek;
 pub mod klinotaxis;
 
 pub mod phototaxis;
+pub mod rheotaxis;
+pub mod thermotaxis;
 
 
 pub use avoid::{TaxisAvoid, TaxisAvoidPlugin};
 
-pub use taxis::Taxis;
+pub use taxis::{Taxis, TaxisPriority, TaxisSign};
 
 pub use taxis_vector::GoalVector;
@@ -7,14 +7,19 @@ use mind_ecs::Tick;
 
 use crate::{
     body::Body, 
-    hind_brain::HindMove, 
+    hind_brain::{HindMove, HindMovePlugin}, 
     util::{DecayValue, EgoVector, HalfLife, Heading, Seconds}, 
     world::World
 };
 
-use super::{GoalVector, Taxis};
+use super::{GoalVector, Taxis, TaxisPriority, TaxisSign};
 
 pub struct Phototaxis {
+    sign: TaxisSign,
+    gain: f32,
+    threshold: f32,
+    priority: TaxisPriority,
+
     average: DecayValue,
     value: f32,
 
@@ -26,6 +31,11 @@ pub struct Phototaxis {
 
 impl Phototaxis {
     pub const N_DIR : usize = 12;
+
+    #[inline]
+    pub fn sign(&self) -> TaxisSign {
+        self.sign
+    }
     
     pub fn average(&self) -> f32 {
         self.average.value()
@@ -60,8 +70,8 @@ impl Phototaxis {
         self.short_average.update();
         self.short_average.add(value);
 
-        let gradient = self.short_gradient();
-        self.goal_vector.avoid(head_dir, gradient);
+        let gradient = self.gain * self.short_gradient();
+        self.goal_vector.approach(head_dir, gradient);
     }
 }
 
@@ -70,6 +80,11 @@ impl Default for Phototaxis {
         let half_life = HalfLife(4.);
 
         Self { 
+            sign: TaxisSign::Toward,
+            gain: 1.,
+            threshold: 0.05,
+            priority: TaxisPriority::Seek,
+
             // start with 20
             average: DecayValue::new(Seconds(4.)),
             short_average: DecayValue::new(Seconds(0.5)),
@@ -89,50 +104,171 @@ fn update_phototaxis(
     let light = world.light(body.head_pos());
 
     // TODO: negative light is an error value
-    if light >= 0.0 {
-        // update the light average
-        phototaxis.update(light, body.head_dir());
+    if light < 0.0 {
+        return;
     }
 
-    // the 5HT cells average the past light
-    // let average = phototaxis.average.value();
-
-    let diff = phototaxis.gradient();
+    // preference is the light for positive phototaxis and the dark for
+    // negative phototaxis (scototaxis)
+    let preference = match phototaxis.sign {
+        TaxisSign::Toward => light,
+        TaxisSign::Away => 1. - light,
+    };
 
-    let avoid_vector = phototaxis.goal_vector();
-    let avoid_ego = avoid_vector.to_ego(body.head_dir());
+    phototaxis.update(preference, body.head_dir());
 
-    let avoid_ego = avoid_ego.scale((-diff).clamp(0., 1.));
+    let diff = phototaxis.gain * phototaxis.short_gradient();
+    let priority = phototaxis.priority;
 
-    // hind_move.turn(TurnCommand::AvoidVector(avoid_ego));
-    hind_move.turn(avoid_ego.turn());
+    let approach_vector = phototaxis.goal_vector();
+    let approach_ego = approach_vector.to_approach(body.head_dir());
 
-    if diff >= 0.2 {
-        // positive gradient, move forward, avoiding the current area
-        // hind_move.avoid();
-        todo!();
-    } else if diff <= -0.2 {
-        // negative gradient, turn avound
-        // hind_move.turn(TurnCommand::AvoidUTurn);
-        todo!();
-    } else if light > 0.5 {
-        // hind_move.send_move(MoveCommand::Normal);
-    } else {
-        // hind_move.avoid(); // .send_move(MoveCommand::Avoid);
-        todo!();
+    if diff <= - phototaxis.threshold {
+        // leaving the preferred area, turn around
+        priority.turn(hind_move.get_mut(), approach_ego.turn());
+    } else if diff >= phototaxis.threshold {
+        // entering the preferred area, keep moving forward
+        priority.forward(hind_move.get_mut());
+    } else if preference < 0.5 {
+        // in the avoided area, move to leave it
+        priority.forward(hind_move.get_mut());
     }
 
-    let goal_vector = phototaxis.goal_vector();
-    taxis.set_avoid_dir(goal_vector);
+    taxis.set_approach_dir(approach_vector);
+}
+
+pub struct PhototaxisPlugin {
+    is_enable: bool,
+    sign: TaxisSign,
+    gain: f32,
+    threshold: f32,
+    priority: TaxisPriority,
 }
 
-pub struct PhototaxisPlugin;
+impl PhototaxisPlugin {
+    pub fn new() -> Self {
+        Self {
+            is_enable: true,
+            sign: TaxisSign::Toward,
+            gain: 1.,
+            threshold: 0.05,
+            priority: TaxisPriority::Seek,
+        }
+    }
+
+    pub fn enable(&mut self, is_enable: bool) -> &mut Self {
+        self.is_enable = is_enable;
+
+        self
+    }
+
+    pub fn is_enable(&self) -> bool {
+        self.is_enable
+    }
+
+    ///
+    /// Toward light for positive phototaxis, away for a dark preference.
+    ///
+    pub fn sign(&mut self, sign: TaxisSign) -> &mut Self {
+        self.sign = sign;
+
+        self
+    }
+
+    ///
+    /// Gain on the light gradient before the turn threshold.
+    ///
+    pub fn gain(&mut self, gain: f32) -> &mut Self {
+        assert!(gain > 0.);
+
+        self.gain = gain;
+
+        self
+    }
+
+    pub fn threshold(&mut self, threshold: f32) -> &mut Self {
+        assert!(threshold >= 0.);
+
+        self.threshold = threshold;
+
+        self
+    }
+
+    pub fn priority(&mut self, priority: TaxisPriority) -> &mut Self {
+        self.priority = priority;
+
+        self
+    }
+}
 
 impl Plugin for PhototaxisPlugin {
     fn build(&self, app: &mut App) {
-        app.init_resource::<Phototaxis>();
+        if ! self.is_enable {
+            return;
+        }
+
+        assert!(app.contains_plugin::<HindMovePlugin>(), "phototaxis requires HindMovePlugin");
+
+        app.insert_resource(Phototaxis {
+            sign: self.sign,
+            gain: self.gain,
+            threshold: self.threshold,
+            priority: self.priority,
+            ..Phototaxis::default()
+        });
         app.init_resource::<Taxis>();
 
         app.system(Tick, update_phototaxis);
     }
 }
+
+#[cfg(test)]
+mod test {
+    use essay_ecs::{core::error::Result, prelude::Res};
+    use mind_ecs::MindApp;
+
+    use crate::{
+        body::{BodyEatPlugin, BodyPlugin}, 
+        hind_brain::{HindEatPlugin, HindMovePlugin}, 
+        hypothalamus::MotiveSleepPlugin, 
+        mid_brain::taxis::TaxisSign, 
+        world::{FloorType, WorldPlugin}
+    };
+
+    use super::{Phototaxis, PhototaxisPlugin};
+
+    #[test]
+    fn phototaxis_dark_preference() -> Result<()> {
+        let mut app = MindApp::test();
+        app.plugin(WorldPlugin::new(10, 10).floor((0, 0), (5, 10), FloorType::Dark));
+        app.plugin(BodyPlugin::new().pos((2.5, 5.)));
+        app.plugin(BodyEatPlugin::new());
+        app.plugin(HindEatPlugin::new());
+        app.plugin(MotiveSleepPlugin::new());
+        app.plugin(HindMovePlugin);
+
+        let mut phototaxis = PhototaxisPlugin::new();
+        phototaxis.sign(TaxisSign::Away);
+        app.plugin(phototaxis);
+
+        app.tick()?;
+
+        // the dark floor is the preferred area for negative phototaxis
+        assert_eq!(TaxisSign::Away, app.eval(|x: Res<Phototaxis>| x.sign())?);
+        assert_eq!(1., app.eval(|x: Res<Phototaxis>| x.value())?);
+
+        Ok(())
+    }
+
+    #[test]
+    fn phototaxis_disable() {
+        let mut app = MindApp::test();
+
+        // a disabled plugin doesn't require its dependencies
+        let mut phototaxis = PhototaxisPlugin::new();
+        phototaxis.enable(false);
+        app.plugin(phototaxis);
+
+        assert!(! app.contains_resource::<Phototaxis>());
+    }
+}
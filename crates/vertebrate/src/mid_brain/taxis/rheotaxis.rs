@@ -0,0 +1,248 @@
+///
+/// rheotaxis
+///
+
+use std::f32::consts::TAU;
+
+use essay_ecs::prelude::*;
+use mind_ecs::Tick;
+
+use crate::{
+    hind_brain::{lateral_line::{LateralLine, LateralLine2Plugin}, HindMove, HindMovePlugin},
+    hypothalamus::{Motive, Wake},
+    util::{HalfLife, Point, Seconds, Turn},
+};
+
+///
+/// Rheotaxis orients to a water current sensed by the lateral line.
+/// Positive rheotaxis turns into the current and swims to hold position,
+/// and negative rheotaxis turns downstream.
+///
+pub struct Rheotaxis {
+    mode: RheotaxisMode,
+    threshold: f32,
+    is_hold: bool,
+
+    // smoothed flow in body coordinates (forward, right)
+    flow: Point,
+    decay: f32,
+}
+
+impl Rheotaxis {
+    // turns smaller than this are aligned with the current
+    pub const ALIGN : f32 = 0.05;
+
+    #[inline]
+    pub fn mode(&self) -> RheotaxisMode {
+        self.mode
+    }
+
+    ///
+    /// Sensed current in body coordinates (forward, right).
+    ///
+    #[inline]
+    pub fn flow(&self) -> Point {
+        self.flow
+    }
+
+    #[inline]
+    pub fn speed(&self) -> f32 {
+        self.flow.x().hypot(self.flow.y())
+    }
+
+    #[inline]
+    pub fn is_active(&self) -> bool {
+        self.speed() > self.threshold
+    }
+
+    ///
+    /// Turn toward the goal direction, upstream for positive rheotaxis.
+    ///
+    pub fn turn(&self) -> Turn {
+        // flow direction clockwise from forward
+        let flow_dir = self.flow.y().atan2(self.flow.x()) / TAU;
+
+        match self.mode {
+            RheotaxisMode::Positive => Turn::unit(flow_dir + 0.5),
+            RheotaxisMode::Negative => Turn::unit(flow_dir),
+        }
+    }
+
+    fn update(&mut self, lateral_line: &LateralLine) {
+        let head = lateral_line.head_flow();
+        let tail = lateral_line.tail_flow();
+
+        let mean = Point(0.5 * (head.x() + tail.x()), 0.5 * (head.y() + tail.y()));
+
+        self.flow = self.decay * self.flow + (1. - self.decay) * mean;
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum RheotaxisMode {
+    Positive,
+    Negative,
+}
+
+fn update_rheotaxis(
+    mut rheotaxis: ResMut<Rheotaxis>,
+    lateral_line: Res<LateralLine>,
+    wake: Option<Res<Motive<Wake>>>,
+    mut hind_move: ResMut<HindMove>,
+) {
+    rheotaxis.update(lateral_line.get());
+
+    if let Some(wake) = wake {
+        if ! wake.is_active() {
+            return;
+        }
+    }
+
+    if ! rheotaxis.is_active() {
+        return;
+    }
+
+    let turn = rheotaxis.turn();
+
+    if turn.to_unit().abs() > Rheotaxis::ALIGN {
+        hind_move.turn(turn);
+    } else if rheotaxis.is_hold && rheotaxis.mode == RheotaxisMode::Positive {
+        // swim against the current to hold position
+        hind_move.seek();
+    }
+}
+
+pub struct RheotaxisPlugin {
+    mode: RheotaxisMode,
+    threshold: f32,
+    is_hold: bool,
+    half_life: Seconds,
+}
+
+impl RheotaxisPlugin {
+    pub fn new() -> Self {
+        Self {
+            mode: RheotaxisMode::Positive,
+            threshold: 0.1,
+            is_hold: true,
+            half_life: Seconds(0.5),
+        }
+    }
+
+    pub fn mode(&mut self, mode: RheotaxisMode) -> &mut Self {
+        self.mode = mode;
+
+        self
+    }
+
+    ///
+    /// Minimum sensed current in world units per second.
+    ///
+    pub fn threshold(&mut self, threshold: f32) -> &mut Self {
+        assert!(threshold >= 0.);
+
+        self.threshold = threshold;
+
+        self
+    }
+
+    pub fn hold(&mut self, is_hold: bool) -> &mut Self {
+        self.is_hold = is_hold;
+
+        self
+    }
+
+    ///
+    /// Smoothing of the sensed current.
+    ///
+    pub fn half_life(&mut self, half_life: impl Into<Seconds>) -> &mut Self {
+        self.half_life = half_life.into();
+
+        self
+    }
+}
+
+impl Plugin for RheotaxisPlugin {
+    fn build(&self, app: &mut App) {
+        assert!(app.contains_plugin::<HindMovePlugin>(), "rheotaxis requires HindMovePlugin");
+        assert!(app.contains_plugin::<LateralLine2Plugin>(), "rheotaxis requires LateralLine");
+
+        let half_life: HalfLife = self.half_life.into();
+
+        app.insert_resource(Rheotaxis {
+            mode: self.mode,
+            threshold: self.threshold,
+            is_hold: self.is_hold,
+
+            flow: Point(0., 0.),
+            decay: half_life.decay(),
+        });
+
+        app.system(Tick, update_rheotaxis);
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use essay_ecs::{core::error::Result, prelude::Res};
+    use mind_ecs::MindApp;
+
+    use crate::{
+        body::{Body, BodyEatPlugin, BodyPlugin},
+        hind_brain::{lateral_line::LateralLine2Plugin, HindEatPlugin, HindMovePlugin},
+        hypothalamus::MotiveSleepPlugin,
+        util::Point,
+        world::{FlowFieldPlugin, WorldPlugin},
+    };
+
+    use super::{Rheotaxis, RheotaxisMode, RheotaxisPlugin};
+
+    #[test]
+    fn rheotaxis_turn() {
+        let mut rheotaxis = Rheotaxis {
+            mode: RheotaxisMode::Positive,
+            threshold: 0.1,
+            is_hold: true,
+            flow: Point(-1., 0.),
+            decay: 0.5,
+        };
+
+        // head-on current is aligned
+        assert!(rheotaxis.turn().to_unit().abs() < 1e-5);
+
+        // current flowing to the right comes from the left
+        rheotaxis.flow = Point(0., 1.);
+        assert!((rheotaxis.turn().to_unit() + 0.25).abs() < 1e-5);
+
+        rheotaxis.mode = RheotaxisMode::Negative;
+        assert!((rheotaxis.turn().to_unit() - 0.25).abs() < 1e-5);
+    }
+
+    #[test]
+    fn rheotaxis_upstream() -> Result<()> {
+        let mut app = MindApp::test();
+        app.plugin(WorldPlugin::new(30, 30));
+        app.plugin(BodyPlugin::new().pos((15., 15.)));
+        app.plugin(BodyEatPlugin::new());
+        app.plugin(HindEatPlugin::new());
+        app.plugin(MotiveSleepPlugin::new());
+        app.plugin(HindMovePlugin);
+        app.plugin(LateralLine2Plugin);
+        app.plugin(RheotaxisPlugin::new());
+
+        // eastward current across the north-facing body
+        app.plugin(FlowFieldPlugin::new().uniform(1., 0.));
+
+        for _ in 0..50 {
+            app.tick()?;
+        }
+
+        assert!(app.eval(|x: Res<Rheotaxis>| x.is_active())?);
+
+        // the body turns west, into the current
+        let (sin, cos) = app.eval(|x: Res<Body>| x.dir().sin_cos())?;
+        assert!(cos < -0.7, "forward ({}, {})", cos, sin);
+
+        Ok(())
+    }
+}
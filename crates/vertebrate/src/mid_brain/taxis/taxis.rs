@@ -1,4 +1,4 @@
-use crate::util::EgoVector;
+use crate::{hind_brain::HindMove, util::{EgoVector, Turn}};
 
 pub struct Taxis {
     approach_dir: EgoVector,
@@ -30,4 +30,42 @@ impl Default for Taxis {
             avoid_dir: EgoVector::zero(),
         }
     }
-}
\ No newline at end of file
+}
+
+///
+/// Taxis sign: toward the stimulus (positive) or away (negative).
+///
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum TaxisSign {
+    Toward,
+    Away,
+}
+
+///
+/// Taxis priority relative to the other HindMove requests. Roam defers
+/// to seek and avoid requests and to turns already requested, while seek
+/// and avoid override roaming.
+///
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum TaxisPriority {
+    Roam,
+    Seek,
+    Avoid,
+}
+
+impl TaxisPriority {
+    pub(super) fn forward(&self, hind_move: &mut HindMove) {
+        match self {
+            TaxisPriority::Roam => hind_move.roam(),
+            TaxisPriority::Seek => hind_move.seek(),
+            TaxisPriority::Avoid => hind_move.avoid(),
+        }
+    }
+
+    pub(super) fn turn(&self, hind_move: &mut HindMove, turn: Turn) {
+        match self {
+            TaxisPriority::Roam => hind_move.turn_if_new(turn),
+            _ => hind_move.turn(turn),
+        }
+    }
+}
@@ -0,0 +1,320 @@
+///
+/// thermotaxis
+///
+
+use essay_ecs::prelude::*;
+use mind_ecs::Tick;
+
+use crate::{
+    body::{Body, Metabolism, Thermosensor, ThermosensorPlugin},
+    hind_brain::{HindMove, HindMovePlugin},
+    hypothalamus::{Motive, Sleep, Wake},
+    util::{DecayValue, EgoVector, HalfLife, Heading, Seconds},
+};
+
+use super::{GoalVector, Taxis};
+
+///
+/// Thermotaxis moves toward a preferred temperature. Discomfort is the
+/// distance from the set point, and a rising short average compared to
+/// the long average turns the animal away, while a falling one keeps it
+/// swimming forward.
+///
+/// The set point shifts with sleep and hunger, so a sleeping or starved
+/// animal prefers cooler water.
+///
+pub struct Thermotaxis {
+    set_point: f32,
+    tolerance: f32,
+    sleep_shift: f32,
+    hunger_shift: f32,
+    threshold: f32,
+
+    target: f32,
+    value: f32,
+
+    average: DecayValue,
+    short_average: DecayValue,
+
+    goal_vector: GoalVector,
+}
+
+impl Thermotaxis {
+    ///
+    /// Base preferred temperature in degrees C.
+    ///
+    #[inline]
+    pub fn set_point(&self) -> f32 {
+        self.set_point
+    }
+
+    ///
+    /// Preferred temperature after the sleep and hunger shifts.
+    ///
+    #[inline]
+    pub fn target(&self) -> f32 {
+        self.target
+    }
+
+    ///
+    /// Discomfort in [0, 1] for the current temperature.
+    ///
+    #[inline]
+    pub fn value(&self) -> f32 {
+        self.value
+    }
+
+    #[inline]
+    pub fn average(&self) -> f32 {
+        self.average.value()
+    }
+
+    #[inline]
+    pub fn short_average(&self) -> f32 {
+        self.short_average.value()
+    }
+
+    ///
+    /// Positive when the discomfort is increasing.
+    ///
+    #[inline]
+    pub fn gradient(&self) -> f32 {
+        self.short_average() - self.average()
+    }
+
+    fn goal_vector(&self) -> EgoVector {
+        self.goal_vector.to_vector()
+    }
+
+    fn discomfort(&self, temperature: f32) -> f32 {
+        ((temperature - self.target).abs() / self.tolerance).clamp(0., 1.)
+    }
+
+    fn update(&mut self, temperature: f32, sleep: f32, hunger: f32, head_dir: Heading) {
+        self.target = self.set_point
+            + self.sleep_shift * sleep.clamp(0., 1.)
+            + self.hunger_shift * hunger.clamp(0., 1.);
+
+        self.value = self.discomfort(temperature);
+
+        self.average.update();
+        self.average.add(self.value);
+
+        self.short_average.update();
+        self.short_average.add(self.value);
+
+        let gradient = self.gradient();
+        self.goal_vector.avoid(head_dir, gradient);
+    }
+}
+
+fn update_thermotaxis(
+    mut thermotaxis: ResMut<Thermotaxis>,
+    thermosensor: Res<Thermosensor>,
+    body: Res<Body>,
+    sleep: Option<Res<Motive<Sleep>>>,
+    wake: Option<Res<Motive<Wake>>>,
+    metabolism: Option<Res<Metabolism>>,
+    mut hind_move: ResMut<HindMove>,
+    mut taxis: ResMut<Taxis>,
+) {
+    if let Some(wake) = wake {
+        if ! wake.is_active() {
+            return;
+        }
+    }
+
+    let sleep = sleep.map_or(0., |sleep| sleep.value());
+    let hunger = metabolism.map_or(0., |metabolism| metabolism.deficit());
+
+    thermotaxis.update(thermosensor.value(), sleep, hunger, body.head_dir());
+
+    let diff = thermotaxis.gradient();
+
+    if diff >= thermotaxis.threshold {
+        // warming or cooling away from the set point, turn away
+        let avoid_ego = thermotaxis.goal_vector().to_ego(body.head_dir());
+
+        hind_move.turn(avoid_ego.turn());
+        taxis.set_avoid_dir(thermotaxis.goal_vector());
+    } else if diff <= - thermotaxis.threshold {
+        // approaching the set point, keep swimming forward
+        hind_move.seek();
+    }
+}
+
+pub struct ThermotaxisPlugin {
+    set_point: f32,
+    tolerance: f32,
+    sleep_shift: f32,
+    hunger_shift: f32,
+    threshold: f32,
+}
+
+impl ThermotaxisPlugin {
+    pub fn new() -> Self {
+        Self {
+            set_point: 26.,
+            tolerance: 4.,
+            sleep_shift: -1.,
+            hunger_shift: -2.,
+            threshold: 0.01,
+        }
+    }
+
+    ///
+    /// Preferred temperature in degrees C.
+    ///
+    pub fn set_point(&mut self, set_point: f32) -> &mut Self {
+        self.set_point = set_point;
+
+        self
+    }
+
+    ///
+    /// Distance from the set point in degrees C for full discomfort.
+    ///
+    pub fn tolerance(&mut self, tolerance: f32) -> &mut Self {
+        assert!(tolerance > 0.);
+
+        self.tolerance = tolerance;
+
+        self
+    }
+
+    ///
+    /// Set point shift in degrees C when fully asleep.
+    ///
+    pub fn sleep_shift(&mut self, shift: f32) -> &mut Self {
+        self.sleep_shift = shift;
+
+        self
+    }
+
+    ///
+    /// Set point shift in degrees C when fully starved.
+    ///
+    pub fn hunger_shift(&mut self, shift: f32) -> &mut Self {
+        self.hunger_shift = shift;
+
+        self
+    }
+
+    ///
+    /// Minimum discomfort gradient to turn or swim forward.
+    ///
+    pub fn threshold(&mut self, threshold: f32) -> &mut Self {
+        assert!(threshold >= 0.);
+
+        self.threshold = threshold;
+
+        self
+    }
+
+    fn create_thermotaxis(&self) -> Thermotaxis {
+        Thermotaxis {
+            set_point: self.set_point,
+            tolerance: self.tolerance,
+            sleep_shift: self.sleep_shift,
+            hunger_shift: self.hunger_shift,
+            threshold: self.threshold,
+
+            target: self.set_point,
+            value: 0.,
+
+            average: DecayValue::new(Seconds(4.)),
+            short_average: DecayValue::new(Seconds(0.5)),
+
+            goal_vector: GoalVector::new(HalfLife(4.)),
+        }
+    }
+}
+
+impl Plugin for ThermotaxisPlugin {
+    fn build(&self, app: &mut App) {
+        assert!(app.contains_plugin::<HindMovePlugin>(), "thermotaxis requires HindMovePlugin");
+        assert!(app.contains_plugin::<ThermosensorPlugin>(), "thermotaxis requires ThermosensorPlugin");
+
+        app.insert_resource(self.create_thermotaxis());
+        app.init_resource::<Taxis>();
+
+        app.system(Tick, update_thermotaxis);
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use essay_ecs::{core::error::Result, prelude::{Res, ResMut}};
+    use mind_ecs::MindApp;
+
+    use crate::{
+        body::{BodyEatPlugin, BodyPlugin, ThermosensorPlugin}, 
+        hind_brain::{HindEatPlugin, HindMovePlugin}, 
+        hypothalamus::MotiveSleepPlugin, 
+        mid_brain::taxis::Taxis, 
+        util::{EgoVector, Heading}, 
+        world::{TemperaturePlugin, WorldPlugin}
+    };
+
+    use super::ThermotaxisPlugin;
+
+    #[test]
+    fn thermotaxis_set_point() {
+        let mut thermotaxis = ThermotaxisPlugin::new()
+            .set_point(26.)
+            .tolerance(4.)
+            .sleep_shift(-1.)
+            .hunger_shift(-2.)
+            .create_thermotaxis();
+
+        thermotaxis.update(26., 0., 0., Heading::Unit(0.));
+        assert_eq!(26., thermotaxis.target());
+        assert_eq!(0., thermotaxis.value());
+
+        thermotaxis.update(26., 1., 1., Heading::Unit(0.));
+        assert_eq!(23., thermotaxis.target());
+        assert_eq!(0.75, thermotaxis.value());
+
+        // warming away from the set point increases the discomfort gradient
+        for i in 0..10 {
+            thermotaxis.update(26. + 0.1 * i as f32, 1., 1., Heading::Unit(0.));
+        }
+
+        assert!(thermotaxis.gradient() > 0.);
+    }
+
+    #[test]
+    fn thermotaxis_keeps_avoid_dir() -> Result<()> {
+        let mut app = MindApp::test();
+        app.plugin(WorldPlugin::new(15, 15));
+        app.plugin(BodyPlugin::new().pos((5., 5.)));
+        app.plugin(BodyEatPlugin::new());
+        app.plugin(HindEatPlugin::new());
+        app.plugin(MotiveSleepPlugin::new());
+        app.plugin(HindMovePlugin);
+        app.plugin(TemperaturePlugin::new().base(26.));
+        app.plugin(ThermosensorPlugin);
+        app.plugin(ThermotaxisPlugin::new());
+
+        // let the sensor's first reading settle
+        for _ in 0..10 {
+            app.tick()?;
+        }
+
+        // avoid direction from another taxis, like chemotaxis
+        app.eval(|mut x: ResMut<Taxis>| {
+            x.set_avoid_dir(EgoVector::new(Heading::Unit(0.25), 1.))
+        })?;
+
+        for _ in 0..5 {
+            app.tick()?;
+        }
+
+        // at the set point, thermotaxis doesn't overwrite the avoid direction
+        let avoid = app.eval(|x: Res<Taxis>| x.avoid_dir())?;
+        assert!((avoid.dir().to_unit() - 0.25).abs() < 1e-4);
+        assert_eq!(1., avoid.value());
+
+        Ok(())
+    }
+}
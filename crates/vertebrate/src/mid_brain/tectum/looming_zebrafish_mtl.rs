@@ -1,7 +1,10 @@
 use essay_ecs::{app::App, core::{Res, ResMut}};
 use mind_ecs::Tick;
 
-use crate::{hind_brain::HindMove, retina::Retina, util::{DecayValue, Seconds, Turn}};
+use crate::{
+    hind_brain::HindMove, hypothalamus::HypEat, retina::Retina, 
+    util::{DecayValue, Habituation, Seconds, Turn}
+};
 
 use super::looming::LoomingStrategy;
 
@@ -11,10 +14,15 @@ use super::looming::LoomingStrategy;
 fn looming_update(
     mut looming: ResMut<Looming>, 
     retina: Res<Retina>,
-    mut hind_move: ResMut<HindMove>
+    mut hind_move: ResMut<HindMove>,
+    hyp_eat: Option<Res<HypEat>>,
 ) {
     looming.update();
 
+    if hyp_eat.map_or(false, |hyp_eat| hyp_eat.is_alarm()) {
+        looming.habituation.sensitize(1.);
+    }
+
     looming.dim_left.add(retina.dim_left());
     looming.dim_right.add(retina.dim_right());
 
@@ -31,12 +39,16 @@ fn looming_update(
         let sum_dim = left_dim.max(0.) + right_dim.max(0.);
         
         if sum_dim * 0.75 < left_dim {
-            hind_move.optic().escape(Looming::TURN);
-            hind_move.set_ss_left(0.75);
+            if looming.is_escape(Looming::LEFT) {
+                hind_move.optic().escape(Looming::TURN);
+                hind_move.set_ss_left(0.75);
+            }
         } else if sum_dim * 0.75 < right_dim {
-            hind_move.optic().escape(- Looming::TURN);
-            hind_move.set_ss_right(0.75);
-        } else {
+            if looming.is_escape(Looming::RIGHT) {
+                hind_move.optic().escape(- Looming::TURN);
+                hind_move.set_ss_right(0.75);
+            }
+        } else if looming.is_escape(Looming::FORWARD) {
             if left_dim < right_dim {
                 hind_move.optic().u_turn(- Looming::U_TURN);
             } else {
@@ -64,6 +76,9 @@ struct Looming {
     light_left: DecayValue,
     /// Retina light level averaged for a short time
     light_right: DecayValue,
+
+    /// Repeated looms from the same direction habituate the escape
+    habituation: Habituation,
 }
 
 impl Looming {
@@ -74,6 +89,13 @@ impl Looming {
     const TURN : Turn = Turn::Unit(0.20);
     const U_TURN : Turn = Turn::Unit(0.40);
 
+    const LEFT : usize = 0;
+    const RIGHT : usize = 1;
+    const FORWARD : usize = 2;
+
+    // habituated response for an escape
+    const ESCAPE : f32 = 0.5;
+
     fn new() -> Self {
         Self {
             threshold: Self::THRESHOLD,
@@ -85,6 +107,8 @@ impl Looming {
 
             light_left: DecayValue::new(Self::AVG_TIME).fill_decay(),
             light_right: DecayValue::new(Self::AVG_TIME).fill_decay(),
+
+            habituation: Habituation::new(3).decrement(0.1),
         }
     }
 
@@ -95,6 +119,12 @@ impl Looming {
         self.light_mid.update();
         self.light_left.update();
         self.light_right.update();
+
+        self.habituation.update();
+    }
+
+    fn is_escape(&mut self, side: usize) -> bool {
+        self.habituation.stimulus(side, 1.) >= Self::ESCAPE
     }
 
     fn is_looming(&self) -> bool {
@@ -1,6 +1,8 @@
 mod odor_cortex;
+pub mod odor_conditioning;
 pub mod olfactory_bulb;
 pub mod odor_place;
+pub mod receptor;
 
 pub use odor_cortex::{OdorCortex, OlfactoryCortexPlugin};
 // pub use olfactory_context::{OlfactoryCortex, OlfactoryCortexPlugin};
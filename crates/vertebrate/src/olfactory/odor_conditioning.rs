@@ -0,0 +1,353 @@
+use std::collections::HashMap;
+
+use essay_ecs::{app::{App, Plugin}, core::{Res, ResMut}};
+use mind_ecs::Tick;
+
+use crate::{
+    body::{BodyEat, BodyEatPlugin},
+    hind_brain::HindEat,
+    world::{FoodKind, OdorKind},
+};
+
+use super::olfactory_bulb::{innate_valence, OlfactoryBulb, OlfactoryBulbPlugin};
+
+//
+// Pavlovian odor-taste conditioning
+//
+// [Bures et al 1998] Conditioned taste aversion acquired in one trial
+//   with delays of hours between taste and illness
+// [Parker 2003] Conditioned gaping to a taste paired with LiCl
+// [Rescorla and Wagner 1972] Associative strength changes by the
+//   prediction error of the outcome
+//
+
+fn update_odor_conditioning(
+    mut conditioning: ResMut<OdorConditioning>,
+    body_eat: Res<BodyEat>,
+    mut bulb: ResMut<OlfactoryBulb>,
+    hind_eat: Option<ResMut<HindEat>>,
+) {
+    // the digested food pairs the odors at eating with the delayed outcome
+    for odor in body_eat.digested_odors() {
+        conditioning.pair(*odor, body_eat.digested());
+    }
+
+    if ! body_eat.is_eating() {
+        // odor exposure without the outcome extinguishes the learning
+        let odors: Vec<OdorKind> = conditioning.odors().collect();
+
+        for odor in odors {
+            if bulb.value(odor) > conditioning.threshold {
+                conditioning.extinguish(odor);
+            }
+        }
+    } else if let Some(mut hind_eat) = hind_eat {
+        // eating while a learned aversive odor is present gapes
+        let odors: Vec<OdorKind> = conditioning.odors().collect();
+
+        for odor in odors {
+            if bulb.value(odor) > conditioning.threshold
+            && conditioning.valence(odor).map_or(false, |v| v <= conditioning.gape) {
+                hind_eat.gape();
+            }
+        }
+    }
+
+    for (odor, valence) in conditioning.valences() {
+        bulb.set_valence(odor, valence);
+    }
+}
+
+///
+/// OdorConditioning is a learned valence table for odors. An odor is
+/// paired with the outcome of the food eaten with it, which arrives after
+/// the gut delay: sweet and plain food are positive, bitter and sickness
+/// are negative.
+///
+/// Learning follows the outcome's prediction error, so repeated pairing
+/// acquires the valence, odor exposure without an outcome extinguishes it
+/// back to the innate valence, and a new outcome reverses it. Aversive
+/// learning is faster, giving one-trial conditioned taste aversion.
+///
+pub struct OdorConditioning {
+    appetitive: f32,
+    aversive: f32,
+    extinction: f32,
+    threshold: f32,
+    gape: f32,
+
+    outcomes: [f32; 6],
+
+    table: HashMap<OdorKind, f32>,
+}
+
+impl OdorConditioning {
+    ///
+    /// Learned valence in [-1, 1], None for an unconditioned odor.
+    ///
+    pub fn valence(&self, odor: OdorKind) -> Option<f32> {
+        self.table.get(&odor).map(|v| *v)
+    }
+
+    pub fn odors(&self) -> impl Iterator<Item=OdorKind> + '_ {
+        self.table.keys().map(|odor| *odor)
+    }
+
+    pub fn valences(&self) -> impl Iterator<Item=(OdorKind, f32)> + '_ {
+        self.table.iter().map(|(odor, valence)| (*odor, *valence))
+    }
+
+    ///
+    /// Outcome value of digested food.
+    ///
+    pub fn outcome(&self, kind: FoodKind) -> f32 {
+        self.outcomes[food_index(kind)]
+    }
+
+    fn pair(&mut self, odor: OdorKind, kind: FoodKind) {
+        if kind == FoodKind::None {
+            return;
+        }
+
+        let outcome = self.outcome(kind);
+        let valence = self.get_or_innate(odor);
+
+        let error = outcome - valence;
+
+        let rate = if error < 0. { self.aversive } else { self.appetitive };
+
+        self.table.insert(odor, (valence + rate * error).clamp(-1., 1.));
+    }
+
+    fn extinguish(&mut self, odor: OdorKind) {
+        if let Some(valence) = self.table.get_mut(&odor) {
+            *valence += self.extinction * (innate_valence(odor) - *valence);
+        }
+    }
+
+    fn get_or_innate(&self, odor: OdorKind) -> f32 {
+        self.valence(odor).unwrap_or_else(|| innate_valence(odor))
+    }
+}
+
+fn food_index(kind: FoodKind) -> usize {
+    match kind {
+        FoodKind::None => 0,
+        FoodKind::Poor => 1,
+        FoodKind::Plain => 2,
+        FoodKind::Sweet => 3,
+        FoodKind::Bitter => 4,
+        FoodKind::Sick => 5,
+    }
+}
+
+pub struct OdorConditioningPlugin {
+    appetitive: f32,
+    aversive: f32,
+    extinction: f32,
+    threshold: f32,
+    gape: f32,
+
+    outcomes: [f32; 6],
+}
+
+impl OdorConditioningPlugin {
+    pub fn new() -> Self {
+        let mut plugin = Self {
+            appetitive: 0.3,
+            aversive: 0.8,
+            extinction: 0.005,
+            threshold: 0.1,
+            gape: -0.5,
+
+            outcomes: [0.; 6],
+        };
+
+        plugin.outcome(FoodKind::Poor, 0.)
+            .outcome(FoodKind::Plain, 0.5)
+            .outcome(FoodKind::Sweet, 1.)
+            .outcome(FoodKind::Bitter, -0.5)
+            .outcome(FoodKind::Sick, -1.);
+
+        plugin
+    }
+
+    ///
+    /// Learning rate for outcomes better than the odor's valence.
+    ///
+    pub fn appetitive(&mut self, rate: f32) -> &mut Self {
+        assert!(0. <= rate && rate <= 1.);
+
+        self.appetitive = rate;
+
+        self
+    }
+
+    ///
+    /// Learning rate for outcomes worse than the odor's valence.
+    ///
+    pub fn aversive(&mut self, rate: f32) -> &mut Self {
+        assert!(0. <= rate && rate <= 1.);
+
+        self.aversive = rate;
+
+        self
+    }
+
+    ///
+    /// Rate per tick of odor exposure returning to the innate valence.
+    ///
+    pub fn extinction(&mut self, rate: f32) -> &mut Self {
+        assert!(0. <= rate && rate <= 1.);
+
+        self.extinction = rate;
+
+        self
+    }
+
+    ///
+    /// Minimum bulb odor value for exposure.
+    ///
+    pub fn threshold(&mut self, threshold: f32) -> &mut Self {
+        assert!(threshold >= 0.);
+
+        self.threshold = threshold;
+
+        self
+    }
+
+    ///
+    /// Learned valence for conditioned gaping.
+    ///
+    pub fn gape(&mut self, valence: f32) -> &mut Self {
+        assert!(-1. <= valence && valence <= 0.);
+
+        self.gape = valence;
+
+        self
+    }
+
+    ///
+    /// Outcome value in [-1, 1] of digested food.
+    ///
+    pub fn outcome(&mut self, kind: FoodKind, value: f32) -> &mut Self {
+        assert!(-1. <= value && value <= 1.);
+
+        self.outcomes[food_index(kind)] = value;
+
+        self
+    }
+
+    fn create_conditioning(&self) -> OdorConditioning {
+        OdorConditioning {
+            appetitive: self.appetitive,
+            aversive: self.aversive,
+            extinction: self.extinction,
+            threshold: self.threshold,
+            gape: self.gape,
+
+            outcomes: self.outcomes,
+
+            table: HashMap::new(),
+        }
+    }
+}
+
+impl Plugin for OdorConditioningPlugin {
+    fn build(&self, app: &mut App) {
+        assert!(app.contains_plugin::<BodyEatPlugin>(), "OdorConditioning requires BodyEatPlugin");
+        assert!(app.contains_plugin::<OlfactoryBulbPlugin>(), "OdorConditioning requires OlfactoryBulbPlugin");
+
+        app.insert_resource(self.create_conditioning());
+
+        app.system(Tick, update_odor_conditioning);
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use essay_ecs::{core::error::Result, prelude::{Res, ResMut}};
+    use mind_ecs::MindApp;
+
+    use crate::{
+        body::{BodyEat, BodyEatPlugin, BodyPlugin},
+        olfactory::olfactory_bulb::OlfactoryBulbPlugin,
+        util::Ticks,
+        world::{FoodKind, FoodPlugin, OdorKind, OdorPlugin, WorldPlugin},
+    };
+
+    use super::{OdorConditioning, OdorConditioningPlugin};
+
+    #[test]
+    fn conditioned_taste_aversion() {
+        let mut conditioning = OdorConditioningPlugin::new().create_conditioning();
+
+        assert_eq!(None, conditioning.valence(OdorKind::FoodA));
+
+        // one sickness pairing reverses the innate food odor
+        conditioning.pair(OdorKind::FoodA, FoodKind::Sick);
+        assert!(conditioning.valence(OdorKind::FoodA).unwrap() < -0.5);
+
+        // extinction returns toward the innate valence
+        for _ in 0..500 {
+            conditioning.extinguish(OdorKind::FoodA);
+        }
+        assert!(conditioning.valence(OdorKind::FoodA).unwrap() > 0.5);
+
+        // acquisition of a neutral odor with sweet food
+        for _ in 0..10 {
+            conditioning.pair(OdorKind::OtherA, FoodKind::Sweet);
+        }
+        assert!(conditioning.valence(OdorKind::OtherA).unwrap() > 0.9);
+
+        // reversal with bitter food
+        for _ in 0..3 {
+            conditioning.pair(OdorKind::OtherA, FoodKind::Bitter);
+        }
+        assert!(conditioning.valence(OdorKind::OtherA).unwrap() < -0.4);
+    }
+
+    #[test]
+    fn conditioning_odor_at_eating() -> Result<()> {
+        let mut app = MindApp::test();
+        app.plugin(WorldPlugin::new(15, 15));
+        app.plugin(BodyPlugin::new().pos((5.5, 5.5)));
+
+        let mut body_eat = BodyEatPlugin::new();
+        body_eat.gut_delay(Ticks(5));
+        app.plugin(body_eat);
+
+        // odorless sickening food under a separate odor source
+        let mut food = FoodPlugin::new();
+        food.food(5, 5).kind(FoodKind::Sick).radius(1.);
+        app.plugin(food);
+
+        let mut odor = OdorPlugin::new();
+        odor.odor(5, 6, OdorKind::OtherA);
+        app.plugin(odor);
+
+        let mut bulb = OlfactoryBulbPlugin::new();
+        bulb.odor(OdorKind::OtherA);
+        app.plugin(bulb);
+
+        app.plugin(OdorConditioningPlugin::new());
+
+        app.tick()?;
+
+        app.eval(|mut x: ResMut<BodyEat>| x.eat())?;
+        app.tick()?;
+
+        // the meal is still in the gut delay
+        assert_eq!(None, app.eval(|x: Res<OdorConditioning>| x.valence(OdorKind::OtherA))?);
+
+        for _ in 0..10 {
+            app.tick()?;
+        }
+
+        // the odor at the head when eating is paired with the sickness
+        let valence = app.eval(|x: Res<OdorConditioning>| x.valence(OdorKind::OtherA))?;
+        assert!(valence.unwrap() < -0.5);
+
+        Ok(())
+    }
+}
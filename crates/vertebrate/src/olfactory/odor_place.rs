@@ -4,7 +4,15 @@ use std::{collections::HashMap, hash::Hash};
 use essay_ecs::{app::{App, Plugin}, core::{Res, ResMut}};
 use mind_ecs::Tick;
 
-use crate::{body::Body, hippocampus::Engram64, mid_brain::SeekContext, util::base64_rev, world::{WorldHex, WorldHexTrait}};
+use crate::{
+    body::Body, 
+    hippocampus::Engram64, 
+    mid_brain::SeekContext, 
+    util::base64_rev, 
+    world::{WorldHex, WorldHexTrait}
+};
+
+use super::olfactory_bulb::OlfactoryBulb;
 
 fn update_odor_place<K: WorldHexTrait + Eq + Hash + fmt::Debug + 'static>(
     mut odor_context: ResMut<OdorPlace>, 
@@ -15,12 +23,19 @@ fn update_odor_place<K: WorldHexTrait + Eq + Hash + fmt::Debug + 'static>(
     let pos = body.head_pos();
 
     if let Some(item) = odor_map.find(&world_hex[pos]) {
-        odor_context.odor = item.clone();
+        odor_context.engram = item.engram();
     } else {
-        odor_context.odor = OdorItem::default();
+        odor_context.engram = Engram64::default();
     }
 }
 
+fn update_odor_pattern(
+    mut odor_context: ResMut<OdorPlace>, 
+    bulb: Res<OlfactoryBulb>,
+) {
+    odor_context.engram = bulb.pattern_engram();
+}
+
 pub struct OdorPlaceMap<K: Eq + Hash, T> {
     loc_map: HashMap<K, T>,
 }
@@ -33,12 +48,12 @@ impl<K: Eq + Hash, T> OdorPlaceMap<K, T> {
 
 #[derive(Default)]
 pub struct OdorPlace {
-    odor: OdorItem,
+    engram: Engram64,
 }
 
 impl SeekContext for OdorPlace {
     fn context(&self) -> Engram64 {
-        self.odor.engram()
+        self.engram
     }
 }
 
@@ -101,3 +116,19 @@ impl<K: WorldHexTrait + Eq + Hash + fmt::Debug + Send> Plugin for OdorPlacePlugi
         app.system(Tick, update_odor_place::<K>);
     }
 }
+
+///
+/// OdorPlace context from the olfactory bulb's receptor pattern, instead
+/// of a hand-written place map.
+///
+pub struct OdorPatternPlugin;
+
+impl Plugin for OdorPatternPlugin {
+    fn build(&self, app: &mut App) {
+        assert!(app.contains_resource::<OlfactoryBulb>(), "OdorPatternPlugin requires OlfactoryBulb");
+
+        app.insert_resource(OdorPlace::default());
+
+        app.system(Tick, update_odor_pattern);
+    }
+}
@@ -1,20 +1,35 @@
-use std::collections::HashMap;
+use std::{collections::HashMap, f32::consts::TAU};
 
 ///
 /// Olfactory bulb
 ///
 
-use essay_ecs::{core::Query, prelude::{App, Event, Plugin, Res, ResMut}};
+use essay_ecs::{core::{Component, Query}, prelude::{App, Event, Plugin, Res, ResMut}};
 use mind_ecs::Tick;
 
 use crate::{
     body::Body, 
+    hippocampus::Engram64, 
     mid_brain::SeekInput, 
     subpallium::{AttendId, AttendValue, BasalForebrain}, 
-    util::{Angle, EgoVector}, 
-    world::{Odor, OdorKind}
+    util::{Angle, EgoVector, Heading, Point}, 
+    world::{Odor, OdorFeatures, OdorField, OdorInnate, OdorKind, OdorType}
 };
 
+use super::receptor::ReceptorBank;
+
+///
+/// OlfactoryBulb has one glomerulus per configured OdorKind, whose
+/// direction and value drive seek and avoid. Each source's features
+/// drive the receptor bank, and the receptor drive is decomposed into
+/// the glomeruli's own kinds, so a mixture activates the glomerulus of
+/// each component and a similar unconfigured odor activates its nearest
+/// glomeruli. A kind without features is a labeled line.
+///
+/// The receptor bank's saturating pattern of the odor features at the
+/// head is the odor identity used as the OdorPlace context.
+///
+#[derive(Component)]
 pub struct OlfactoryBulb {
     food: Option<OdorItem>,
     avoid: Option<OdorItem>,
@@ -22,29 +37,59 @@ pub struct OlfactoryBulb {
     glomerules: Vec<Glomerule>,
     odor_map: HashMap<OdorKind, usize>,
 
+    // receptor drive of each glomerulus's own kind
+    templates: Vec<Vec<f32>>,
+
     active_odors: Vec<OdorId>,
 
     attention: BasalForebrain,
+
+    strategy: OlfactoryStrategy,
+    nostril: f32,
+
+    receptors: ReceptorBank,
+    features: OdorFeatures,
+    pattern: Vec<f32>,
 }
 
 impl OlfactoryBulb {
+    // turn toward the stronger nostril, as a fraction of a quarter turn
+    pub const TROPOTAXIS_GAIN : f32 = 2.;
+    // covariance of the head cast and concentration for a quarter turn
+    pub const KLINOTAXIS_GAIN : f32 = 4.;
+    // minimum glomerulus weight for a source
+    const MIN_WEIGHT : f32 = 0.05;
+
     fn new() -> Self {
         Self { 
             food: None,
             avoid: None,
             glomerules: Vec::new(),
+            templates: Vec::new(),
             active_odors: Vec::new(),
             odor_map: HashMap::new(),
             attention: BasalForebrain::new(),
+            strategy: OlfactoryStrategy::Exact,
+            nostril: OdorField::NOSTRIL,
+
+            receptors: ReceptorBank::default(),
+            features: OdorFeatures::zero(),
+            pattern: Vec::new(),
         }
     }
 
+    #[inline]
+    pub fn strategy(&self) -> OlfactoryStrategy {
+        self.strategy
+    }
+
     fn odor(&mut self, odor: OdorKind) -> OdorId {
         let index = self.glomerules.len();
 
         let attend_id = self.attention.push();
 
         self.glomerules.push(Glomerule::new(odor, attend_id));
+        self.templates.push(self.receptors.drive(&odor.features()));
         self.odor_map.insert(odor, index);
 
         OdorId(index)
@@ -67,6 +112,233 @@ impl OlfactoryBulb {
         self.attention.add(attend_id, value);
     }
 
+    fn update_body(
+        &mut self, 
+        body: &Body, 
+        odors: &[&Odor<OdorKind>], 
+        field: Option<&OdorField>
+    ) {
+        self.update_pattern(body.head_pos(), odors, field);
+
+        let drive = self.drive(odors, field);
+
+        if self.strategy != OlfactoryStrategy::Exact {
+            self.update_nostrils(body, &drive);
+            return;
+        }
+
+        if field.is_some() {
+            self.update_field(body, &drive);
+            return;
+        }
+
+        self.pre_update();
+
+        let head_pos = body.head_pos();
+
+        for index in 0..self.glomerules.len() {
+            let mut best: Option<EgoVector> = None;
+
+            // the strongest source in range gives the glomerulus's direction
+            for (odor, (_, weights)) in odors.iter().zip(&drive.sources) {
+                if weights[index] <= 0. || ! odor.contains(head_pos) {
+                    continue;
+                }
+
+                let dist = odor.pos().dist(&head_pos);
+                let value = weights[index] * 0.5 / dist.max(0.5);
+
+                if best.map_or(true, |best| best.value() < value) {
+                    let angle = head_pos.heading_to(odor.pos());
+
+                    best = Some(EgoVector::new(angle, value).to_ego(body.head_dir()));
+                }
+            }
+
+            if let Some(vector) = best {
+                self.update_odor(index, vector);
+            }
+        }
+
+        self.update();
+    }
+
+    ///
+    /// Glomerulus weights for each odor source, a field layer or a point
+    /// odor.
+    ///
+    fn drive<'a>(
+        &self, 
+        odors: &'a [&'a Odor<OdorKind>], 
+        field: Option<&'a OdorField>
+    ) -> GlomDrive<'a> {
+        let sources = if let Some(field) = field {
+            field.kinds()
+                .map(|kind| (*kind, self.weights(*kind, &field.features(*kind))))
+                .collect()
+        } else {
+            odors.iter()
+                .map(|odor| (*odor.odor(), self.weights(*odor.odor(), odor.features())))
+                .collect()
+        };
+
+        GlomDrive {
+            odors,
+            field,
+            sources,
+        }
+    }
+
+    ///
+    /// Response of each glomerulus to a source's features, from the
+    /// decomposition of the receptor drive into the glomeruli's kinds.
+    ///
+    fn weights(&self, kind: OdorKind, features: &OdorFeatures) -> Vec<f32> {
+        let mut weights = self.receptors.decompose(&self.templates, features);
+
+        for (weight, glom) in weights.iter_mut().zip(&self.glomerules) {
+            if glom.kind == kind && glom.kind.features().is_zero() {
+                // kinds without features are labeled lines
+                *weight = 1.;
+            } else if *weight < Self::MIN_WEIGHT {
+                // decomposition residue isn't a response
+                *weight = 0.;
+            }
+        }
+
+        weights
+    }
+
+    ///
+    /// Samples the odor field at the head and at left and right nostrils.
+    /// The nostril difference and the head to body difference estimate
+    /// the gradient, which gives the odor direction.
+    ///
+    fn update_field(&mut self, body: &Body, drive: &GlomDrive) {
+        self.pre_update();
+
+        let head_pos = body.head_pos();
+        let body_pos = body.pos();
+
+        let (sin, cos) = body.head_dir().sin_cos();
+        let (right_x, right_y) = (sin, - cos);
+
+        let n = self.nostril;
+        let left_pos = Point(head_pos.x() - n * right_x, head_pos.y() - n * right_y);
+        let right_pos = Point(head_pos.x() + n * right_x, head_pos.y() + n * right_y);
+
+        let len = head_pos.dist(body_pos).max(1e-3);
+
+        for index in 0..self.glomerules.len() {
+            let head = drive.value(index, head_pos);
+
+            if head <= 0. {
+                continue;
+            }
+
+            let left = drive.value(index, left_pos);
+            let right = drive.value(index, right_pos);
+            let tail = drive.value(index, body_pos);
+
+            let lateral = (right - left) / (2. * n);
+            let forward = (head - tail) / len;
+
+            let dx = forward * cos + lateral * right_x;
+            let dy = forward * sin + lateral * right_y;
+
+            let angle = head_pos.heading_to(Point(head_pos.x() + dx, head_pos.y() + dy));
+
+            let vector = EgoVector::new(angle, head);
+            let vector = vector.to_ego(body.head_dir());
+
+            self.update_odor(index, vector);
+        }
+
+        self.update();
+    }
+
+    ///
+    /// Receptor pattern for the mixture of all odors at the head,
+    /// including odors without a glomerulus. The pattern is the odor
+    /// identity for OdorPlace, while seek and avoid use the glomeruli.
+    ///
+    fn update_pattern(
+        &mut self, 
+        head_pos: Point, 
+        odors: &[&Odor<OdorKind>], 
+        field: Option<&OdorField>
+    ) {
+        let mut features = OdorFeatures::zero();
+
+        if let Some(field) = field {
+            for kind in field.kinds() {
+                let value = field.response(field.concentration(*kind, head_pos));
+
+                features.add(&field.features(*kind), value);
+            }
+        } else {
+            for odor in odors.iter().filter(|odor| odor.contains(head_pos)) {
+                let value = 0.5 / odor.pos().dist(&head_pos).max(0.5);
+
+                features.add(odor.features(), value);
+            }
+        }
+
+        self.features = features;
+        self.pattern = self.receptors.pattern(&features);
+    }
+
+    ///
+    /// Samples only the concentrations at the left and right nostrils,
+    /// without the odor's position. Tropotaxis turns toward the stronger
+    /// nostril, and klinotaxis correlates the concentration with the
+    /// body's head cast, which requires a body cast period.
+    ///
+    fn update_nostrils(&mut self, body: &Body, drive: &GlomDrive) {
+        self.pre_update();
+
+        let head_pos = body.head_pos();
+
+        let (sin, cos) = body.head_dir().sin_cos();
+        let (right_x, right_y) = (sin, - cos);
+
+        let n = self.nostril;
+        let left_pos = Point(head_pos.x() - n * right_x, head_pos.y() - n * right_y);
+        let right_pos = Point(head_pos.x() + n * right_x, head_pos.y() + n * right_y);
+
+        let head_cast = body.head_cast();
+
+        for index in 0..self.glomerules.len() {
+            let left = drive.value(index, left_pos);
+            let right = drive.value(index, right_pos);
+
+            let glom = &mut self.glomerules[index];
+            glom.sides(left, right);
+
+            let value = 0.5 * (left + right);
+
+            let turn = match self.strategy {
+                OlfactoryStrategy::Klinotaxis => {
+                    glom.cast(value, head_cast) * Self::KLINOTAXIS_GAIN
+                }
+                _ if value > 0. => {
+                    (right - left) / (right + left) * Self::TROPOTAXIS_GAIN
+                }
+                _ => 0.,
+            };
+
+            if value <= 0. {
+                continue;
+            }
+
+            let dir = Heading::unit(0.25 * turn.clamp(-1., 1.));
+
+            self.update_odor(index, EgoVector::new(dir, value));
+        }
+
+        self.update();
+    }
+
     fn update(&mut self) {
         self.attention.update();
 
@@ -84,6 +356,125 @@ impl OlfactoryBulb {
                 self.active_odors.push(OdorId(i));
             }
         }
+
+        self.food = self.valence_item(true);
+        self.avoid = self.valence_item(false);
+    }
+
+    ///
+    /// Combined direction of the active glomeruli with positive valence
+    /// for seek or negative valence for avoid. Each glomerulus's vector is
+    /// weighted by its attention and the strength of its valence.
+    ///
+    fn valence_item(&self, is_seek: bool) -> Option<OdorItem> {
+        let mut sum_x = 0.;
+        let mut sum_y = 0.;
+        let mut best: Option<(OdorKind, f32)> = None;
+
+        for id in &self.active_odors {
+            let glom = &self.glomerules[id.i()];
+            let valence = glom.valence();
+
+            if (is_seek && valence <= 0.) || (! is_seek && valence >= 0.) {
+                continue;
+            }
+
+            let weight = glom.attend_value() * valence.abs();
+
+            if weight <= Glomerule::MIN {
+                continue;
+            }
+
+            sum_x += weight * glom.vector.dx();
+            sum_y += weight * glom.vector.dy();
+
+            if best.map_or(true, |(_, w)| w < weight) {
+                best = Some((glom.kind, weight));
+            }
+        }
+
+        let (odor, _) = best?;
+        let value = sum_x.hypot(sum_y);
+
+        if value <= Glomerule::MIN {
+            return None;
+        }
+
+        // math angle back to a heading, clockwise from forward
+        let dir = Heading::unit(0.25 - sum_y.atan2(sum_x) / TAU);
+
+        Some(OdorItem::new(odor, EgoVector::new(dir, value.min(1.))))
+    }
+
+    ///
+    /// Mixed odor features at the head.
+    ///
+    #[inline]
+    pub fn features(&self) -> &OdorFeatures {
+        &self.features
+    }
+
+    #[inline]
+    pub fn receptors(&self) -> &ReceptorBank {
+        &self.receptors
+    }
+
+    ///
+    /// Distributed receptor response to the odors at the head.
+    ///
+    #[inline]
+    pub fn pattern(&self) -> &[f32] {
+        &self.pattern
+    }
+
+    ///
+    /// Context engram of the current receptor pattern.
+    ///
+    pub fn pattern_engram(&self) -> Engram64 {
+        self.receptors.engram(&self.pattern)
+    }
+
+    ///
+    /// Left and right nostril responses for the odor.
+    ///
+    pub fn sides(&self, odor: OdorKind) -> (f32, f32) {
+        if let Some(index) = self.odor_map.get(&odor) {
+            let glom = &self.glomerules[*index];
+
+            (glom.left, glom.right)
+        } else {
+            (0., 0.)
+        }
+    }
+
+    ///
+    /// Odor valence in [-1, 1], where a learned valence overrides the
+    /// innate food and avoid valence.
+    ///
+    pub fn valence(&self, odor: OdorKind) -> f32 {
+        if let Some(index) = self.odor_map.get(&odor) {
+            self.glomerules[*index].valence()
+        } else {
+            innate_valence(odor)
+        }
+    }
+
+    ///
+    /// Sets a learned valence, for example from odor-taste conditioning.
+    ///
+    pub fn set_valence(&mut self, odor: OdorKind, valence: f32) {
+        if let Some(index) = self.odor_map.get(&odor) {
+            self.glomerules[*index].learned = Some(valence.clamp(-1., 1.));
+        }
+    }
+
+    ///
+    /// Clears a learned valence, returning to the innate valence.
+    ///
+    pub fn clear_valence(&mut self, odor: OdorKind) {
+        if let Some(index) = self.odor_map.get(&odor) {
+            self.glomerules[*index].learned = None;
+        }
     }
 
     #[inline]
@@ -108,7 +499,7 @@ impl OlfactoryBulb {
 
     pub fn food_dir(&self) -> Option<Angle> {
         if let Some(food) = &self.food {
-            Some(food.dir)
+            Some(food.dir())
         } else {
             None
         }
@@ -116,25 +507,85 @@ impl OlfactoryBulb {
 
     pub fn avoid_dir(&self) -> Option<Angle> {
         if let Some(avoid) = &self.avoid {
-            Some(avoid.dir)
+            Some(avoid.dir())
         } else {
             None
         }
     }
+
+    ///
+    /// Seek direction relative to the head with its strength.
+    ///
+    pub fn food_vector(&self) -> Option<EgoVector> {
+        self.food.as_ref().map(|food| food.vector)
+    }
+
+    ///
+    /// Direction of the aversive odors relative to the head.
+    ///
+    pub fn avoid_vector(&self) -> Option<EgoVector> {
+        self.avoid.as_ref().map(|avoid| avoid.vector)
+    }
+
+    ///
+    /// Odors with an active glomerulus at the head.
+    ///
+    pub fn active_odors(&self) -> impl Iterator<Item=OdorKind> + '_ {
+        self.active_odors.iter().map(|id| self.glomerules[id.i()].kind)
+    }
+
+    pub fn food_odor(&self) -> Option<OdorKind> {
+        self.food.as_ref().map(|food| food.odor)
+    }
+
+    pub fn avoid_odor(&self) -> Option<OdorKind> {
+        self.avoid.as_ref().map(|avoid| avoid.odor)
+    }
 }
 
-impl SeekInput for OlfactoryBulb {
-    fn seek_dir(&self) -> Option<EgoVector> {
-        //for id in &self.active_odors {
-            //let glom = &self.glomerules[id.0];
+///
+/// Odor sources with their glomerulus weights, computed once per update.
+///
+struct GlomDrive<'a> {
+    odors: &'a [&'a Odor<OdorKind>],
+    field: Option<&'a OdorField>,
 
-            //if glom.odor.is_food() {
-            //    return Some(glom.vector);
-            //}
-        //    todo!();
-        //}
+    // field layers with a field, otherwise the point odors
+    sources: Vec<(OdorKind, Vec<f32>)>,
+}
 
-        None
+impl GlomDrive<'_> {
+    ///
+    /// Glomerulus response at a position, summed over the field's layers
+    /// or from the strongest point odor.
+    ///
+    fn value(&self, index: usize, pos: Point) -> f32 {
+        if let Some(field) = self.field {
+            return self.sources.iter()
+                .map(|(kind, weights)| {
+                    weights[index] * field.response(field.concentration(*kind, pos))
+                })
+                .sum();
+        }
+
+        self.odors.iter().zip(&self.sources)
+            .filter(|(odor, (_, weights))| weights[index] > 0. && odor.contains(pos))
+            .map(|(odor, (_, weights))| weights[index] * 0.5 / odor.pos().dist(&pos).max(0.5))
+            .fold(0., f32::max)
+    }
+}
+
+pub(crate) fn innate_valence(odor: OdorKind) -> f32 {
+    match odor.innate() {
+        OdorInnate::Food => 1.,
+        OdorInnate::Avoid => -1.,
+        OdorInnate::None => 0.,
+    }
+}
+
+impl SeekInput for OlfactoryBulb {
+    fn seek_dir(&self) -> Option<EgoVector> {
+        self.food_vector()
     }
 }
 
@@ -142,24 +593,18 @@ fn update_olfactory(
     body: Res<Body>, 
     odors: Query<&Odor<OdorKind>>, 
     mut olf_bulb: ResMut<OlfactoryBulb>,
+    animals: Query<(&Body, &mut OlfactoryBulb)>,
+    field: Option<Res<OdorField>>,
 ) {
-    olf_bulb.pre_update();
-
-    for odor in odors.iter().filter(|odor| odor.contains(body.head_pos())) {
-        let index = *olf_bulb.odor_map.get(&odor.odor()).unwrap();
-
-        let dist = odor.pos().dist(&body.head_pos());
-        let angle = body.head_pos().heading_to(odor.pos());
-        let value = 0.5 / dist.max(0.5);
+    let odors: Vec<&Odor<OdorKind>> = odors.iter().collect();
+    let field = field.as_ref().map(|f| f.get());
 
-        let vector = EgoVector::new(angle, value);
-        let vector = vector.to_ego(body.head_dir());
+    olf_bulb.update_body(body.get(), &odors, field);
 
-        olf_bulb.get_mut().update_odor(index, vector);
+    for (body, bulb) in animals.iter() {
+        bulb.update_body(body, &odors, field);
     }
 
-    olf_bulb.update();
-
     // for glomerule in &olf_bulb.glomerules {
     //    if glomerule.vector.value() > Glomerule::MIN {
     //        ob_events.send(ObEvent::Odor(glomerule.odor, glomerule.vector));
@@ -180,38 +625,89 @@ impl OdorId {
 }
 
 struct OdorItem {
-    _odor: OdorKind,
-    dir: Angle,
+    odor: OdorKind,
+    vector: EgoVector,
 }
 
 impl OdorItem {
-    fn _new(odor: OdorKind, dir: Angle) -> Self {
+    fn new(odor: OdorKind, vector: EgoVector) -> Self {
         Self {
-            _odor: odor,
-            dir,
+            odor,
+            vector,
         }
     }
+
+    fn dir(&self) -> Angle {
+        Angle::Unit(self.vector.dir().to_turn().to_unit())
+    }
 }
 
 struct Glomerule {
-    _odor: OdorKind,
+    kind: OdorKind,
     vector: EgoVector,
     attend_id: AttendId,
     attend: f32,
+    learned: Option<f32>,
+
+    left: f32,
+    right: f32,
+
+    // head cast averages for klinotaxis
+    cast_mean: f32,
+    value_mean: f32,
+    cast_value: f32,
 }
 
 impl Glomerule {
     const MIN : f32 = 0.;
+    const CAST_DECAY : f32 = 0.9;
 
     fn new(odor: OdorKind, attend_id: AttendId) -> Self {
         Self {
-            _odor: odor,
+            kind: odor,
             vector: EgoVector::zero(),
             attend_id,
             attend: 1.,
+            learned: None,
+
+            left: 0.,
+            right: 0.,
+
+            cast_mean: 0.,
+            value_mean: 0.,
+            cast_value: 0.,
+        }
+    }
+
+    fn sides(&mut self, left: f32, right: f32) {
+        self.left = left;
+        self.right = right;
+    }
+
+    ///
+    /// Covariance of the head cast and the concentration, normalized by
+    /// the mean concentration. Positive when the odor is stronger when
+    /// the head is cast to the right.
+    ///
+    fn cast(&mut self, value: f32, head_cast: f32) -> f32 {
+        let decay = Self::CAST_DECAY;
+
+        self.cast_mean = decay * self.cast_mean + (1. - decay) * head_cast;
+        self.value_mean = decay * self.value_mean + (1. - decay) * value;
+        self.cast_value = decay * self.cast_value + (1. - decay) * head_cast * value;
+
+        if self.value_mean > 0. {
+            (self.cast_value - self.cast_mean * self.value_mean) / self.value_mean
+        } else {
+            0.
         }
     }
 
+    #[inline]
+    fn valence(&self) -> f32 {
+        self.learned.unwrap_or_else(|| innate_valence(self.kind))
+    }
+
     #[inline]
     fn value(&self) -> f32 {
         self.vector.value()
@@ -242,6 +738,18 @@ impl Glomerule {
     }
 }
 
+///
+/// Olfactory direction. Exact uses the odor's position or the field's
+/// gradient, while Tropotaxis and Klinotaxis infer the direction from
+/// the nostrils' concentrations alone.
+///
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum OlfactoryStrategy {
+    Exact,
+    Tropotaxis,
+    Klinotaxis,
+}
+
 #[derive(Clone, Copy, Debug, Event)]
 pub enum ObEvent {
     Odor(OdorKind, EgoVector),
@@ -249,30 +757,91 @@ pub enum ObEvent {
 
 pub struct OlfactoryBulbPlugin {
     odors: Vec<OdorKind>,
+    valences: Vec<(OdorKind, f32)>,
+    strategy: OlfactoryStrategy,
+    nostril: f32,
+    receptors: ReceptorBank,
 }
 
 impl OlfactoryBulbPlugin {
     pub fn new() -> Self {
         Self {
             odors: Vec::new(),
+            valences: Vec::new(),
+            strategy: OlfactoryStrategy::Exact,
+            nostril: OdorField::NOSTRIL,
+            receptors: ReceptorBank::default(),
         }
     }
 
+    ///
+    /// Receptor bank of n receptors with tuning width in feature units.
+    ///
+    pub fn receptors(&mut self, n: usize, width: f32) -> &mut Self {
+        self.receptors = ReceptorBank::new(n, width);
+
+        self
+    }
+
+    pub fn strategy(&mut self, strategy: OlfactoryStrategy) -> &mut Self {
+        self.strategy = strategy;
+
+        self
+    }
+
+    ///
+    /// Distance of each nostril from the head's midline.
+    ///
+    pub fn nostril(&mut self, nostril: f32) -> &mut Self {
+        assert!(nostril > 0.);
+
+        self.nostril = nostril;
+
+        self
+    }
+
     pub fn odor(&mut self, odor: OdorKind) -> &mut Self {
         self.odors.push(odor);
 
         self
     }
+
+    ///
+    /// Initial learned valence for the last odor, overriding its innate
+    /// valence.
+    ///
+    pub fn valence(&mut self, valence: f32) -> &mut Self {
+        let odor = *self.odors.last().expect("valence requires an odor");
+
+        self.valences.push((odor, valence));
+
+        self
+    }
 }
 
-impl Plugin for OlfactoryBulbPlugin {
-    fn build(&self, app: &mut App) {
+impl OlfactoryBulbPlugin {
+    pub(crate) fn create_bulb(&self) -> OlfactoryBulb {
         let mut bulb = OlfactoryBulb::new();
+        bulb.strategy = self.strategy;
+        bulb.nostril = self.nostril;
+        bulb.receptors = self.receptors.clone();
 
         for odor in &self.odors {
             bulb.odor(*odor);
         }
 
+        for (odor, valence) in &self.valences {
+            bulb.set_valence(*odor, *valence);
+        }
+
+        bulb
+    }
+}
+
+impl Plugin for OlfactoryBulbPlugin {
+    fn build(&self, app: &mut App) {
+        let bulb = self.create_bulb();
+
         app.insert_resource(bulb);
 
         app.event::<ObEvent>();
@@ -280,3 +849,94 @@ impl Plugin for OlfactoryBulbPlugin {
         app.system(Tick, update_olfactory);
     }
 }
+
+#[cfg(test)]
+mod test {
+    use essay_ecs::{core::error::Result, prelude::Res};
+    use mind_ecs::MindApp;
+
+    use crate::{
+        body::BodyPlugin, 
+        util::{EgoVector, Heading}, 
+        world::{OdorKind, OdorPlugin, WorldPlugin}
+    };
+
+    use super::{OlfactoryBulb, OlfactoryBulbPlugin, OlfactoryStrategy};
+
+    #[test]
+    fn bulb_seek_avoid() {
+        let mut plugin = OlfactoryBulbPlugin::new();
+        plugin.odor(OdorKind::FoodA).odor(OdorKind::AvoidA);
+
+        let mut bulb = plugin.create_bulb();
+
+        bulb.pre_update();
+        bulb.update_odor(0, EgoVector::new(Heading::Unit(0.25), 0.8));
+        bulb.update_odor(1, EgoVector::new(Heading::Unit(0.75), 0.5));
+        bulb.update();
+
+        let food = bulb.food_vector().unwrap();
+        assert!((food.dir().to_unit() - 0.25).abs() < 1e-4);
+        assert_eq!(Some(OdorKind::FoodA), bulb.food_odor());
+
+        let avoid = bulb.avoid_vector().unwrap();
+        assert!((avoid.dir().to_unit() - 0.75).abs() < 1e-4);
+
+        // learned aversion overrides the innate food valence
+        bulb.set_valence(OdorKind::FoodA, -1.);
+        bulb.update();
+
+        assert!(bulb.food_vector().is_none());
+        assert_eq!(Some(OdorKind::FoodA), bulb.avoid_odor());
+    }
+
+    #[test]
+    fn bulb_mixture() -> Result<()> {
+        let mut app = MindApp::test();
+        app.plugin(WorldPlugin::new(15, 15));
+        app.plugin(BodyPlugin::new().pos((5.5, 5.5)));
+
+        let mut odor = OdorPlugin::new();
+        odor.mixture(5, 7, 3, &[(OdorKind::FoodA, 0.5), (OdorKind::AvoidA, 0.5)]);
+        app.plugin(odor);
+
+        let mut bulb = OlfactoryBulbPlugin::new();
+        bulb.odor(OdorKind::FoodA).odor(OdorKind::AvoidA);
+        app.plugin(bulb);
+
+        app.tick()?;
+
+        // the mixture drives both component glomeruli
+        assert!(app.eval(|x: Res<OlfactoryBulb>| x.value_pair(OdorKind::FoodA).value)? > 0.);
+        assert!(app.eval(|x: Res<OlfactoryBulb>| x.value_pair(OdorKind::AvoidA).value)? > 0.);
+        assert_eq!(Some(OdorKind::AvoidA), app.eval(|x: Res<OlfactoryBulb>| x.avoid_odor())?);
+
+        Ok(())
+    }
+
+    #[test]
+    fn bulb_tropotaxis() -> Result<()> {
+        let mut app = MindApp::test();
+        app.plugin(WorldPlugin::new(15, 15));
+        app.plugin(BodyPlugin::new().pos((5.5, 5.5)));
+
+        let mut odor = OdorPlugin::new();
+        odor.odor(7, 5, OdorKind::FoodA);
+        app.plugin(odor);
+
+        let mut bulb = OlfactoryBulbPlugin::new();
+        bulb.odor(OdorKind::FoodA).strategy(OlfactoryStrategy::Tropotaxis);
+        app.plugin(bulb);
+
+        app.tick()?;
+
+        // the odor is to the right of the north-facing body
+        let (left, right) = app.eval(|x: Res<OlfactoryBulb>| x.sides(OdorKind::FoodA))?;
+        assert!(0. < left && left < right);
+
+        let turn = app.eval(|x: Res<OlfactoryBulb>| x.food_vector().unwrap().turn().to_unit())?;
+        assert!(turn > 0.);
+
+        Ok(())
+    }
+}
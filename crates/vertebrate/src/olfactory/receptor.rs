@@ -0,0 +1,211 @@
+use crate::{hippocampus::Engram64, world::OdorFeatures};
+
+///
+/// Bank of olfactory receptors with overlapping gaussian tuning curves
+/// along the odor feature axes. An odor activates a distributed pattern
+/// of receptors, so similar odors and mixtures share active receptors.
+///
+#[derive(Clone, Debug)]
+pub struct ReceptorBank {
+    receptors: Vec<Receptor>,
+    saturation: f32,
+    threshold: f32,
+}
+
+impl ReceptorBank {
+    // the engram packs 6-bit receptor indices
+    pub const MAX_RECEPTORS : usize = 63;
+    pub const ENGRAM_RECEPTORS : usize = 10;
+    // coordinate descent passes for a decomposition
+    const DECOMPOSE_ITER : usize = 20;
+
+    ///
+    /// Receptors evenly spaced across the feature axes.
+    ///
+    pub fn new(n: usize, width: f32) -> Self {
+        assert!(n > 0 && n <= Self::MAX_RECEPTORS, "receptors must be in [1, {}]", Self::MAX_RECEPTORS);
+        assert!(width > 0.);
+
+        let step = (OdorFeatures::N - 1) as f32 / (n - 1).max(1) as f32;
+
+        let receptors = (0..n).map(|i| Receptor {
+            center: i as f32 * step,
+            width,
+        }).collect();
+
+        Self {
+            receptors,
+            saturation: 0.5,
+            threshold: 0.1,
+        }
+    }
+
+    #[inline]
+    pub fn len(&self) -> usize {
+        self.receptors.len()
+    }
+
+    ///
+    /// Saturating response of each receptor to the features.
+    ///
+    pub fn pattern(&self, features: &OdorFeatures) -> Vec<f32> {
+        self.receptors.iter().map(|receptor| {
+            let drive = receptor.drive(features);
+
+            drive / (drive + self.saturation)
+        }).collect()
+    }
+
+    ///
+    /// Linear drive of each receptor by the features, before saturation.
+    ///
+    pub fn drive(&self, features: &OdorFeatures) -> Vec<f32> {
+        self.receptors.iter().map(|receptor| receptor.drive(features)).collect()
+    }
+
+    ///
+    /// Non-negative weights of the template drives that best explain the
+    /// receptor drive of the features, by coordinate descent. A mixture
+    /// of the templates' odors recovers its component weights, and an
+    /// unknown odor is explained by the most similar templates.
+    ///
+    pub fn decompose(&self, templates: &[Vec<f32>], features: &OdorFeatures) -> Vec<f32> {
+        let mut residual = self.drive(features);
+        let mut weights = vec![0.; templates.len()];
+
+        for _ in 0..Self::DECOMPOSE_ITER {
+            for (weight, template) in weights.iter_mut().zip(templates) {
+                let norm = dot(template, template);
+
+                if norm <= 0. {
+                    continue;
+                }
+
+                let next = (*weight + dot(template, &residual) / norm).max(0.);
+                let delta = next - *weight;
+
+                for (r, t) in residual.iter_mut().zip(template) {
+                    *r -= delta * t;
+                }
+
+                *weight = next;
+            }
+        }
+
+        weights
+    }
+
+    ///
+    /// Context engram of the strongest active receptors.
+    ///
+    pub fn engram(&self, pattern: &[f32]) -> Engram64 {
+        let mut active: Vec<usize> = (0..pattern.len())
+            .filter(|i| pattern[*i] > self.threshold)
+            .collect();
+
+        active.sort_by(|a, b| pattern[*b].total_cmp(&pattern[*a]));
+        active.truncate(Self::ENGRAM_RECEPTORS);
+        active.sort();
+
+        let mut value : u64 = 0;
+
+        // offset by one so receptor 0 differs from no odor
+        for i in active {
+            value = (value << 6) + (i + 1) as u64;
+        }
+
+        Engram64(value)
+    }
+
+    ///
+    /// Cosine similarity of two receptor patterns.
+    ///
+    pub fn similarity(a: &[f32], b: &[f32]) -> f32 {
+        let len_a = dot(a, a).sqrt();
+        let len_b = dot(b, b).sqrt();
+
+        if len_a > 0. && len_b > 0. {
+            dot(a, b) / (len_a * len_b)
+        } else {
+            0.
+        }
+    }
+}
+
+fn dot(a: &[f32], b: &[f32]) -> f32 {
+    a.iter().zip(b.iter()).map(|(x, y)| x * y).sum()
+}
+
+impl Default for ReceptorBank {
+    fn default() -> Self {
+        ReceptorBank::new(12, 1.)
+    }
+}
+
+#[derive(Clone, Debug)]
+struct Receptor {
+    center: f32,
+    width: f32,
+}
+
+impl Receptor {
+    fn drive(&self, features: &OdorFeatures) -> f32 {
+        features.features().iter().enumerate().map(|(i, value)| {
+            let d = (i as f32 - self.center) / self.width;
+
+            value * (-0.5 * d * d).exp()
+        }).sum()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::world::{OdorFeatures, OdorKind, OdorType};
+
+    use super::ReceptorBank;
+
+    #[test]
+    fn receptor_generalization() {
+        let bank = ReceptorBank::default();
+
+        let food_a = bank.pattern(&OdorKind::FoodA.features());
+        let food_b = bank.pattern(&OdorKind::FoodB.features());
+        let avoid_a = bank.pattern(&OdorKind::AvoidA.features());
+
+        // overlapping food odors are more similar than food and avoid
+        let food_sim = ReceptorBank::similarity(&food_a, &food_b);
+        let avoid_sim = ReceptorBank::similarity(&food_a, &avoid_a);
+        assert!(food_sim > avoid_sim);
+
+        // a mixture resembles both components
+        let mix = bank.pattern(&OdorFeatures::mixture(&[(OdorKind::FoodA, 0.5), (OdorKind::AvoidA, 0.5)]));
+        assert!(ReceptorBank::similarity(&mix, &food_a) > avoid_sim);
+        assert!(ReceptorBank::similarity(&mix, &avoid_a) > avoid_sim);
+
+        assert_ne!(bank.engram(&food_a), bank.engram(&avoid_a));
+        assert_eq!(0, bank.engram(&bank.pattern(&OdorFeatures::zero())).0);
+    }
+
+    #[test]
+    fn receptor_decompose() {
+        let bank = ReceptorBank::default();
+
+        let templates = vec![
+            bank.drive(&OdorKind::FoodA.features()),
+            bank.drive(&OdorKind::AvoidA.features()),
+        ];
+
+        // a pure odor is its own template
+        let w = bank.decompose(&templates, &OdorKind::FoodA.features());
+        assert!((w[0] - 1.).abs() < 1e-3 && w[1] < 1e-3, "{:?}", w);
+
+        // a mixture recovers both components
+        let mix = OdorFeatures::mixture(&[(OdorKind::FoodA, 0.5), (OdorKind::AvoidA, 0.5)]);
+        let w = bank.decompose(&templates, &mix);
+        assert!((w[0] - 0.5).abs() < 1e-2 && (w[1] - 0.5).abs() < 1e-2, "{:?}", w);
+
+        // a similar odor generalizes to the nearest template
+        let w = bank.decompose(&templates, &OdorKind::FoodB.features());
+        assert!(w[0] > 0.5 && w[1] < 0.2, "{:?}", w);
+    }
+}
@@ -0,0 +1,5 @@
+mod recorder;
+
+pub use recorder::{
+    Recorder, RecorderPlugin, RecordFormat, RecordValue, IntoProbe, Probe
+};
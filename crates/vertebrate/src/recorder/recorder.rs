@@ -0,0 +1,435 @@
+use std::{
+    any::type_name,
+    cell::RefCell,
+    fs::File,
+    io::{self, BufWriter, Write},
+    path::{Path, PathBuf}
+};
+
+use essay_ecs::prelude::*;
+use log::warn;
+use mind_ecs::{AppTick, PostTick};
+
+use crate::util::{Heading, Point};
+
+fn recorder_flush(mut recorder: ResMut<Recorder>) {
+    recorder.flush();
+}
+
+///
+/// Recorder streams probe values to a CSV or JSON Lines file, one row
+/// per tick, for offline analysis.
+///
+/// Probes run in PostTick in any order, so values are collected into a
+/// row keyed by the AppTick, and the row is written when the tick
+/// changes or at the end of the update.
+///
+pub struct Recorder {
+    path: PathBuf,
+    format: RecordFormat,
+    writer: BufWriter<File>,
+
+    labels: Vec<String>,
+    columns: Vec<Vec<&'static str>>,
+
+    row_tick: Option<u64>,
+    row: Vec<Option<Vec<(&'static str, f32)>>>,
+
+    is_header: bool,
+    is_error: bool,
+}
+
+impl Recorder {
+    fn new(
+        path: &Path,
+        format: RecordFormat,
+        labels: Vec<String>,
+        columns: Vec<Vec<&'static str>>,
+    ) -> io::Result<Self> {
+        assert_eq!(labels.len(), columns.len());
+
+        let writer = BufWriter::new(File::create(path)?);
+
+        let mut row = Vec::new();
+        row.resize(labels.len(), None);
+
+        Ok(Self {
+            path: path.to_path_buf(),
+            format,
+            writer,
+
+            labels,
+            columns,
+
+            row_tick: None,
+            row,
+
+            is_header: false,
+            is_error: false,
+        })
+    }
+
+    #[inline]
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    #[inline]
+    pub fn format(&self) -> RecordFormat {
+        self.format
+    }
+
+    fn set(&mut self, tick: u64, index: usize, fields: Vec<(&'static str, f32)>) {
+        if self.row_tick != Some(tick) {
+            self.write_row();
+            self.row_tick = Some(tick);
+        }
+
+        self.row[index] = Some(fields);
+    }
+
+    pub fn flush(&mut self) {
+        self.write_row();
+
+        let result = self.writer.flush();
+        self.check(result);
+    }
+
+    fn write_row(&mut self) {
+        let Some(tick) = self.row_tick.take() else {
+            return;
+        };
+
+        let result = match self.format {
+            RecordFormat::Csv => self.write_csv(tick),
+            RecordFormat::Jsonl => self.write_jsonl(tick),
+        };
+
+        self.check(result);
+
+        for value in &mut self.row {
+            *value = None;
+        }
+    }
+
+    fn write_csv(&mut self, tick: u64) -> io::Result<()> {
+        if ! self.is_header {
+            self.is_header = true;
+
+            let mut header = vec![String::from("tick")];
+            header.append(&mut self.column_names());
+
+            writeln!(self.writer, "{}", header.join(","))?;
+        }
+
+        let mut line = tick.to_string();
+
+        for (fields, columns) in self.row.iter().zip(&self.columns) {
+            for j in 0..columns.len() {
+                line.push(',');
+
+                if let Some((_, value)) = fields.as_ref().and_then(|f| f.get(j)) {
+                    line.push_str(&value.to_string());
+                }
+            }
+        }
+
+        writeln!(self.writer, "{}", line)
+    }
+
+    fn write_jsonl(&mut self, tick: u64) -> io::Result<()> {
+        let mut line = format!("{{\"tick\":{}", tick);
+
+        for (i, fields) in self.row.iter().enumerate() {
+            if let Some(fields) = fields {
+                for (name, value) in fields {
+                    let label = column_name(&self.labels[i], name);
+
+                    line.push_str(&format!(",\"{}\":", json_escape(&label)));
+
+                    if value.is_finite() {
+                        line.push_str(&value.to_string());
+                    } else {
+                        line.push_str("null");
+                    }
+                }
+            }
+        }
+
+        line.push('}');
+
+        writeln!(self.writer, "{}", line)
+    }
+
+    fn column_names(&self) -> Vec<String> {
+        let mut names = Vec::new();
+
+        for (label, columns) in self.labels.iter().zip(&self.columns) {
+            for name in columns {
+                names.push(column_name(label, name));
+            }
+        }
+
+        names
+    }
+
+    fn check(&mut self, result: io::Result<()>) {
+        if let Err(err) = result {
+            if ! self.is_error {
+                self.is_error = true;
+                warn!("Recorder write to {:?} failed: {}", self.path, err);
+            }
+        }
+    }
+}
+
+impl Drop for Recorder {
+    fn drop(&mut self) {
+        self.flush();
+    }
+}
+
+fn column_name(label: &str, name: &str) -> String {
+    if name.is_empty() {
+        String::from(label)
+    } else {
+        format!("{}.{}", label, name)
+    }
+}
+
+fn json_escape(value: &str) -> String {
+    value.replace('\\', "\\\\").replace('"', "\\\"")
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum RecordFormat {
+    Csv,
+    Jsonl,
+}
+
+impl RecordFormat {
+    fn from_path(path: &Path) -> Self {
+        match path.extension().and_then(|e| e.to_str()) {
+            Some("jsonl") | Some("json") => RecordFormat::Jsonl,
+            _ => RecordFormat::Csv,
+        }
+    }
+}
+
+///
+/// Value captured by a probe. Multi-valued types like Point become
+/// several named columns, e.g. "pos.x" and "pos.y". The columns are
+/// fixed by the type, so the CSV header is known when the probe is
+/// registered.
+///
+pub trait RecordValue {
+    fn columns() -> Vec<&'static str>;
+
+    fn fields(&self) -> Vec<(&'static str, f32)>;
+}
+
+impl RecordValue for f32 {
+    fn columns() -> Vec<&'static str> {
+        vec![""]
+    }
+
+    fn fields(&self) -> Vec<(&'static str, f32)> {
+        vec![("", *self)]
+    }
+}
+
+impl RecordValue for bool {
+    fn columns() -> Vec<&'static str> {
+        vec![""]
+    }
+
+    fn fields(&self) -> Vec<(&'static str, f32)> {
+        vec![("", if *self { 1. } else { 0. })]
+    }
+}
+
+impl RecordValue for usize {
+    fn columns() -> Vec<&'static str> {
+        vec![""]
+    }
+
+    fn fields(&self) -> Vec<(&'static str, f32)> {
+        vec![("", *self as f32)]
+    }
+}
+
+impl RecordValue for Point {
+    fn columns() -> Vec<&'static str> {
+        vec!["x", "y"]
+    }
+
+    fn fields(&self) -> Vec<(&'static str, f32)> {
+        vec![("x", self.x()), ("y", self.y())]
+    }
+}
+
+impl RecordValue for Heading {
+    fn columns() -> Vec<&'static str> {
+        vec![""]
+    }
+
+    fn fields(&self) -> Vec<(&'static str, f32)> {
+        vec![("", self.to_unit())]
+    }
+}
+
+pub struct RecorderPlugin {
+    path: PathBuf,
+    format: RecordFormat,
+
+    probes: Vec<(String, Box<dyn Probe>)>,
+}
+
+impl RecorderPlugin {
+    ///
+    /// Records to path, as JSON Lines for a .jsonl extension and
+    /// CSV otherwise.
+    ///
+    pub fn new(path: impl AsRef<Path>) -> Self {
+        let path = path.as_ref().to_path_buf();
+        let format = RecordFormat::from_path(&path);
+
+        Self {
+            path,
+            format,
+            probes: Vec::new(),
+        }
+    }
+
+    pub fn format(mut self, format: RecordFormat) -> Self {
+        self.format = format;
+
+        self
+    }
+
+    pub fn item<T, V>(
+        mut self,
+        label: &str,
+        probe: impl IntoProbe<T, V>
+    ) -> Self {
+        let probe = IntoProbe::into_probe(probe);
+
+        self.probes.push((String::from(label), probe));
+
+        self
+    }
+}
+
+impl Plugin for RecorderPlugin {
+    fn build(&self, app: &mut App) {
+        assert!(app.contains_resource::<AppTick>(), "RecorderPlugin requires TickSchedulePlugin");
+
+        let labels = self.probes.iter().map(|(label, _)| label.clone()).collect();
+        let columns = self.probes.iter().map(|(_, probe)| probe.columns()).collect();
+
+        let recorder = match Recorder::new(&self.path, self.format, labels, columns) {
+            Ok(recorder) => recorder,
+            Err(err) => panic!("RecorderPlugin can't create {:?}: {}", self.path, err),
+        };
+
+        app.insert_resource(recorder);
+
+        for (i, (_, probe)) in self.probes.iter().enumerate() {
+            probe.add(i, app);
+        }
+
+        app.system(Last, recorder_flush);
+    }
+}
+
+pub trait Probe {
+    fn columns(&self) -> Vec<&'static str>;
+
+    fn add(&self, index: usize, app: &mut App);
+}
+
+type ProbeBox<T> = Box<dyn Fn(&T) -> Vec<(&'static str, f32)> + Sync + Send>;
+
+struct ProbeImpl<T> {
+    columns: Vec<&'static str>,
+    probe: RefCell<Option<ProbeBox<T>>>,
+}
+
+impl<T: Send + Sync + 'static> Probe for ProbeImpl<T> {
+    fn columns(&self) -> Vec<&'static str> {
+        self.columns.clone()
+    }
+
+    fn add(&self, index: usize, app: &mut App) {
+        assert!(app.contains_resource::<T>(),
+            "{:?} is an unregistered resource", type_name::<T>());
+
+        if let Some(fun) = self.probe.take() {
+            app.system(
+                PostTick,
+                move |res: Res<T>, tick: Res<AppTick>, mut recorder: ResMut<Recorder>| {
+                    recorder.set(tick.ticks(), index, fun(res.get()));
+                }
+            );
+        }
+    }
+}
+
+pub trait IntoProbe<T, V> {
+    fn into_probe(this: Self) -> Box<dyn Probe>;
+}
+
+impl<T: Send + Sync + 'static, V: RecordValue, F> IntoProbe<T, V> for F
+where
+    F: Fn(&T) -> V + Send + Sync + 'static
+{
+    fn into_probe(this: Self) -> Box<dyn Probe> {
+        Box::new(ProbeImpl {
+            columns: V::columns(),
+            probe: RefCell::new(Some(Box::new(move |x: &T| this(x).fields())))
+        })
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::fs;
+
+    use essay_ecs::core::error::Result;
+    use mind_ecs::MindApp;
+
+    use crate::{body::{Body, BodyPlugin}, world::WorldPlugin};
+
+    use super::RecorderPlugin;
+
+    #[test]
+    fn record_csv() -> Result<()> {
+        let path = std::env::temp_dir()
+            .join(format!("essay_record_csv_{}.csv", std::process::id()));
+
+        {
+            let mut app = MindApp::test();
+            app.plugin(WorldPlugin::new(7, 13));
+            app.plugin(BodyPlugin::new());
+            app.plugin(RecorderPlugin::new(&path)
+                .item("pos", |b: &Body| b.pos())
+                .item("speed", |b: &Body| b.speed())
+            );
+
+            app.tick()?;
+            app.tick()?;
+        }
+
+        let text = fs::read_to_string(&path).unwrap();
+        let lines: Vec<&str> = text.lines().collect();
+
+        assert_eq!("tick,pos.x,pos.y,speed", lines[0]);
+        assert_eq!("1,0.5,0.5,0", lines[1]);
+        assert_eq!("2,0.5,0.5,0", lines[2]);
+        assert_eq!(3, lines.len());
+
+        fs::remove_file(&path).unwrap();
+
+        Ok(())
+    }
+}
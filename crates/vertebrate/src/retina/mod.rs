@@ -1,3 +1,4 @@
+mod ray_cast;
 mod retina;
 
-pub use retina::{Retina, RetinaPlugin, world_form};
\ No newline at end of file
+pub use retina::{Retina, RetinaPlugin, RetinaStrategy, world_form};
\ No newline at end of file
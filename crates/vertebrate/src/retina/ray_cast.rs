@@ -0,0 +1,287 @@
+use essay_tensor::tensor::Tensor;
+
+use crate::{util::{Angle, Heading, Point}, world::{Predator, Wall, World}};
+
+use super::Retina;
+
+///
+/// Software retina that ray-casts the World grid instead of rendering
+/// through wgpu. The shading follows world_form's texture: lit floor
+/// checkerboard, dark wall faces, and a black roof and out-of-bounds.
+/// Predators are black cylinders drawn in front of the walls.
+///
+pub(super) struct RayCastRetina {
+    size: usize,
+}
+
+impl RayCastRetina {
+    // luma of world_form's texture colors
+    const WALL_N : f32 = 0.198;
+    const WALL_S : f32 = 0.049;
+    const WALL_E : f32 = 0.099;
+    const WALL_W : f32 = 0.103;
+
+    const FLOOR_GL : f32 = 0.753;
+    const FLOOR_GD : f32 = 0.816;
+    const FOOD : f32 = 0.52;
+
+    const FLOOR_LIGHT : f32 = 0.9;
+    const FLOOR_DARK : f32 = 0.1;
+
+    const PREDATOR : f32 = 0.02;
+
+    const BLACK : f32 = 0.;
+
+    pub(super) fn new(size: u32) -> Self {
+        assert!(size > 0);
+
+        Self {
+            size: size as usize,
+        }
+    }
+
+    pub(super) fn draw(
+        &self,
+        world: &World,
+        predators: &[&Predator],
+        head_pos: Point,
+        head_dir: Heading,
+        eye_angle: Angle,
+        fov: Angle,
+    ) -> (Tensor, Tensor) {
+        let left = self.draw_eye(world, predators, head_pos, head_dir - eye_angle, fov);
+        let right = self.draw_eye(world, predators, head_pos, head_dir + eye_angle, fov);
+
+        (left, right)
+    }
+
+    fn draw_eye(
+        &self,
+        world: &World,
+        predators: &[&Predator],
+        pos: Point,
+        dir: Heading,
+        fov: Angle
+    ) -> Tensor {
+        let size = self.size;
+        let tan = (0.5 * fov.to_radians()).tan();
+
+        let (sin, cos) = dir.sin_cos();
+        // clockwise from forward
+        let (right_x, right_y) = (sin, - cos);
+
+        let mut vec = Vec::<f32>::new();
+        vec.resize(size * size, 0.);
+
+        for i in 0..size {
+            let x_ndc = 2. * (i as f32 + 0.5) / size as f32 - 1.;
+
+            let dx = cos + x_ndc * tan * right_x;
+            let dy = sin + x_ndc * tan * right_y;
+
+            let wall = nearest_wall(cast(world, pos, dx, dy), world, pos, dx, dy);
+            let predator = cast_predators(predators, pos, dx, dy);
+
+            for j in 0..size {
+                // row 0 is the top of the image
+                let y_ndc = 1. - 2. * (j as f32 + 0.5) / size as f32;
+                let slope = y_ndc * tan;
+
+                vec[j * size + i] = self.shade(world, pos, dx, dy, slope, wall, predator);
+            }
+        }
+
+        Tensor::from(vec).reshape([size, size])
+    }
+
+    fn shade(
+        &self,
+        world: &World,
+        pos: Point,
+        dx: f32,
+        dy: f32,
+        slope: f32,
+        wall: Option<(f32, f32)>,
+        predator: Option<f32>,
+    ) -> f32 {
+        if let Some(s) = predator {
+            let z = Retina::HEIGHT + s * slope;
+
+            if 0. <= z && z <= Predator::HEIGHT && wall.map_or(true, |w| s < w.0) {
+                return Self::PREDATOR;
+            }
+        }
+
+        if let Some((s, luma)) = wall {
+            let z = Retina::HEIGHT + s * slope;
+
+            if 0. <= z && z <= 1. {
+                return luma;
+            }
+        }
+
+        if slope < 0. {
+            let s = Retina::HEIGHT / - slope;
+
+            Self::floor(world, Point(pos.x() + s * dx, pos.y() + s * dy))
+        } else {
+            // roof
+            Self::BLACK
+        }
+    }
+
+    fn floor(world: &World, pos: Point) -> f32 {
+        let (width, height) = world.extent();
+
+        if pos.x() < 0. || pos.y() < 0. {
+            return Self::BLACK;
+        }
+
+        let (i, j) = (pos.x() as usize, pos.y() as usize);
+
+        if width <= i || height <= j {
+            return Self::BLACK;
+        }
+
+        match world[(i, j)] {
+            Wall::Empty => {
+                if (i + j) % 2 == 0 { Self::FLOOR_GL } else { Self::FLOOR_GD }
+            }
+            Wall::Food => Self::FOOD,
+            Wall::Wall => Self::BLACK,
+            Wall::FloorLight => Self::FLOOR_LIGHT,
+            Wall::FloorDark => Self::FLOOR_DARK,
+        }
+    }
+}
+
+///
+/// Obstacles in front of the grid wall replace it, shaded like the grid
+/// face nearest their normal.
+///
+fn nearest_wall(
+    wall: Option<(f32, f32)>,
+    world: &World,
+    pos: Point,
+    dx: f32,
+    dy: f32
+) -> Option<(f32, f32)> {
+    match world.cast_obstacles(pos, dx, dy) {
+        Some((s, normal)) if wall.map_or(true, |w| s < w.0) => {
+            Some((s, obstacle_luma(normal)))
+        }
+        _ => wall,
+    }
+}
+
+fn obstacle_luma(normal: Point) -> f32 {
+    // faces toward -x are N walls in world_form, and toward -y are E walls
+    if normal.x().abs() > normal.y().abs() {
+        if normal.x() < 0. { RayCastRetina::WALL_N } else { RayCastRetina::WALL_S }
+    } else {
+        if normal.y() < 0. { RayCastRetina::WALL_E } else { RayCastRetina::WALL_W }
+    }
+}
+
+///
+/// Nearest ray parameter where pos + s * (dx, dy) enters a predator's
+/// cylinder.
+///
+fn cast_predators(predators: &[&Predator], pos: Point, dx: f32, dy: f32) -> Option<f32> {
+    let a = dx * dx + dy * dy;
+    let mut nearest: Option<f32> = None;
+
+    for predator in predators {
+        let (ox, oy) = (pos.x() - predator.pos().x(), pos.y() - predator.pos().y());
+        let r = predator.radius();
+
+        let b = 2. * (dx * ox + dy * oy);
+        let c = ox * ox + oy * oy - r * r;
+
+        let disc = b * b - 4. * a * c;
+
+        if disc < 0. {
+            continue;
+        }
+
+        let s = (- b - disc.sqrt()) / (2. * a);
+
+        if s > 0. && nearest.map_or(true, |n| s < n) {
+            nearest = Some(s);
+        }
+    }
+
+    nearest
+}
+
+///
+/// Grid traversal (DDA) along pos + s * (dx, dy), returning the ray
+/// parameter and face luma of the first wall or world boundary.
+///
+fn cast(world: &World, pos: Point, dx: f32, dy: f32) -> Option<(f32, f32)> {
+    let (width, height) = world.extent();
+    let (w, h) = (width as i32, height as i32);
+
+    let mut i = pos.x().floor() as i32;
+    let mut j = pos.y().floor() as i32;
+
+    let step_x = if dx > 0. { 1 } else { -1 };
+    let step_y = if dy > 0. { 1 } else { -1 };
+
+    let delta_x = if dx != 0. { 1. / dx.abs() } else { f32::INFINITY };
+    let delta_y = if dy != 0. { 1. / dy.abs() } else { f32::INFINITY };
+
+    let mut t_x = if dx > 0. {
+        (i as f32 + 1. - pos.x()) / dx
+    } else if dx < 0. {
+        (pos.x() - i as f32) / - dx
+    } else {
+        f32::INFINITY
+    };
+
+    let mut t_y = if dy > 0. {
+        (j as f32 + 1. - pos.y()) / dy
+    } else if dy < 0. {
+        (pos.y() - j as f32) / - dy
+    } else {
+        f32::INFINITY
+    };
+
+    for _ in 0..(w + h + 4) {
+        let (s, luma) = if t_x < t_y {
+            let s = t_x;
+            i += step_x;
+            t_x += delta_x;
+
+            let luma = if step_x > 0 {
+                if w <= i { RayCastRetina::WALL_S } else { RayCastRetina::WALL_N }
+            } else {
+                if i < 0 { RayCastRetina::WALL_N } else { RayCastRetina::WALL_S }
+            };
+
+            (s, luma)
+        } else {
+            let s = t_y;
+            j += step_y;
+            t_y += delta_y;
+
+            let luma = if step_y > 0 {
+                if h <= j { RayCastRetina::WALL_W } else { RayCastRetina::WALL_E }
+            } else {
+                if j < 0 { RayCastRetina::WALL_E } else { RayCastRetina::WALL_W }
+            };
+
+            (s, luma)
+        };
+
+        if i < 0 || j < 0 || w <= i || h <= j {
+            return Some((s, luma));
+        }
+
+        if let Wall::Wall = world[(i as usize, j as usize)] {
+            return Some((s, luma));
+        }
+    }
+
+    None
+}
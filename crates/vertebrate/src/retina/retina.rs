@@ -1,4 +1,4 @@
-use essay_ecs::{app::{App, Plugin, Startup}, core::{Res, ResMut}};
+use essay_ecs::{app::{App, Plugin, Startup}, core::{Component, Query, Res, ResMut}};
 use essay_graphics::api;
 use essay_plot::{
     api::{
@@ -11,45 +11,42 @@ use essay_plot::{
 use essay_tensor::tensor::Tensor;
 use mind_ecs::Tick;
 use image::Pixel;
+use std::f32::consts::TAU;
 
-use crate::{body::Body, util::{Angle, Heading}, world::{World, Wall}};
+use crate::{body::Body, util::{Angle, Heading}, world::{Predator, World, Wall}};
+
+use super::ray_cast::RayCastRetina;
 
 fn retina_update(
     body: Res<Body>,
-    mut retina: ResMut<Retina>
+    world: Res<World>,
+    mut retina: ResMut<Retina>,
+    animals: Query<(&Body, &mut Retina)>,
+    predators: Query<&Predator>,
 ) {
-    retina.draw_and_load(body.get());
-
-    let light_left = if let Some(tensor) = &retina.data_left {
-        tensor.reduce_mean()[0]
-    } else {
-        0.
-    };
-
-    let light_right = if let Some(tensor) = &retina.data_right {
-        tensor.reduce_mean()[0]
-    } else {
-        0.
-    };
-
-    retina.brighten_left = (light_left - retina.light_left) / light_left.max(0.01);
-    retina.brighten_right = (light_right - retina.light_right) / light_right.max(0.01);
-    retina.light_left = light_left;
-    retina.light_right = light_right;
-
-    // println!("Avg {:.2}({:.2}) {:.2}({:.2})", 
-    //    retina.light_left, retina.brighten_left, 
-    //    retina.light_right, retina.brighten_right
-    //);
+    let predators: Vec<&Predator> = predators.iter()
+        .filter(|p| p.is_active())
+        .collect();
+
+    if ! retina.is_startup(world.get()) {
+        retina.startup(world.get());
+    }
+
+    retina.update(body.get(), world.get(), &predators);
+
+    for (body, retina) in animals.iter() {
+        if ! retina.is_startup(world.get()) {
+            retina.startup(world.get());
+        }
+
+        retina.update(body, world.get(), &predators);
+    }
 }
 
+#[derive(Component)]
 pub struct Retina {
-    width: u32,
     size: u32,
-    wgpu: WgpuHardcopy,
-    id_left: SurfaceId,
-    _id_right: SurfaceId,
-    form_id: Option<FormId>,
+    render: RetinaRender,
 
     fov: Angle,
     eye_angle: Angle,
@@ -68,18 +65,15 @@ impl Retina {
     pub const HEIGHT : f32 = 0.3;
     pub const SIZE : usize = 16; // 8;
 
-    fn new(size: u32) -> Self {
-        let width = (2 * size).max(64);
-
-        let mut wgpu = WgpuHardcopy::new(width, size);
+    fn new(size: u32, strategy: RetinaStrategy) -> Self {
+        let render = match strategy {
+            RetinaStrategy::Wgpu => RetinaRender::Wgpu(WgpuRetina::new(size)),
+            RetinaStrategy::RayCast => RetinaRender::RayCast(RayCastRetina::new(size)),
+        };
 
         Self {
-            width,
             size,
-            id_left: wgpu.add_surface(),
-            _id_right: wgpu.add_surface(),
-            wgpu,
-            form_id: None,
+            render,
             fov: Angle::Deg(90.),
             eye_angle: Angle::Deg(90.),
 
@@ -136,6 +130,114 @@ impl Retina {
         (- self.brighten_right).max(0.)
     }
 
+    ///
+    /// The Wgpu world form is current, rebuilt after a WorldEdit.
+    ///
+    fn is_startup(&self, world: &World) -> bool {
+        match &self.render {
+            RetinaRender::Wgpu(wgpu) => {
+                wgpu.form_id.is_some() && wgpu.world_count == world.update_count()
+            }
+            RetinaRender::RayCast(_) => true,
+        }
+    }
+
+    fn startup(&mut self, world: &World) {
+        if let RetinaRender::Wgpu(wgpu) = &mut self.render {
+            wgpu.startup(world);
+        }
+    }
+
+    fn update(&mut self, body: &Body, world: &World, predators: &[&Predator]) {
+        self.draw_and_load(body, world, predators);
+
+        let light_left = if let Some(tensor) = &self.data_left {
+            tensor.reduce_mean()[0]
+        } else {
+            0.
+        };
+
+        let light_right = if let Some(tensor) = &self.data_right {
+            tensor.reduce_mean()[0]
+        } else {
+            0.
+        };
+
+        self.brighten_left = (light_left - self.light_left) / light_left.max(0.01);
+        self.brighten_right = (light_right - self.light_right) / light_right.max(0.01);
+        self.light_left = light_left;
+        self.light_right = light_right;
+
+        // println!("Avg {:.2}({:.2}) {:.2}({:.2})", 
+        //    self.light_left, self.brighten_left, 
+        //    self.light_right, self.brighten_right
+        //);
+    }
+
+    fn draw_and_load(&mut self, body: &Body, world: &World, predators: &[&Predator]) {
+        let (left, right) = match &mut self.render {
+            RetinaRender::Wgpu(wgpu) => {
+                wgpu.draw_and_load(body, predators, self.size, self.eye_angle, self.fov)
+            }
+            RetinaRender::RayCast(ray_cast) => {
+                ray_cast.draw(
+                    world, 
+                    predators,
+                    body.head_pos(), 
+                    body.head_dir(), 
+                    self.eye_angle, 
+                    self.fov
+                )
+            }
+        };
+
+        self.data_left = Some(left);
+        self.data_right = Some(right);
+    }
+}
+
+///
+/// Retina rendering backend. Wgpu renders the 3D world_form, and RayCast
+/// is a software fallback for machines without a GPU adapter.
+///
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum RetinaStrategy {
+    Wgpu,
+    RayCast,
+}
+
+enum RetinaRender {
+    Wgpu(WgpuRetina),
+    RayCast(RayCastRetina),
+}
+
+struct WgpuRetina {
+    width: u32,
+    wgpu: WgpuHardcopy,
+    id_left: SurfaceId,
+    _id_right: SurfaceId,
+    form_id: Option<FormId>,
+    predator_id: Option<FormId>,
+    world_count: usize,
+}
+
+impl WgpuRetina {
+    fn new(size: u32) -> Self {
+        let width = (2 * size).max(64);
+
+        let mut wgpu = WgpuHardcopy::new(width, size);
+
+        Self {
+            width,
+            id_left: wgpu.add_surface(),
+            _id_right: wgpu.add_surface(),
+            wgpu,
+            form_id: None,
+            predator_id: None,
+            world_count: 0,
+        }
+    }
+
     fn startup(&mut self, world: &World) {
         //let mut startup = RetinaStartup {
         //    world,
@@ -153,46 +255,62 @@ impl Retina {
 
         //self.form_id = startup.form_id;
         self.form_id = form_id;
+        self.world_count = world.update_count();
+
+        if self.predator_id.is_none() {
+            self.predator_id = self.wgpu.draw_viewless(|ui| {
+                Ok(Some(predator_form(ui)))
+            }).unwrap();
+        }
 
         assert!(self.form_id.is_some());
+        assert!(self.predator_id.is_some());
     }
 
-    fn draw_and_load(&mut self, body: &Body) {
-        let (left, right) = self.wgpu.draw_and_read(self.id_left, 
+    fn draw_and_load(
+        &mut self, 
+        body: &Body, 
+        predators: &[&Predator],
+        size: u32, 
+        eye_angle: Angle, 
+        fov: Angle
+    ) -> (Tensor, Tensor) {
+        self.wgpu.draw_and_read(self.id_left, 
             &mut DoubleDrawable {
                 width: self.width as f32,
-                size: self.size as f32,
+                size: size as f32,
                 form_id: self.form_id.unwrap(),
-                eye_angle: self.eye_angle,
+                predator_id: self.predator_id.unwrap(),
+                predators: predators.iter()
+                    .map(|p| (Point(p.pos().x(), p.pos().y()), p.radius()))
+                    .collect(),
+                eye_angle,
                 head_pos: body.head_pos().into(),
                 head_dir: body.head_dir(),
-                fov: self.fov,
+                fov,
             }, |buf| {
                 let mut vec = Vec::<f32>::new();
 
-                for j in 0..self.size {
-                    for i in 0..self.size {
+                for j in 0..size {
+                    for i in 0..size {
                         vec.push(buf.get_pixel(i, j).to_luma().0[0] as f32 / 255.);
                     }
                 }
 
-                let left = Tensor::from(vec).reshape([self.size as usize, self.size as usize]);
+                let left = Tensor::from(vec).reshape([size as usize, size as usize]);
 
                 let mut vec = Vec::<f32>::new();
-                for j in 0..self.size {
-                    for i in self.size..2 * self.size {
+                for j in 0..size {
+                    for i in size..2 * size {
                         vec.push(buf.get_pixel(i, j).to_luma().0[0] as f32 / 255.);
                     }
                 }
 
-                let right = Tensor::from(vec).reshape([self.size as usize, self.size as usize]);
+                let right = Tensor::from(vec).reshape([size as usize, size as usize]);
 
                 (left, right)
             }
-        );
-        
-        self.data_left = Some(left);
-        self.data_right = Some(right);
+        )
     }
 }
 
@@ -303,6 +421,37 @@ pub fn world_form(renderer: &mut dyn Renderer, world: &World) -> FormId {
         }
     }
 
+    for obstacle in world.obstacles() {
+        for edge in obstacle.edges() {
+            let v = edge.vector();
+            let c = if v.y().abs() > v.x().abs() { c_n } else { c_e };
+
+            wall(&mut form, [edge.0.x(), edge.0.y()], [edge.1.x(), edge.1.y()], c);
+        }
+    }
+
+    renderer.create_form(&form)
+}
+
+///
+/// Unit octagonal prism for predators, scaled and placed per draw.
+///
+fn predator_form(renderer: &mut dyn Renderer) -> FormId {
+    let mut form = Form::new();
+
+    form.texture(renderer.create_texture_rgba8(&texture_colors(&[
+        Color::black(),
+    ])));
+
+    let n = 8;
+
+    for i in 0..n {
+        let (y0, x0) = (i as f32 * TAU / n as f32).sin_cos();
+        let (y1, x1) = ((i + 1) as f32 * TAU / n as f32).sin_cos();
+
+        wall(&mut form, [x0, y0], [x1, y1], 0.5);
+    }
+
     renderer.create_form(&form)
 }
 
@@ -400,12 +549,28 @@ struct DoubleDrawable {
     width: f32,
     size: f32,
     form_id: FormId,
+    predator_id: FormId,
+    predators: Vec<(Point, f32)>,
     head_pos: Point,
     head_dir: Heading,
     eye_angle: Angle,
     fov: Angle,
 }
 
+impl DoubleDrawable {
+    fn predator_cameras(&self, eye_angle: Angle, x0: f32) -> Vec<Matrix4> {
+        self.predators.iter().map(|(pos, r)| {
+            let model = Matrix4::eye()
+                .scale(*r, Predator::HEIGHT, *r)
+                .translate(pos.x(), 0., - pos.y());
+
+            camera_model(model, self.head_pos, self.head_dir, eye_angle, self.fov)
+                .scale(self.size / self.width, 1., 1.)
+                .translate(x0, 0., 0.)
+        }).collect()
+    }
+}
+
 impl Drawable for DoubleDrawable {
     fn draw(&mut self, ui: &mut dyn Renderer) -> renderer::Result<()> {
         //let x0 = - self.size / self.width;
@@ -415,12 +580,16 @@ impl Drawable for DoubleDrawable {
             .scale(self.size / self.width, 1., 1.)
             .translate(x0, 0., 0.);
 
+        let left_predators = self.predator_cameras(self.eye_angle, x0);
+
         let pos = Bounds::<Canvas>::from([self.size, self.size]);
 
         ui.draw_with_clip(pos, Box::new(|ui| {
             RetinaDraw {
                 form_id: self.form_id,
                 camera: left_camera,
+                predator_id: self.predator_id,
+                predators: left_predators,
             }.draw(ui)
         }))?;
 
@@ -429,12 +598,16 @@ impl Drawable for DoubleDrawable {
             .scale(self.size / self.width, 1., 1.)
             .translate(x0 + dw, 0., 0.);
 
+        let right_predators = self.predator_cameras(eye_angle, x0 + dw);
+
         let pos = Bounds::<Canvas>::from(([self.size, 0.], [self.size, self.size]));
 
         ui.draw_with_clip(pos, Box::new(|ui| {
             RetinaDraw {
                 form_id: self.form_id,
                 camera: right_camera,
+                predator_id: self.predator_id,
+                predators: right_predators,
             }.draw(ui)
         }))?;
     
@@ -446,17 +619,35 @@ impl Drawable for DoubleDrawable {
 struct RetinaDraw {
     form_id: FormId,
     camera: Matrix4,
+    predator_id: FormId,
+    predators: Vec<Matrix4>,
 }
 
 impl Drawable for RetinaDraw {
     fn draw(&mut self, renderer: &mut dyn Renderer) -> renderer::Result<()> {
-        renderer.draw_form(self.form_id, &self.camera)
+        renderer.draw_form(self.form_id, &self.camera)?;
+
+        for camera in &self.predators {
+            renderer.draw_form(self.predator_id, camera)?;
+        }
+
+        Ok(())
     }
 }
 
 
 fn camera(pos: Point, dir: Heading, eye_angle: Angle, fov: Angle) -> Matrix4 {
-    let mut camera = Matrix4::eye();
+    camera_model(Matrix4::eye(), pos, dir, eye_angle, fov)
+}
+
+fn camera_model(
+    model: Matrix4,
+    pos: Point,
+    dir: Heading,
+    eye_angle: Angle,
+    fov: Angle
+) -> Matrix4 {
+    let mut camera = model;
 
     camera = camera.translate(- pos.x(), - Retina::HEIGHT, pos.y());
     camera = camera.rot_xz(api::Angle::Unit(- dir.to_unit()));
@@ -473,6 +664,7 @@ pub struct RetinaPlugin {
     size: u32,
     fov: Angle,
     eye_angle: Angle,
+    strategy: RetinaStrategy,
 
     is_enable: bool,
 }
@@ -483,11 +675,18 @@ impl RetinaPlugin {
             size: Retina::SIZE as u32,
             fov: Angle::Deg(90.),
             eye_angle: Angle::Deg(90.),
+            strategy: RetinaStrategy::Wgpu,
 
             is_enable: true,
         }
     }
 
+    pub fn strategy(&mut self, strategy: RetinaStrategy) -> &mut Self {
+        self.strategy = strategy;
+
+        self
+    }
+
     pub fn enable(&mut self, is_enable: bool) -> &mut Self {
         self.is_enable = is_enable;
 
@@ -513,15 +712,27 @@ impl RetinaPlugin {
     }
 }
 
+impl RetinaPlugin {
+    pub(crate) fn is_enable(&self) -> bool {
+        self.is_enable
+    }
+
+    pub(crate) fn create_retina(&self) -> Retina {
+        let mut retina = Retina::new(self.size, self.strategy);
+        retina.fov = self.fov;
+        retina.eye_angle = self.eye_angle;
+
+        retina
+    }
+}
+
 impl Plugin for RetinaPlugin {
     fn build(&self, app: &mut App) {
         if ! self.is_enable {
             return;
         }
 
-        let mut retina = Retina::new(self.size);
-        retina.fov = self.fov;
-        retina.eye_angle = self.eye_angle;
+        let retina = self.create_retina();
 
         app.insert_resource(retina);
 
@@ -529,3 +740,43 @@ impl Plugin for RetinaPlugin {
         app.system(Tick, retina_update);
     }
 }
+
+#[cfg(test)]
+mod test {
+    use essay_ecs::core::{error::Result, Res};
+    use mind_ecs::MindApp;
+
+    use crate::{body::BodyPlugin, world::WorldPlugin};
+
+    use super::{Retina, RetinaPlugin, RetinaStrategy};
+
+    #[test]
+    fn ray_cast_retina() -> Result<()> {
+        let mut app = MindApp::test();
+        app.plugin(WorldPlugin::new(7, 13));
+        app.plugin(BodyPlugin::new());
+
+        let mut retina = RetinaPlugin::new();
+        retina.strategy(RetinaStrategy::RayCast).size(8);
+        app.plugin(retina);
+
+        app.tick()?;
+
+        assert!(app.eval(|x: Res<Retina>| x.data_left())?.is_some());
+        assert!(app.eval(|x: Res<Retina>| x.data_right())?.is_some());
+
+        let light_left = app.eval(|x: Res<Retina>| x.light_left())?;
+        let light_right = app.eval(|x: Res<Retina>| x.light_right())?;
+
+        assert!(0. < light_left && light_left < 1.);
+        assert!(0. < light_right && light_right < 1.);
+
+        app.tick()?;
+
+        // static body and world: the image doesn't change
+        assert_eq!(light_left, app.eval(|x: Res<Retina>| x.light_left())?);
+        assert_eq!(0., app.eval(|x: Res<Retina>| x.brighten_left())?);
+
+        Ok(())
+    }
+}
@@ -3,9 +3,11 @@ mod striatum2;
 mod basal_forebrain;
 mod gate;
 mod striatum;
+mod striatum_reward;
 
 pub use basal_forebrain::{AttendId, AttendValue, BasalForebrain};
 pub use gate::{Gate, StriatumGate};
 pub use mosaic::{Mosaic, MosaicType};
 pub use striatum::{StriatumTimeout, StriatumValue, StriatumId, StriatumExclusive};
+pub use striatum_reward::StriatumReward;
 pub use striatum2::{StriatumSide, Striatum, StriatumValue2};
\ No newline at end of file
@@ -0,0 +1,116 @@
+use std::marker::PhantomData;
+
+use crate::{hippocampus::Engram64, util::lru_cache::LruCache};
+
+///
+/// StriatumReward learns the value of each context from the dopamine
+/// reward prediction error. A positive error potentiates the context's
+/// value (LTP) and a negative error depresses it (LTD).
+///
+/// Values are in [-1, 1], where positive contexts predicted food and
+/// negative contexts predicted bitter taste or sickness.
+///
+pub struct StriatumReward<T> {
+    ltp: f32,
+    ltd: f32,
+
+    cache: LruCache<Engram64, Item>,
+
+    marker: PhantomData<fn(T)>,
+}
+
+impl<T> StriatumReward<T> {
+    pub fn new(capacity: usize) -> Self {
+        Self {
+            ltp: 0.1,
+            ltd: 0.05,
+
+            cache: LruCache::new(capacity),
+
+            marker: Default::default(),
+        }
+    }
+
+    ///
+    /// Learning rate for positive prediction errors.
+    ///
+    pub fn ltp(&mut self, rate: f32) -> &mut Self {
+        assert!(0. <= rate && rate <= 1.);
+
+        self.ltp = rate;
+
+        self
+    }
+
+    ///
+    /// Learning rate for negative prediction errors.
+    ///
+    pub fn ltd(&mut self, rate: f32) -> &mut Self {
+        assert!(0. <= rate && rate <= 1.);
+
+        self.ltd = rate;
+
+        self
+    }
+
+    ///
+    /// Learned value of the context, zero for an unknown context.
+    ///
+    pub fn value(&mut self, context: Engram64) -> f32 {
+        match self.cache.get(context) {
+            Some(entry) => entry.read(|item| item.value),
+            None => 0.,
+        }
+    }
+
+    ///
+    /// Applies the reward prediction error to the context's value.
+    ///
+    pub fn learn(&mut self, context: Engram64, rpe: f32) {
+        let rate = if rpe >= 0. { self.ltp } else { self.ltd };
+
+        self.cache.get_or_insert(context, || Item::new()).write(|item| {
+            item.value = (item.value + rate * rpe).clamp(-1., 1.);
+        });
+    }
+}
+
+struct Item {
+    value: f32,
+}
+
+impl Item {
+    fn new() -> Self {
+        Self {
+            value: 0.,
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::hippocampus::Engram64;
+
+    use super::StriatumReward;
+
+    #[test]
+    fn striatum_reward_ltp_ltd() {
+        let mut striatum = StriatumReward::<()>::new(4);
+        striatum.ltp(0.5).ltd(0.25);
+
+        let food = Engram64(1);
+        let bitter = Engram64(2);
+
+        assert_eq!(0., striatum.value(food));
+
+        striatum.learn(food, 1.);
+        assert_eq!(0.5, striatum.value(food));
+
+        striatum.learn(food, -1.);
+        assert_eq!(0.25, striatum.value(food));
+
+        striatum.learn(bitter, -1.);
+        assert_eq!(-0.25, striatum.value(bitter));
+        assert_eq!(0.25, striatum.value(food));
+    }
+}
@@ -21,12 +21,27 @@ fn startup_camera(
             v.form_id = Some(retina::world_form(ui, &world))
         });
     });
+
+    camera.world_count = world.update_count();
 }
 
 fn draw_camera(
     body: Res<Body>,
+    world: Res<World>,
+    mut canvas: ResMut<UiCanvas>,
     mut ui_camera: ResMut<UiCamera>,
 ) {
+    if ui_camera.world_count != world.update_count() {
+        // rebuild the form after a WorldEdit
+        canvas.draw_viewless(|ui| {
+            ui_camera.view.write(|v| {
+                v.form_id = Some(retina::world_form(ui, &world))
+            });
+        });
+
+        ui_camera.world_count = world.update_count();
+    }
+
     let mut camera = Matrix4::eye();
 
     let head_pos = body.head_pos();
@@ -48,6 +63,7 @@ struct UiCamera {
     view: View<UiCameraView>,
 
     fov: Angle,
+    world_count: usize,
 }
 
 impl UiCamera {
@@ -55,6 +71,7 @@ impl UiCamera {
         Self {
             view,
             fov: Angle::Deg(90.),
+            world_count: 0,
         }
     }
 
@@ -7,7 +7,7 @@ use essay_tensor::tensor::Tensor;
 use renderer::{Canvas, Drawable, Renderer};
 use ui_graphics::ViewPlugin;
 
-use crate::world::{Food, FoodKind, Odor, OdorInnate, OdorKind, World, WorldPlugin};
+use crate::world::{Food, FoodKind, Odor, OdorInnate, OdorKind, Predator, World, WorldPlugin};
 
 use crate::world::Wall;
 
@@ -15,6 +15,7 @@ pub fn draw_world(
     world: Res<World>, 
     odors: Query<&Odor<OdorKind>>, 
     foods: Query<&Food>,
+    predators: Query<&Predator>,
     mut ui_world: ResMut<UiWorld>, 
     // mut ui_canvas: ResMut<UiCanvas>
 ) {
@@ -65,6 +66,24 @@ pub fn draw_world(
     };
 
     ui_world.view.write(|v| v.food = Some(food));
+
+    let mut xy : Vec<[f32; 2]> = Vec::new();
+    let mut sizes : Vec<[f32; 2]> = Vec::new();
+
+    for predator in predators.iter().filter(|p| p.is_active()) {
+        let pos = predator.pos();
+
+        xy.push([pos.x(), pos.y()]);
+        sizes.push([predator.radius(), predator.radius()]);
+    }
+
+    let predator = UiFood {
+        xy: xy.into(),
+        sizes: sizes.into(),
+        colors: vec![Color::from("black").to_rgba()].into(),
+    };
+
+    ui_world.view.write(|v| v.predator = Some(predator));
 }
 
 #[derive(Component)]
@@ -156,7 +175,10 @@ pub struct UiWorldView {
 
     colors: Option<Tensor<u8>>,
     image: Option<TextureId>,
+    world_count: usize,
+    obstacles: Vec<(Vec<[f32; 2]>, bool)>,
     food: Option<UiFood>,
+    predator: Option<UiFood>,
 
     food_x: f32,
 }
@@ -170,7 +192,10 @@ impl UiWorldView {
 
             colors: None,
             image: None,
+            world_count: 0,
+            obstacles: Vec::new(),
             food: None,
+            predator: None,
             food_x: 0.,
 
             clip: Clip::None,
@@ -180,6 +205,19 @@ impl UiWorldView {
     }
 
     fn image(&mut self, world: &World) {
+        if self.world_count != world.update_count() {
+            // rebuild the texture after a WorldEdit
+            self.world_count = world.update_count();
+            self.colors = None;
+            self.image = None;
+
+            self.obstacles = world.obstacles().iter().map(|obstacle| {
+                let (points, is_closed) = obstacle.outline();
+
+                (points.iter().map(|p| [p.x(), p.y()]).collect(), is_closed)
+            }).collect();
+        }
+
         if self.colors.is_none() {
             let mut vec = Vec::<[u8; 4]>::new();
     
@@ -275,6 +313,17 @@ impl Drawable for UiWorldView {
             }
         }
 
+        if let Some(predator) = &self.predator {
+            if predator.xy.len() > 0 {
+                let circle: Path<Canvas> = paths::circle()
+                    .transform(&self.to_canvas_view);
+
+                let style = PathStyle::new();
+
+                ui.draw_markers(&circle, &style, &predator.to_marker_style(&self.to_canvas))?;
+            }
+        }
+
         if self.image.is_none() {
             if let Some(colors) = &self.colors {
                 self.image = Some(ui.create_texture_rgba8(colors));
@@ -305,6 +354,32 @@ impl Drawable for UiWorldView {
             ui.draw_mesh2d(&mesh, *image, &[Color::white().with_alpha(1.).into()])?;
         }
 
+        for (points, is_closed) in &self.obstacles {
+            let to_canvas = |p: &[f32; 2]| self.to_canvas.transform_point(Point(p[0], p[1]));
+
+            let Point(x0, y0) = to_canvas(&points[0]);
+            let mut builder = Path::<Canvas>::move_to(x0, y0);
+
+            for p in &points[1..points.len() - 1] {
+                let Point(x, y) = to_canvas(p);
+                builder = builder.line_to(x, y);
+            }
+
+            let Point(x, y) = to_canvas(&points[points.len() - 1]);
+
+            let path: Path<Canvas> = if *is_closed {
+                builder.line_to(x, y).close_poly(x0, y0).into()
+            } else {
+                builder.line_to(x, y).to_path()
+            };
+
+            let mut style = PathStyle::new();
+            style.color(Color::from(&Wall::Wall));
+            style.line_width(3.);
+
+            ui.draw_path(&path, &style)?;
+        }
+
         Ok(())
     }
 }
@@ -0,0 +1,242 @@
+use super::{DecayValue, HalfLife, Seconds};
+
+///
+/// Habituation reduces the response to a repeated stimulus. Each
+/// stimulus has its own habituation level, which increases at every
+/// stimulus onset and decays back to zero as spontaneous recovery.
+///
+/// A novel strong stimulus dishabituates the other stimuli, and
+/// sensitization from an aversive event increases all responses.
+///
+pub struct Habituation {
+    decrement: f32,
+    threshold: f32,
+    dishabituation: f32,
+    dishabituation_threshold: f32,
+    sensitization_gain: f32,
+
+    stimuli: Vec<Stimulus>,
+    sensitization: DecayValue,
+}
+
+impl Habituation {
+    // habituation level of a novel stimulus
+    const NOVEL : f32 = 0.1;
+
+    pub fn new(n: usize) -> Self {
+        assert!(n > 0);
+
+        let recovery: HalfLife = Seconds(30.).into();
+
+        Self {
+            decrement: 0.2,
+            threshold: 0.1,
+            dishabituation: 0.5,
+            dishabituation_threshold: 0.8,
+            sensitization_gain: 1.,
+
+            stimuli: (0..n).map(|_| Stimulus::new(recovery)).collect(),
+            sensitization: DecayValue::new(Seconds(10.)),
+        }
+    }
+
+    ///
+    /// Habituation increase in [0, 1] for each full stimulus onset.
+    ///
+    pub fn decrement(mut self, decrement: f32) -> Self {
+        assert!(0. <= decrement && decrement <= 1.);
+
+        self.decrement = decrement;
+
+        self
+    }
+
+    ///
+    /// Half-life of spontaneous recovery.
+    ///
+    pub fn recovery(mut self, half_life: impl Into<HalfLife>) -> Self {
+        let half_life = half_life.into();
+
+        for stimulus in &mut self.stimuli {
+            stimulus.level.set_decay(half_life);
+        }
+
+        self
+    }
+
+    ///
+    /// Minimum stimulus for an onset.
+    ///
+    pub fn threshold(mut self, threshold: f32) -> Self {
+        assert!(threshold >= 0.);
+
+        self.threshold = threshold;
+
+        self
+    }
+
+    ///
+    /// Fraction of the other habituation removed by a novel stimulus
+    /// above the threshold.
+    ///
+    pub fn dishabituation(mut self, threshold: f32, fraction: f32) -> Self {
+        assert!(0. <= fraction && fraction <= 1.);
+
+        self.dishabituation_threshold = threshold;
+        self.dishabituation = fraction;
+
+        self
+    }
+
+    ///
+    /// Sensitization half-life and its gain on the response.
+    ///
+    pub fn sensitization(mut self, half_life: impl Into<HalfLife>, gain: f32) -> Self {
+        assert!(gain >= 0.);
+
+        self.sensitization.set_decay(half_life);
+        self.sensitization_gain = gain;
+
+        self
+    }
+
+    ///
+    /// Habituation level in [0, 1] of the stimulus.
+    ///
+    #[inline]
+    pub fn level(&self, i: usize) -> f32 {
+        self.stimuli[i].level.value()
+    }
+
+    ///
+    /// Sensitization level in [0, 1].
+    ///
+    #[inline]
+    pub fn sensitized(&self) -> f32 {
+        self.sensitization.value()
+    }
+
+    ///
+    /// Response gain for the stimulus.
+    ///
+    pub fn gain(&self, i: usize) -> f32 {
+        (1. - self.level(i)) * (1. + self.sensitization_gain * self.sensitization.value())
+    }
+
+    ///
+    /// Presents the stimulus, returning the habituated response. Only
+    /// the onset of a stimulus increases its habituation, so a sustained
+    /// stimulus is one repetition.
+    ///
+    pub fn stimulus(&mut self, i: usize, value: f32) -> f32 {
+        let value = value.max(0.);
+        let response = value * self.gain(i);
+
+        if value < self.threshold {
+            return response;
+        }
+
+        let is_onset = ! self.stimuli[i].is_present && ! self.stimuli[i].was_present;
+        self.stimuli[i].is_present = true;
+
+        if is_onset {
+            if self.dishabituation_threshold <= value && self.level(i) < Self::NOVEL {
+                for (j, stimulus) in self.stimuli.iter_mut().enumerate() {
+                    if i != j {
+                        let level = stimulus.level.value();
+                        stimulus.level.set(level * (1. - self.dishabituation));
+                    }
+                }
+            }
+
+            let level = self.level(i);
+            let level = level + self.decrement * value.min(1.) * (1. - level);
+            self.stimuli[i].level.set(level.clamp(0., 1.));
+        }
+
+        response
+    }
+
+    ///
+    /// Sensitization in [0, 1] from an aversive event.
+    ///
+    pub fn sensitize(&mut self, value: f32) {
+        self.sensitization.set_max(value.clamp(0., 1.));
+    }
+
+    pub fn update(&mut self) {
+        for stimulus in &mut self.stimuli {
+            stimulus.level.update();
+            stimulus.was_present = stimulus.is_present;
+            stimulus.is_present = false;
+        }
+
+        self.sensitization.update();
+    }
+}
+
+struct Stimulus {
+    level: DecayValue,
+    is_present: bool,
+    was_present: bool,
+}
+
+impl Stimulus {
+    fn new(recovery: HalfLife) -> Self {
+        Self {
+            level: DecayValue::new(recovery),
+            is_present: false,
+            was_present: false,
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::util::Seconds;
+
+    use super::Habituation;
+
+    #[test]
+    fn habituation_recovery_dishabituation() {
+        let mut hab = Habituation::new(2).recovery(Seconds(1.));
+
+        let mut responses = Vec::new();
+
+        for _ in 0..5 {
+            responses.push(hab.stimulus(0, 1.));
+            hab.update();
+            hab.update();
+        }
+
+        // decreasing response to a repeated stimulus
+        assert_eq!(1., responses[0]);
+        assert!(responses.windows(2).all(|w| w[1] < w[0]), "{:?}", responses);
+
+        // a sustained stimulus is a single repetition
+        let level = hab.level(0);
+        hab.stimulus(0, 1.);
+        hab.update();
+        hab.stimulus(0, 1.);
+        hab.update();
+        assert!(hab.level(0) < level + 0.2 * (1. - level) + 1e-6);
+
+        // spontaneous recovery
+        let level = hab.level(0);
+        for _ in 0..10 {
+            hab.update();
+        }
+        assert!((hab.level(0) - 0.5 * level).abs() < 1e-3);
+
+        // a novel strong stimulus dishabituates
+        let level = hab.level(0);
+        hab.stimulus(1, 1.);
+        assert!(hab.level(0) < level);
+        assert_eq!(0.2, hab.level(1));
+
+        // sensitization increases the response
+        let gain = hab.gain(0);
+        hab.sensitize(1.);
+        assert!(hab.gain(0) > gain);
+    }
+}
@@ -5,6 +5,7 @@ mod command;
 mod decay_value;
 mod dir_gradient;
 mod ego_vector;
+mod habituation;
 mod point;
 mod ticks;
 mod timeout_value;
@@ -14,6 +15,7 @@ pub use command::Command;
 pub use decay_value::DecayValue;
 pub use dir_gradient::DirGradient;
 pub use ego_vector::EgoVector;
+pub use habituation::Habituation;
 pub use point::{Point, Angle, Heading, Turn, Line};
 pub use ticks::{Ticks, TickDelta, Seconds, HalfLife};
 pub use timeout_value::{Timeout, TimeoutValue, DelayValue};
@@ -1,15 +1,20 @@
+use std::path::Path;
+
 use essay_ecs::app::{App, Plugin};
+use mind_ecs::PreTick;
 
 use crate::{util::Point, world::{Food, FoodKind}};
 
-use super::{FloorType, World, Wall};
+use super::{world_edit::{update_world_edit, WorldEdit}, FloorType, Obstacle, World, Wall, WorldMap};
 
 pub struct WorldPlugin {
     width: usize,
     height: usize,
 
+    cells: Vec<((usize, usize), Wall)>,
     walls: Vec<(usize, usize)>,
     floor: Vec<FloorItem>,
+    obstacles: Vec<Obstacle>,
 }
 
 impl WorldPlugin {
@@ -18,11 +23,47 @@ impl WorldPlugin {
             width,
             height,
 
+            cells: Vec::new(),
             walls: Vec::new(),
             floor: Vec::new(),
+            obstacles: Vec::new(),
+        }
+    }
+
+    ///
+    /// Creates the world from an ASCII map using WorldMap's legend:
+    /// '#' wall, 'F' food, 'L' light floor, 'D' dark floor and '.' empty.
+    ///
+    pub fn from_ascii(map: &str) -> Self {
+        match WorldMap::from_ascii(map) {
+            Ok(map) => Self::from_map(&map),
+            Err(err) => panic!("WorldPlugin::from_ascii: {}", err),
         }
     }
 
+    ///
+    /// Creates the world from a PNG map using WorldMap::PALETTE.
+    ///
+    pub fn from_image(path: impl AsRef<Path>) -> Self {
+        match WorldMap::from_image(path, &WorldMap::PALETTE) {
+            Ok(map) => Self::from_map(&map),
+            Err(err) => panic!("WorldPlugin::from_image: {}", err),
+        }
+    }
+
+    pub fn from_map(map: &WorldMap) -> Self {
+        let mut world = Self::new(map.width(), map.height());
+
+        for (pos, symbol) in map.cells() {
+            match WorldMap::wall(symbol) {
+                Wall::Empty => {},
+                cell => world.cells.push((pos, cell)),
+            }
+        }
+
+        world
+    }
+
     #[inline]
     pub fn width(&self) -> usize {
         self.width
@@ -65,9 +106,34 @@ impl WorldPlugin {
         self
     }
 
+    ///
+    /// Thin wall from p0 to p1.
+    ///
+    pub fn segment(self, p0: impl Into<Point>, p1: impl Into<Point>) -> Self {
+        self.obstacle(Obstacle::segment(p0, p1))
+    }
+
+    pub fn polygon(self, points: &[impl Into<Point> + Copy]) -> Self {
+        self.obstacle(Obstacle::polygon(points))
+    }
+
+    pub fn circle(self, center: impl Into<Point>, r: f32) -> Self {
+        self.obstacle(Obstacle::circle(center, r))
+    }
+
+    pub fn obstacle(mut self, obstacle: Obstacle) -> Self {
+        self.obstacles.push(obstacle);
+
+        self
+    }
+
     fn create_world(&self) -> World {
         let mut world = World::new(self.width, self.height);
 
+        for (pos, cell) in &self.cells {
+            world[*pos] = *cell;
+        }
+
         for floor in &self.floor {
             let (x, y) = floor.pos;
             let (w, h) = floor.extent;
@@ -87,6 +153,10 @@ impl WorldPlugin {
             world[*wall] = Wall::Wall;
         }
 
+        for obstacle in &self.obstacles {
+            world.add_obstacle(obstacle.clone());
+        }
+
         world
     }
 }
@@ -94,6 +164,9 @@ impl WorldPlugin {
 impl Plugin for WorldPlugin {
     fn build(&self, app: &mut App) {
         app.insert_resource(self.create_world());
+
+        app.event::<WorldEdit>();
+        app.system(PreTick, update_world_edit);
     }
 }
 
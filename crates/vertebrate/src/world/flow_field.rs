@@ -0,0 +1,283 @@
+use essay_ecs::{
+    app::{App, Plugin},
+    core::{Res, ResMut},
+};
+use mind_ecs::Tick;
+
+use crate::{
+    body::{Body, BodyPlugin},
+    util::{Line, Point, Ticks},
+};
+
+use super::{OdorField, World};
+
+fn update_flow_body(
+    flow: Res<FlowField>,
+    world: Res<World>,
+    mut body: ResMut<Body>,
+) {
+    let Point(vx, vy) = flow.velocity(body.pos());
+    let scale = flow.drift / Ticks::TICKS_PER_SECOND as f32;
+
+    if vx != 0. || vy != 0. {
+        body.displace(world.get(), Point(scale * vx, scale * vy));
+    }
+}
+
+///
+/// FlowField is the water current in world units per second, a sum of
+/// uniform currents, vortices and channel flows. The current displaces
+/// the body each tick and is sensed by the lateral line. Odors in an
+/// OdorField drift with the current, including after runtime changes.
+///
+#[derive(Clone, Debug)]
+pub struct FlowField {
+    sources: Vec<FlowSource>,
+    drift: f32,
+
+    is_changed: bool,
+}
+
+impl FlowField {
+    pub fn velocity(&self, pos: impl Into<Point>) -> Point {
+        let pos = pos.into();
+
+        self.sources.iter().fold(Point(0., 0.), |v, source| v + source.velocity(pos))
+    }
+
+    #[inline]
+    pub fn sources(&self) -> &Vec<FlowSource> {
+        &self.sources
+    }
+
+    pub fn add(&mut self, source: FlowSource) {
+        self.sources.push(source);
+        self.is_changed = true;
+    }
+
+    pub fn clear(&mut self) {
+        self.sources.clear();
+        self.is_changed = true;
+    }
+
+    ///
+    /// True once after a runtime change to the sources.
+    ///
+    pub(super) fn take_changed(&mut self) -> bool {
+        std::mem::replace(&mut self.is_changed, false)
+    }
+}
+
+///
+/// Vortex is a Rankine vortex, turning counter-clockwise for positive
+/// speed, with solid rotation inside the core radius r and speed at r.
+/// Channel is a parabolic profile along the line, with speed at the
+/// center falling to zero at width from the line.
+///
+#[derive(Clone, Debug)]
+pub enum FlowSource {
+    Uniform(Point),
+    Vortex { center: Point, r: f32, speed: f32 },
+    Channel { line: Line, width: f32, speed: f32 },
+}
+
+impl FlowSource {
+    pub fn velocity(&self, pos: Point) -> Point {
+        match self {
+            FlowSource::Uniform(v) => *v,
+            FlowSource::Vortex { center, r, speed } => {
+                let Point(dx, dy) = pos - *center;
+                let d = dx.hypot(dy);
+
+                if d < 1e-6 {
+                    return Point(0., 0.);
+                }
+
+                let v = if d < *r { speed * d / r } else { speed * r / d };
+
+                Point(- v * dy / d, v * dx / d)
+            }
+            FlowSource::Channel { line, width, speed } => {
+                let d = line.dist_point(pos);
+                let len = line.len();
+
+                if d >= *width || len <= 0. {
+                    return Point(0., 0.);
+                }
+
+                let v = speed * (1. - (d / width).powi(2));
+                let Point(dx, dy) = line.vector();
+
+                Point(v * dx / len, v * dy / len)
+            }
+        }
+    }
+}
+
+pub struct FlowFieldPlugin {
+    sources: Vec<FlowSource>,
+    drift: f32,
+}
+
+impl FlowFieldPlugin {
+    pub fn new() -> Self {
+        Self {
+            sources: Vec::new(),
+            drift: 1.,
+        }
+    }
+
+    ///
+    /// Uniform current in world units per second.
+    ///
+    pub fn uniform(mut self, vx: f32, vy: f32) -> Self {
+        self.sources.push(FlowSource::Uniform(Point(vx, vy)));
+
+        self
+    }
+
+    pub fn vortex(mut self, center: impl Into<Point>, r: f32, speed: f32) -> Self {
+        assert!(r > 0.);
+
+        self.sources.push(FlowSource::Vortex { center: center.into(), r, speed });
+
+        self
+    }
+
+    pub fn channel(
+        mut self,
+        p0: impl Into<Point>,
+        p1: impl Into<Point>,
+        width: f32,
+        speed: f32
+    ) -> Self {
+        assert!(width > 0.);
+
+        let line = Line(p0.into(), p1.into());
+
+        self.sources.push(FlowSource::Channel { line, width, speed });
+
+        self
+    }
+
+    ///
+    /// Fraction of the current that displaces the body, where 0 senses
+    /// the flow without being carried.
+    ///
+    pub fn drift(mut self, drift: f32) -> Self {
+        assert!(drift >= 0.);
+
+        self.drift = drift;
+
+        self
+    }
+
+    fn create_field(&self) -> FlowField {
+        FlowField {
+            sources: self.sources.clone(),
+            drift: self.drift,
+
+            is_changed: false,
+        }
+    }
+}
+
+impl Plugin for FlowFieldPlugin {
+    fn build(&self, app: &mut App) {
+        assert!(app.contains_resource::<World>(), "FlowFieldPlugin requires World");
+        assert!(app.contains_plugin::<BodyPlugin>(), "FlowFieldPlugin requires BodyPlugin");
+
+        let field = self.create_field();
+
+        // odors drift with the current
+        if let Some(odor_field) = app.get_mut_resource::<OdorField>() {
+            odor_field.set_flow(|p| field.velocity(p));
+        }
+
+        app.insert_resource(field);
+
+        app.system(Tick, update_flow_body);
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use essay_ecs::{core::error::Result, prelude::{Res, ResMut}};
+    use mind_ecs::MindApp;
+
+    use crate::{
+        body::{Body, BodyPlugin}, 
+        util::Point, 
+        world::{OdorField, OdorFieldPlugin, OdorKind, OdorPlugin, WorldPlugin}
+    };
+
+    use super::{FlowField, FlowFieldPlugin, FlowSource};
+
+    #[test]
+    fn flow_velocity() {
+        let field = FlowFieldPlugin::new()
+            .vortex([5., 5.], 1., 2.)
+            .create_field();
+
+        // counter-clockwise vortex, slower outside the core
+        let v = field.velocity((7., 5.));
+        assert!(v.x().abs() < 1e-5 && (v.y() - 1.).abs() < 1e-5);
+
+        let field = FlowFieldPlugin::new()
+            .channel([0., 2.], [10., 2.], 1., 1.)
+            .create_field();
+
+        assert_eq!(Point(1., 0.), field.velocity((5., 2.)));
+        assert!(field.velocity((5., 2.5)).x() < 1.);
+        assert_eq!(Point(0., 0.), field.velocity((5., 3.5)));
+    }
+
+    #[test]
+    fn flow_drift() -> Result<()> {
+        let mut app = MindApp::test();
+        app.plugin(WorldPlugin::new(15, 15));
+        app.plugin(BodyPlugin::new().pos((5., 5.)));
+        app.plugin(FlowFieldPlugin::new().uniform(1., 0.));
+
+        let x0 = app.eval(|x: Res<Body>| x.pos().x())?;
+
+        for _ in 0..10 {
+            app.tick()?;
+        }
+
+        let x1 = app.eval(|x: Res<Body>| x.pos().x())?;
+        assert!((x1 - x0 - 1.).abs() < 0.1);
+
+        assert_eq!(Point(1., 0.), app.eval(|x: Res<FlowField>| x.velocity((3., 3.)))?);
+
+        Ok(())
+    }
+
+    #[test]
+    fn flow_odor_runtime_change() -> Result<()> {
+        let mut app = MindApp::test();
+        app.plugin(WorldPlugin::new(15, 5));
+        app.plugin(BodyPlugin::new().pos((1., 1.)));
+
+        let mut odor = OdorPlugin::new();
+        odor.odor(7, 2, OdorKind::FoodA);
+        app.plugin(odor);
+
+        app.plugin(OdorFieldPlugin::new().scale(1));
+        app.plugin(FlowFieldPlugin::new().drift(0.));
+
+        // a current added at runtime carries the odor downstream
+        app.eval(|mut x: ResMut<FlowField>| x.add(FlowSource::Uniform(Point(2., 0.))))?;
+
+        for _ in 0..20 {
+            app.tick()?;
+        }
+
+        let up = app.eval(|x: Res<OdorField>| x.concentration(OdorKind::FoodA, (5.5, 2.5)))?;
+        let down = app.eval(|x: Res<OdorField>| x.concentration(OdorKind::FoodA, (9.5, 2.5)))?;
+
+        assert!(down > up);
+
+        Ok(())
+    }
+}
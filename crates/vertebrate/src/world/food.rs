@@ -1,22 +1,24 @@
 use essay_ecs::{
     app::{App, Plugin, Startup}, 
-    core::{entity::EntityId, Commands, Component, Query, Res}
+    core::{entity::EntityId, Commands, Component, Query, Res, ResMut}
 };
-use mind_ecs::Tick;
+use mind_ecs::{SeedPlugin, Tick};
 use util::random::Rand32;
 
 use crate::{util::{Point, Ticks}, world::World};
 
-use super::OdorKind;
+use super::{Odor, OdorKind};
 
 fn update_food(
     query: Query<(EntityId, &Food)>,
+    odors: Query<(&Food, &mut Odor<OdorKind>)>,
     world: Res<World>,
-    gen: Res<FoodGenerator>,
+    mut gen: ResMut<FoodGenerator>,
     mut command: Commands,
 ) {
     let mut food_count = 0;
 
+    // the food's odor is on the same entity, so it's despawned with the food
     for (id, food) in query.iter() {
         if food.value <= 0. {
             command.entity(id).despawn();
@@ -25,26 +27,42 @@ fn update_food(
         food_count += 1;
     }
 
+    for (food, odor) in odors.iter() {
+        odor.set_pos(food.pos());
+    }
+
     while food_count < gen.count {
-        command.spawn(create_food(gen.get(), world.get()));
+        spawn_food(&mut command, create_food(gen.get_mut(), world.get()));
         food_count += 1;
     }
 }
 
-fn create_food(gen: &FoodGenerator, world: &World) -> Food {
-    let (width, height) = world.extent();
+fn spawn_food(command: &mut Commands, food: Food) {
+    match food.odor {
+        Some(FoodOdor { kind, r }) => {
+            let odor = Odor::new_pos(food.pos(), r, kind);
 
-    let mut rand = Rand32::new();
+            command.spawn((food, odor));
+        }
+        None => {
+            command.spawn(food);
+        }
+    }
+}
+
+fn create_food(gen: &mut FoodGenerator, world: &World) -> Food {
+    let (width, height) = world.extent();
 
     loop {
-        let x = (width - 1) as f32 * rand.next_uniform() + 0.5; 
-        let y = (height - 1) as f32 * rand.next_uniform() + 0.5; 
+        let x = (width - 1) as f32 * gen.rand.next_uniform() + 0.5; 
+        let y = (height - 1) as f32 * gen.rand.next_uniform() + 0.5; 
 
         if ! world.is_collide((x, y)) {
             let mut food = Food::new((x, y));
             food.value = gen.value;
             food.radius = gen.radius;
             food.kind = gen.kind;
+            food.odor = gen.odor;
 
             return food;
         }
@@ -59,6 +77,8 @@ pub struct Food {
     radius: f32,
     
     probability: f32,
+
+    odor: Option<FoodOdor>,
 }
 
 impl Food {
@@ -69,6 +89,7 @@ impl Food {
             value: f32::MAX,
             radius: 0.4,
             probability: 1.,
+            odor: None,
         }
     }
 
@@ -87,6 +108,11 @@ impl Food {
         self.radius
     }
 
+    #[inline]
+    pub fn odor(&self) -> Option<OdorKind> {
+        self.odor.map(|odor| odor.kind)
+    }
+
     #[inline]
     pub fn is_pos(&self, pos: impl Into<Point>) -> bool {
         self.pos.dist(pos) < self.radius
@@ -96,8 +122,8 @@ impl Food {
     /// Stochastic food eating
     /// 
     #[inline]
-    pub fn eat_probability(&mut self) -> bool {
-        if self.value >= 1. && Rand32::new().next_uniform() <= self.probability {
+    pub fn eat_probability(&mut self, rand: &mut Rand32) -> bool {
+        if self.value >= 1. && rand.next_uniform() <= self.probability {
             self.value -= 1.;
             true
         } else {
@@ -106,6 +132,18 @@ impl Food {
     }
 }
 
+#[derive(Debug, Clone, Copy)]
+struct FoodOdor {
+    kind: OdorKind,
+    r: f32,
+}
+
+impl FoodOdor {
+    fn new(kind: OdorKind, r: f32) -> Self {
+        Self { kind, r }
+    }
+}
+
 #[derive(Debug, Clone, Copy, PartialEq)]
 pub enum FoodKind {
     None,
@@ -128,6 +166,8 @@ struct FoodGenerator {
     radius: f32,
     value: f32,
     kind: FoodKind,
+    odor: Option<FoodOdor>,
+    rand: Rand32,
 }
 
 impl Default for FoodGenerator {
@@ -137,6 +177,8 @@ impl Default for FoodGenerator {
             radius: 1.,
             value: f32::MAX,
             kind: FoodKind::Plain,
+            odor: None,
+            rand: Rand32::new(),
         }
     }
 }
@@ -222,18 +264,31 @@ impl FoodPlugin {
         self
     }
 
-    pub fn odor(&mut self, _odor: OdorKind) -> &mut Self {
-        todo!();
-        // self.food.last_mut().unwrap().odor(odor);
+    pub fn gen_odor(&mut self, odor: OdorKind) -> &mut Self {
+        self.gen.odor = Some(FoodOdor::new(odor, Odor::<OdorKind>::RADIUS));
 
-        //self
+        self
     }
 
-    pub fn odor_r(&mut self, _r: usize, _odor: OdorKind) -> &mut Self {
-        todo!();
-        // self.food.last_mut().unwrap().odor_r(odor, r);
+    pub fn gen_odor_r(&mut self, r: usize, odor: OdorKind) -> &mut Self {
+        self.gen.odor = Some(FoodOdor::new(odor, r as f32));
 
-        //self
+        self
+    }
+
+    ///
+    /// The last food emits the odor with the default radius.
+    ///
+    pub fn odor(&mut self, odor: OdorKind) -> &mut Self {
+        self.food.last_mut().unwrap().odor = Some(FoodOdor::new(odor, Odor::<OdorKind>::RADIUS));
+
+        self
+    }
+
+    pub fn odor_r(&mut self, r: usize, odor: OdorKind) -> &mut Self {
+        self.food.last_mut().unwrap().odor = Some(FoodOdor::new(odor, r as f32));
+
+        self
     }
 
     pub fn base_food(&mut self, kind: FoodKind) -> &mut Self {
@@ -247,7 +302,7 @@ impl FoodPlugin {
 
         app.system(Startup, move |mut cmd: Commands| {
             for food in foods.drain(..) {
-                cmd.spawn(food);
+                spawn_food(&mut cmd, food);
             }
         });
     }
@@ -260,7 +315,9 @@ impl Plugin for FoodPlugin {
 
         self.create_food(app);
 
-        app.insert_resource(self.gen.clone());
+        let mut gen = self.gen.clone();
+        gen.rand = SeedPlugin::rand32(app, "food");
+        app.insert_resource(gen);
 
         if self.base_food != FoodKind::None {
             let mut food = Food::new(Point(0., 0.));
@@ -272,3 +329,99 @@ impl Plugin for FoodPlugin {
         app.system(Tick, update_food);
     }
 }
+
+#[cfg(test)]
+mod test {
+    use essay_ecs::core::{error::Result, Query};
+    use mind_ecs::MindApp;
+
+    use crate::world::{Odor, OdorKind, WorldPlugin};
+
+    use super::{Food, FoodPlugin};
+
+    #[test]
+    fn food_odor_r() -> Result<()> {
+        let mut app = MindApp::test();
+        app.plugin(WorldPlugin::new(10, 10));
+
+        let mut food = FoodPlugin::new();
+        food.food(3, 4).odor_r(2, OdorKind::FoodA);
+        app.plugin(food);
+
+        app.tick()?;
+
+        let odors = app.eval(|q: Query<(&Food, &Odor<OdorKind>)>| {
+            q.iter().map(|(food, odor)| {
+                (food.pos(), food.odor(), odor.pos(), odor.r(), *odor.odor())
+            }).collect::<Vec<_>>()
+        })?;
+
+        assert_eq!(1, odors.len());
+
+        let (food_pos, food_odor, odor_pos, r, kind) = odors[0];
+        assert_eq!(Some(OdorKind::FoodA), food_odor);
+        assert_eq!(food_pos, odor_pos);
+        assert_eq!(2., r);
+        assert_eq!(OdorKind::FoodA, kind);
+
+        Ok(())
+    }
+
+    #[test]
+    fn food_gen_odor() -> Result<()> {
+        let mut app = MindApp::test();
+        app.plugin(WorldPlugin::new(10, 10));
+
+        let mut food = FoodPlugin::new();
+        food.gen_count(3).gen_odor_r(4, OdorKind::AvoidA);
+        app.plugin(food);
+
+        app.tick()?;
+        app.tick()?;
+
+        let odors = app.eval(|q: Query<(&Food, &Odor<OdorKind>)>| {
+            q.iter().map(|(food, odor)| {
+                (food.pos(), food.odor(), odor.pos(), odor.r(), *odor.odor())
+            }).collect::<Vec<_>>()
+        })?;
+
+        assert_eq!(3, odors.len());
+
+        for (food_pos, food_odor, odor_pos, r, kind) in odors {
+            assert_eq!(Some(OdorKind::AvoidA), food_odor);
+            assert_eq!(food_pos, odor_pos);
+            assert_eq!(4., r);
+            assert_eq!(OdorKind::AvoidA, kind);
+        }
+
+        Ok(())
+    }
+
+    #[test]
+    fn food_odor_despawn() -> Result<()> {
+        let mut app = MindApp::test();
+        app.plugin(WorldPlugin::new(10, 10));
+
+        let mut food = FoodPlugin::new();
+        food.food(3, 4).odor(OdorKind::FoodA);
+        app.plugin(food);
+
+        app.tick()?;
+        assert_eq!(1, app.eval(|q: Query<&Odor<OdorKind>>| q.iter().count())?);
+
+        // eaten food
+        app.eval(|q: Query<&mut Food>| {
+            for food in q.iter() {
+                food.value = 0.;
+            }
+        })?;
+
+        app.tick()?;
+        app.tick()?;
+
+        assert_eq!(0, app.eval(|q: Query<&Food>| q.iter().count())?);
+        assert_eq!(0, app.eval(|q: Query<&Odor<OdorKind>>| q.iter().count())?);
+
+        Ok(())
+    }
+}
@@ -1,16 +1,40 @@
 mod world_hex;
 mod builder;
+mod flow_field;
 mod food;
 mod odor;
+mod obstacle;
+mod odor_field;
+mod predator;
+mod sound;
+mod temperature;
 mod world;
+mod world_edit;
+mod world_map;
 
 pub use builder::WorldPlugin;
 
+pub use flow_field::{FlowField, FlowFieldPlugin, FlowSource};
+
 pub use food::{Food, FoodKind, FoodPlugin};
 
 pub use world_hex::{WorldHex, WorldHexTrait, WorldHexPlugin};
 
-pub use odor::{Odor, OdorInnate, OdorType, OdorKind, OdorPlugin};
+pub use world_map::WorldMap;
+
+pub use world_edit::{WorldEdit, WorldHexEdit};
+
+pub use odor::{Odor, OdorFeatures, OdorInnate, OdorType, OdorKind, OdorPlugin};
+
+pub use obstacle::Obstacle;
+
+pub use odor_field::{OdorField, OdorFieldPlugin};
+
+pub use predator::{Predator, PredatorEvent, PredatorPlugin, Trajectory};
+
+pub use sound::{SoundPlugin, SoundSource};
+
+pub use temperature::{Temperature, TemperaturePlugin, TemperatureSource};
 
 pub use world::{
     FloorType, World, Wall
@@ -0,0 +1,312 @@
+use std::f32::consts::TAU;
+
+use crate::util::{Line, Point};
+
+use super::World;
+
+///
+/// Geometric obstacle layered over the World's cells, for rounded arenas,
+/// diagonal barriers and thin walls that the unit grid can't express.
+/// Segments are thin walls, while polygons and circles are solid.
+///
+#[derive(Clone, Debug)]
+pub enum Obstacle {
+    Segment(Line),
+    Polygon(Vec<Point>),
+    Circle(Point, f32),
+}
+
+impl Obstacle {
+    // half-width of segment walls for collision
+    pub const THICKNESS : f32 = 0.05;
+
+    // sides when drawing a circle
+    pub const CIRCLE_SIDES : usize = 24;
+
+    pub fn segment(p0: impl Into<Point>, p1: impl Into<Point>) -> Self {
+        Obstacle::Segment(Line(p0.into(), p1.into()))
+    }
+
+    pub fn polygon(points: &[impl Into<Point> + Copy]) -> Self {
+        assert!(points.len() >= 3, "polygon requires at least 3 points");
+
+        Obstacle::Polygon(points.iter().map(|p| (*p).into()).collect())
+    }
+
+    pub fn circle(center: impl Into<Point>, r: f32) -> Self {
+        assert!(r > 0.);
+
+        Obstacle::Circle(center.into(), r)
+    }
+
+    pub fn contains(&self, p: Point) -> bool {
+        match self {
+            Obstacle::Segment(line) => line.dist_point(p) < Self::THICKNESS,
+            Obstacle::Polygon(points) => {
+                // crossing number with a vertical ray
+                let n = points.len();
+
+                (0..n).filter(|i| p.is_below(&points[*i], &points[(i + 1) % n]))
+                    .count() % 2 == 1
+            }
+            Obstacle::Circle(center, r) => p.dist(*center) < *r,
+        }
+    }
+
+    ///
+    /// Nearest ray parameter where pos + s * (dx, dy) meets the obstacle,
+    /// and the unit surface normal facing the ray.
+    ///
+    pub fn cast(&self, pos: Point, dx: f32, dy: f32) -> Option<(f32, Point)> {
+        match self {
+            Obstacle::Circle(center, r) => cast_circle(*center, *r, pos, dx, dy),
+            _ => {
+                let mut nearest: Option<(f32, Point)> = None;
+
+                for edge in self.edges() {
+                    if let Some((s, normal)) = cast_line(&edge, pos, dx, dy) {
+                        if nearest.map_or(true, |n| s < n.0) {
+                            nearest = Some((s, normal));
+                        }
+                    }
+                }
+
+                nearest
+            }
+        }
+    }
+
+    ///
+    /// True if the line crosses the obstacle's boundary.
+    ///
+    pub fn is_cross(&self, line: &Line) -> bool {
+        match self {
+            Obstacle::Circle(center, r) => {
+                line.dist_point(*center) < *r
+                    && (line.0.dist(*center) >= *r || line.1.dist(*center) >= *r)
+            }
+            _ => self.edges().iter().any(|edge| is_intersect(edge, line)),
+        }
+    }
+
+    ///
+    /// Unit normal of the nearest boundary to p, used to slide along walls.
+    ///
+    pub fn normal(&self, p: Point) -> Point {
+        match self {
+            Obstacle::Circle(center, _) => unit(p - *center).unwrap_or(Point(1., 0.)),
+            _ => {
+                let edge = self.edges().into_iter()
+                    .min_by(|a, b| a.dist_point(p).total_cmp(&b.dist_point(p)))
+                    .unwrap();
+
+                let Point(x, y) = edge.vector();
+
+                unit(Point(- y, x)).unwrap_or(Point(1., 0.))
+            }
+        }
+    }
+
+    ///
+    /// Outline points for drawing, closed for polygons and circles.
+    ///
+    pub fn outline(&self) -> (Vec<Point>, bool) {
+        match self {
+            Obstacle::Segment(line) => (vec![line.0, line.1], false),
+            Obstacle::Polygon(points) => (points.clone(), true),
+            Obstacle::Circle(center, r) => {
+                let points = (0..Self::CIRCLE_SIDES).map(|i| {
+                    let (sin, cos) = (i as f32 * TAU / Self::CIRCLE_SIDES as f32).sin_cos();
+
+                    Point(center.x() + r * cos, center.y() + r * sin)
+                }).collect();
+
+                (points, true)
+            }
+        }
+    }
+
+    ///
+    /// Boundary edges, with circles approximated by their outline.
+    ///
+    pub fn edges(&self) -> Vec<Line> {
+        let (points, is_closed) = self.outline();
+        let n = points.len();
+
+        let n_edges = if is_closed { n } else { n - 1 };
+
+        (0..n_edges).map(|i| Line(points[i], points[(i + 1) % n])).collect()
+    }
+}
+
+impl World {
+    ///
+    /// Nearest obstacle along the ray pos + s * (dx, dy), returning the
+    /// ray parameter and surface normal.
+    ///
+    pub fn cast_obstacles(&self, pos: Point, dx: f32, dy: f32) -> Option<(f32, Point)> {
+        let mut nearest: Option<(f32, Point)> = None;
+
+        for obstacle in self.obstacles() {
+            if let Some((s, normal)) = obstacle.cast(pos, dx, dy) {
+                if nearest.map_or(true, |n| s < n.0) {
+                    nearest = Some((s, normal));
+                }
+            }
+        }
+
+        nearest
+    }
+
+    ///
+    /// True if the line crosses an obstacle, catching thin walls between
+    /// sample points.
+    ///
+    pub fn is_cross(&self, line: impl Into<Line>) -> bool {
+        let line = line.into();
+
+        self.obstacles().iter().any(|o| o.is_cross(&line))
+    }
+
+    ///
+    /// Unit normal of the wall or obstacle that p collides with.
+    ///
+    pub fn collide_normal(&self, p: impl Into<Point>) -> Option<Point> {
+        let p = p.into();
+
+        if let Some(obstacle) = self.obstacles().iter().find(|o| o.contains(p)) {
+            return Some(obstacle.normal(p));
+        }
+
+        let (width, height) = (self.width() as f32, self.height() as f32);
+
+        if p.x() <= 0. || p.x() >= width {
+            return Some(Point(1., 0.));
+        } else if p.y() <= 0. || p.y() >= height {
+            return Some(Point(0., 1.));
+        }
+
+        if ! self.is_collide(p) {
+            return None;
+        }
+
+        // the nearest cell edge is the one that was crossed
+        let (fx, fy) = (p.x().fract(), p.y().fract());
+
+        if fx.min(1. - fx) < fy.min(1. - fy) {
+            Some(Point(1., 0.))
+        } else {
+            Some(Point(0., 1.))
+        }
+    }
+}
+
+fn unit(p: Point) -> Option<Point> {
+    let len = p.x().hypot(p.y());
+
+    if len > 0. {
+        Some(Point(p.x() / len, p.y() / len))
+    } else {
+        None
+    }
+}
+
+fn is_intersect(a: &Line, b: &Line) -> bool {
+    let d0 = a.tri_det(b.0);
+    let d1 = a.tri_det(b.1);
+    let d2 = b.tri_det(a.0);
+    let d3 = b.tri_det(a.1);
+
+    d0 * d1 <= 0. && d2 * d3 <= 0. && (d0 != 0. || d1 != 0.)
+}
+
+fn cast_line(edge: &Line, pos: Point, dx: f32, dy: f32) -> Option<(f32, Point)> {
+    let Point(ex, ey) = edge.vector();
+
+    let denom = dx * ey - dy * ex;
+
+    if denom.abs() < 1e-9 {
+        return None;
+    }
+
+    let Point(wx, wy) = edge.0 - pos;
+
+    let s = (wx * ey - wy * ex) / denom;
+    let t = (wx * dy - wy * dx) / denom;
+
+    if s <= 0. || t < 0. || 1. < t {
+        return None;
+    }
+
+    let mut normal = unit(Point(- ey, ex))?;
+
+    if normal.x() * dx + normal.y() * dy > 0. {
+        normal = Point(- normal.x(), - normal.y());
+    }
+
+    Some((s, normal))
+}
+
+fn cast_circle(center: Point, r: f32, pos: Point, dx: f32, dy: f32) -> Option<(f32, Point)> {
+    let (ox, oy) = (pos.x() - center.x(), pos.y() - center.y());
+
+    let a = dx * dx + dy * dy;
+    let b = 2. * (dx * ox + dy * oy);
+    let c = ox * ox + oy * oy - r * r;
+
+    let disc = b * b - 4. * a * c;
+
+    if disc < 0. {
+        return None;
+    }
+
+    let s = (- b - disc.sqrt()) / (2. * a);
+
+    if s <= 0. {
+        return None;
+    }
+
+    let hit = Point(pos.x() + s * dx, pos.y() + s * dy);
+
+    Some((s, unit(hit - center)?))
+}
+
+#[cfg(test)]
+mod test {
+    use crate::{util::{Line, Point}, world::World};
+
+    use super::Obstacle;
+
+    #[test]
+    fn obstacle_geometry() {
+        let square = Obstacle::polygon(&[[1., 1.], [3., 1.], [3., 3.], [1., 3.]]);
+
+        assert!(square.contains(Point(2., 2.)));
+        assert!(! square.contains(Point(4., 2.)));
+
+        let (s, normal) = square.cast(Point(0., 2.), 1., 0.).unwrap();
+        assert!((s - 1.).abs() < 1e-5);
+        assert_eq!(Point(-1., 0.), normal);
+
+        let segment = Obstacle::segment([2., 0.], [2., 4.]);
+        assert!(segment.is_cross(&Line(Point(1., 1.), Point(3., 1.))));
+        assert!(! segment.is_cross(&Line(Point(1., 1.), Point(1.5, 1.))));
+
+        let circle = Obstacle::circle([5., 5.], 1.);
+        let (s, _) = circle.cast(Point(5., 2.), 0., 1.).unwrap();
+        assert!((s - 2.).abs() < 1e-5);
+    }
+
+    #[test]
+    fn world_obstacle_collide() {
+        let mut world = World::new(8, 8);
+        world.add_obstacle(Obstacle::segment([0., 0.], [8., 8.]));
+
+        assert!(world.is_collide((4., 4.02)));
+        assert!(! world.is_collide((4., 5.)));
+        assert!(world.is_cross(Line(Point(3., 4.), Point(4., 3.))));
+
+        let normal = world.collide_normal((4., 4.02)).unwrap();
+        assert!((normal.x() + normal.y()).abs() < 1e-5);
+    }
+}
@@ -11,6 +11,7 @@ pub struct Odor<T: OdorType> {
     pos: Point,
     r: f32,
     odor: T,
+    features: OdorFeatures,
 }
 
 impl<T: OdorType> Odor<T> {
@@ -20,10 +21,28 @@ impl<T: OdorType> Odor<T> {
         Self {
             pos: Point(x as f32 + 0.5, y as f32 + 0.5),
             r: r as f32,
+            features: odor.features(),
             odor,
         }
     }
 
+    pub(super) fn new_pos(pos: Point, r: f32, odor: T) -> Self {
+        Self {
+            pos,
+            r,
+            features: odor.features(),
+            odor,
+        }
+    }
+
+    pub(super) fn set_features(&mut self, features: OdorFeatures) {
+        self.features = features;
+    }
+
+    pub(super) fn set_pos(&mut self, pos: Point) {
+        self.pos = pos;
+    }
+
     #[inline]
     pub fn x(&self) -> f32 {
         self.pos.x()
@@ -56,6 +75,14 @@ impl<T: OdorType> Odor<T> {
     pub fn odor(&self) -> &T {
         &self.odor
     }
+
+    ///
+    /// Molecular features, which differ from the kind's for mixtures.
+    ///
+    #[inline]
+    pub fn features(&self) -> &OdorFeatures {
+        &self.features
+    }
 }
 
 impl<T: OdorType> fmt::Debug for Odor<T> {
@@ -96,6 +123,96 @@ impl OdorInnate {
 // #[derive(Clone, Debug, Copy, PartialEq, Eq, Hash)]
 pub trait OdorType : Clone + fmt::Debug + Send + Sync + 'static {
     fn innate(&self) -> OdorInnate;
+
+    fn features(&self) -> OdorFeatures {
+        OdorFeatures::zero()
+    }
+}
+
+///
+/// Molecular descriptor of an odor as intensities along a small set of
+/// feature axes, like carbon chain length or functional group. Similar
+/// odors share features, and a mixture is the weighted sum of its
+/// components.
+///
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct OdorFeatures([f32; OdorFeatures::N]);
+
+impl OdorFeatures {
+    pub const N : usize = 8;
+
+    pub fn new(features: [f32; Self::N]) -> Self {
+        Self(features)
+    }
+
+    #[inline]
+    pub fn zero() -> Self {
+        Self([0.; Self::N])
+    }
+
+    ///
+    /// Single feature peak with its neighbors at half strength.
+    ///
+    pub fn peak(i: usize) -> Self {
+        assert!(i < Self::N);
+
+        let mut features = [0.; Self::N];
+        features[i] = 1.;
+
+        if i > 0 {
+            features[i - 1] = 0.5;
+        }
+
+        if i + 1 < Self::N {
+            features[i + 1] = 0.5;
+        }
+
+        Self(features)
+    }
+
+    #[inline]
+    pub fn features(&self) -> &[f32; Self::N] {
+        &self.0
+    }
+
+    #[inline]
+    pub fn is_zero(&self) -> bool {
+        self.0.iter().all(|v| *v == 0.)
+    }
+
+    pub fn add(&mut self, other: &OdorFeatures, weight: f32) {
+        for (a, b) in self.0.iter_mut().zip(other.0.iter()) {
+            *a += weight * b;
+        }
+    }
+
+    ///
+    /// Weighted mixture of odors.
+    ///
+    pub fn mixture<T: OdorType>(odors: &[(T, f32)]) -> Self {
+        let mut features = Self::zero();
+
+        for (odor, weight) in odors {
+            features.add(&odor.features(), *weight);
+        }
+
+        features
+    }
+
+    ///
+    /// Cosine similarity in [0, 1] for non-negative features.
+    ///
+    pub fn similarity(&self, other: &OdorFeatures) -> f32 {
+        let dot: f32 = self.0.iter().zip(other.0.iter()).map(|(a, b)| a * b).sum();
+        let len_a = self.0.iter().map(|a| a * a).sum::<f32>().sqrt();
+        let len_b = other.0.iter().map(|b| b * b).sum::<f32>().sqrt();
+
+        if len_a > 0. && len_b > 0. {
+            dot / (len_a * len_b)
+        } else {
+            0.
+        }
+    }
 }
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
@@ -128,6 +245,18 @@ impl OdorType for OdorKind {
             _ => OdorInnate::None,
         }
     }
+
+    // food odors overlap, as do the avoid odors
+    fn features(&self) -> OdorFeatures {
+        match self {
+            OdorKind::FoodA => OdorFeatures::peak(1),
+            OdorKind::FoodB => OdorFeatures::peak(2),
+            OdorKind::OtherA => OdorFeatures::peak(4),
+            OdorKind::AvoidA => OdorFeatures::peak(6),
+            OdorKind::AvoidB => OdorFeatures::peak(7),
+            _ => OdorFeatures::zero(),
+        }
+    }
 }
 
 pub struct OdorPlugin {
@@ -160,12 +289,36 @@ impl OdorPlugin {
 
         self
     }
+
+    ///
+    /// Odor source emitting a weighted mixture. The source's kind is the
+    /// strongest component, while receptors and the bulb's glomeruli see
+    /// the mixed features.
+    ///
+    pub fn mixture(&mut self, x: usize, y: usize, r: usize, odors: &[(OdorKind, f32)]) -> &mut Self {
+        let (kind, _) = odors.iter()
+            .max_by(|a, b| a.1.total_cmp(&b.1))
+            .expect("mixture requires an odor");
+
+        let mut item = OdorItem::new(x, y, r, *kind);
+        item.features = Some(OdorFeatures::mixture(odors));
+
+        self.odors.push(item);
+
+        self
+    }
 }
 
 impl Plugin for OdorPlugin {
     fn build(&self, app: &mut App) {
-        let mut odors : Vec<Odor<OdorKind>> = self.odors.iter().map(|odor| {
-            Odor::new_r(odor.pos.0, odor.pos.1, odor.r, odor.odor)
+        let mut odors : Vec<Odor<OdorKind>> = self.odors.iter().map(|item| {
+            let mut odor = Odor::new_r(item.pos.0, item.pos.1, item.r, item.odor);
+
+            if let Some(features) = item.features {
+                odor.set_features(features);
+            }
+
+            odor
         }).collect();
     
         app.system(Startup, move |mut cmd: Commands| {
@@ -180,6 +333,7 @@ struct OdorItem<T: OdorType> {
     pos: (usize, usize),
     r: usize,
     odor: T,
+    features: Option<OdorFeatures>,
 }
 
 impl<T: OdorType> OdorItem<T> {
@@ -187,7 +341,8 @@ impl<T: OdorType> OdorItem<T> {
         Self { 
             pos: (x, y), 
             r,
-            odor 
+            odor,
+            features: None,
         }
     }
 }
@@ -0,0 +1,596 @@
+use std::collections::HashMap;
+
+use essay_ecs::{app::{App, Plugin}, core::Query, prelude::{Res, ResMut}};
+use mind_ecs::PreTick;
+
+use crate::util::{HalfLife, Point, Seconds, Ticks};
+
+use super::{FlowField, Odor, OdorFeatures, OdorKind, OdorType, Wall, World};
+
+fn update_odor_field(
+    world: Res<World>,
+    odors: Query<&Odor<OdorKind>>,
+    mut field: ResMut<OdorField>,
+    flow: Option<ResMut<FlowField>>,
+) {
+    // re-sample the current after runtime changes, before advecting
+    if let Some(mut flow) = flow {
+        if flow.take_changed() {
+            let flow = flow.get();
+            field.set_flow(|p| flow.velocity(p));
+        }
+    }
+
+    for odor in odors.iter() {
+        let rate = field.rate;
+        field.set_features(*odor.odor(), *odor.features());
+        field.emit(*odor.odor(), odor.pos(), rate);
+    }
+
+    field.update(world.get());
+}
+
+///
+/// OdorField is a concentration grid per odor kind, on the world grid or
+/// a finer sub-grid. Each tick sources emit, the plume is advected by
+/// the optional flow, diffuses between open cells and decays. Wall cells
+/// hold no odor and block diffusion.
+///
+/// Each layer carries the molecular features of its sources, so a
+/// mixture source's plume has the mixed features. Sources of the same
+/// kind share a layer and its features.
+///
+pub struct OdorField {
+    width: usize,
+    height: usize,
+    scale: usize,
+
+    layers: Vec<Vec<f32>>,
+    layer_map: HashMap<OdorKind, usize>,
+    features: Vec<OdorFeatures>,
+
+    // world units per second at each sub-cell
+    flow: Option<Vec<Point>>,
+
+    diffusion: f32,
+    decay: f32,
+    rate: f32,
+    saturation: f32,
+
+    puffs: Vec<OdorPuff>,
+    ticks: usize,
+
+    // scratch buffer for the update
+    next: Vec<f32>,
+}
+
+impl OdorField {
+    pub const NOSTRIL : f32 = 0.1;
+
+    fn new(world: &World, plugin: &OdorFieldPlugin) -> Self {
+        let scale = plugin.scale;
+        let (width, height) = (world.width() * scale, world.height() * scale);
+
+        let flow = plugin.flow.map(|v| {
+            let mut flow = Vec::new();
+            flow.resize(width * height, v);
+            flow
+        });
+
+        Self {
+            width,
+            height,
+            scale,
+
+            layers: Vec::new(),
+            layer_map: HashMap::new(),
+            features: Vec::new(),
+
+            flow,
+
+            diffusion: plugin.diffusion,
+            decay: plugin.half_life.decay(),
+            rate: plugin.rate,
+            saturation: plugin.saturation,
+
+            puffs: plugin.puffs.clone(),
+            ticks: 0,
+
+            next: Vec::new(),
+        }
+    }
+
+    #[inline]
+    pub fn scale(&self) -> usize {
+        self.scale
+    }
+
+    ///
+    /// Concentration of the odor at a world position, bilinear between
+    /// sub-cell centers.
+    ///
+    pub fn concentration(&self, kind: OdorKind, pos: impl Into<Point>) -> f32 {
+        match self.layer_map.get(&kind) {
+            Some(layer) => self.sample(&self.layers[*layer], pos.into()),
+            None => 0.,
+        }
+    }
+
+    ///
+    /// Molecular features of the odor's layer, which are the source's
+    /// mixture features if it has any.
+    ///
+    pub fn features(&self, kind: OdorKind) -> OdorFeatures {
+        match self.layer_map.get(&kind) {
+            Some(layer) => self.features[*layer],
+            None => kind.features(),
+        }
+    }
+
+    ///
+    /// Sets the molecular features of the odor's layer.
+    ///
+    pub fn set_features(&mut self, kind: OdorKind, features: OdorFeatures) {
+        let layer = self.layer(kind);
+
+        self.features[layer] = features;
+    }
+
+    ///
+    /// Odor kinds emitted into the field.
+    ///
+    pub fn kinds(&self) -> impl Iterator<Item=&OdorKind> {
+        self.layer_map.keys()
+    }
+
+    ///
+    /// Saturating receptor response in [0, 1) for a concentration.
+    ///
+    #[inline]
+    pub fn response(&self, concentration: f32) -> f32 {
+        concentration / (concentration + self.saturation)
+    }
+
+    ///
+    /// Adds amount / second of odor at the position for one tick.
+    ///
+    pub fn emit(&mut self, kind: OdorKind, pos: impl Into<Point>, rate: f32) {
+        let Some(index) = self.index(pos.into()) else {
+            return;
+        };
+
+        let layer = self.layer(kind);
+
+        self.layers[layer][index] += rate * Self::dt();
+    }
+
+    ///
+    /// Sets the flow velocity in world units per second, replacing any
+    /// uniform flow from the plugin.
+    ///
+    pub fn set_flow(&mut self, flow: impl Fn(Point) -> Point) {
+        let scale = self.scale as f32;
+        let mut vec = Vec::with_capacity(self.width * self.height);
+
+        for j in 0..self.height {
+            for i in 0..self.width {
+                vec.push(flow(Point((i as f32 + 0.5) / scale, (j as f32 + 0.5) / scale)));
+            }
+        }
+
+        self.flow = Some(vec);
+    }
+
+    pub fn clear(&mut self) {
+        for layer in &mut self.layers {
+            layer.fill(0.);
+        }
+    }
+
+    fn update(&mut self, world: &World) {
+        self.ticks += 1;
+
+        for i in 0..self.puffs.len() {
+            let puff = self.puffs[i].clone();
+
+            if self.ticks % puff.period.max(1) < puff.duration {
+                self.emit(puff.kind, puff.pos, puff.rate);
+            }
+        }
+
+        let open = self.open_cells(world);
+
+        for layer in 0..self.layers.len() {
+            let mut values = std::mem::take(&mut self.layers[layer]);
+
+            if self.flow.is_some() {
+                self.advect(&mut values, &open);
+            }
+
+            self.diffuse(&mut values, &open);
+
+            for (value, is_open) in values.iter_mut().zip(&open) {
+                *value = if *is_open { *value * self.decay } else { 0. };
+            }
+
+            self.layers[layer] = values;
+        }
+    }
+
+    ///
+    /// Semi-Lagrangian advection: each cell takes the value upstream
+    /// along the flow.
+    ///
+    fn advect(&mut self, values: &mut Vec<f32>, open: &[bool]) {
+        let Some(flow) = &self.flow else {
+            return;
+        };
+
+        let (width, height) = (self.width, self.height);
+        let scale = self.scale as f32;
+        let dt = Self::dt();
+
+        let mut next = std::mem::take(&mut self.next);
+        next.clear();
+        next.resize(values.len(), 0.);
+
+        for j in 0..height {
+            for i in 0..width {
+                let index = j * width + i;
+
+                if ! open[index] {
+                    continue;
+                }
+
+                let v = flow[index];
+
+                let x = i as f32 - v.x() * dt * scale;
+                let y = j as f32 - v.y() * dt * scale;
+
+                next[index] = bilinear(values, width, height, x, y, |k| open[k]);
+            }
+        }
+
+        std::mem::swap(values, &mut next);
+        self.next = next;
+    }
+
+    ///
+    /// Explicit diffusion with zero flux through wall cells, split into
+    /// substeps for stability.
+    ///
+    fn diffuse(&mut self, values: &mut Vec<f32>, open: &[bool]) {
+        let alpha = self.diffusion * Self::dt() * (self.scale * self.scale) as f32;
+
+        if alpha <= 0. {
+            return;
+        }
+
+        let steps = (alpha / 0.2).ceil().max(1.) as usize;
+        let alpha = alpha / steps as f32;
+
+        let (width, height) = (self.width, self.height);
+
+        for _ in 0..steps {
+            self.next.clear();
+            self.next.extend_from_slice(values);
+
+            for j in 0..height {
+                for i in 0..width {
+                    let index = j * width + i;
+
+                    if ! open[index] {
+                        continue;
+                    }
+
+                    let value = values[index];
+                    let mut flux = 0.;
+
+                    if i > 0 && open[index - 1] {
+                        flux += values[index - 1] - value;
+                    }
+                    if i + 1 < width && open[index + 1] {
+                        flux += values[index + 1] - value;
+                    }
+                    if j > 0 && open[index - width] {
+                        flux += values[index - width] - value;
+                    }
+                    if j + 1 < height && open[index + width] {
+                        flux += values[index + width] - value;
+                    }
+
+                    self.next[index] = value + alpha * flux;
+                }
+            }
+
+            std::mem::swap(values, &mut self.next);
+        }
+    }
+
+    fn open_cells(&self, world: &World) -> Vec<bool> {
+        let mut open = Vec::with_capacity(self.width * self.height);
+
+        for j in 0..self.height {
+            for i in 0..self.width {
+                open.push(! matches!(world[(i / self.scale, j / self.scale)], Wall::Wall));
+            }
+        }
+
+        open
+    }
+
+    fn sample(&self, values: &[f32], pos: Point) -> f32 {
+        let scale = self.scale as f32;
+
+        let x = pos.x() * scale - 0.5;
+        let y = pos.y() * scale - 0.5;
+
+        // wall cells are already empty
+        bilinear(values, self.width, self.height, x, y, |_| true)
+    }
+
+    fn layer(&mut self, kind: OdorKind) -> usize {
+        if let Some(layer) = self.layer_map.get(&kind) {
+            return *layer;
+        }
+
+        let layer = self.layers.len();
+
+        let mut values = Vec::new();
+        values.resize(self.width * self.height, 0.);
+
+        self.layers.push(values);
+        self.layer_map.insert(kind, layer);
+        self.features.push(kind.features());
+
+        layer
+    }
+
+    fn index(&self, pos: Point) -> Option<usize> {
+        let scale = self.scale as f32;
+
+        if pos.x() < 0. || pos.y() < 0. {
+            return None;
+        }
+
+        let (i, j) = ((pos.x() * scale) as usize, (pos.y() * scale) as usize);
+
+        if i < self.width && j < self.height {
+            Some(j * self.width + i)
+        } else {
+            None
+        }
+    }
+
+    #[inline]
+    fn dt() -> f32 {
+        1. / Ticks::TICKS_PER_SECOND as f32
+    }
+}
+
+///
+/// Bilinear interpolation at sub-cell coordinates, where closed or
+/// out-of-bounds corners contribute nothing.
+///
+fn bilinear(
+    values: &[f32],
+    width: usize,
+    height: usize,
+    x: f32,
+    y: f32,
+    is_open: impl Fn(usize) -> bool
+) -> f32 {
+    let x = x.clamp(0., (width - 1) as f32);
+    let y = y.clamp(0., (height - 1) as f32);
+
+    let (i0, j0) = (x.floor() as usize, y.floor() as usize);
+    let (i1, j1) = ((i0 + 1).min(width - 1), (j0 + 1).min(height - 1));
+
+    let (fx, fy) = (x - i0 as f32, y - j0 as f32);
+
+    let value = |i: usize, j: usize| {
+        let index = j * width + i;
+
+        if is_open(index) { values[index] } else { 0. }
+    };
+
+    (1. - fx) * (1. - fy) * value(i0, j0)
+        + fx * (1. - fy) * value(i1, j0)
+        + (1. - fx) * fy * value(i0, j1)
+        + fx * fy * value(i1, j1)
+}
+
+#[derive(Clone, Debug)]
+struct OdorPuff {
+    pos: Point,
+    kind: OdorKind,
+    rate: f32,
+    period: usize,
+    duration: usize,
+}
+
+pub struct OdorFieldPlugin {
+    scale: usize,
+    diffusion: f32,
+    half_life: HalfLife,
+    rate: f32,
+    saturation: f32,
+    flow: Option<Point>,
+
+    puffs: Vec<OdorPuff>,
+}
+
+impl OdorFieldPlugin {
+    pub fn new() -> Self {
+        Self {
+            scale: 2,
+            diffusion: 0.5,
+            half_life: HalfLife(10.),
+            rate: 1.,
+            saturation: 1.,
+            flow: None,
+
+            puffs: Vec::new(),
+        }
+    }
+
+    ///
+    /// Sub-cells per world cell along each axis.
+    ///
+    pub fn scale(mut self, scale: usize) -> Self {
+        assert!(scale > 0);
+
+        self.scale = scale;
+
+        self
+    }
+
+    ///
+    /// Diffusion coefficient in world units^2 per second.
+    ///
+    pub fn diffusion(mut self, diffusion: f32) -> Self {
+        assert!(diffusion >= 0.);
+
+        self.diffusion = diffusion;
+
+        self
+    }
+
+    pub fn half_life(mut self, half_life: impl Into<HalfLife>) -> Self {
+        self.half_life = half_life.into();
+
+        self
+    }
+
+    ///
+    /// Emission per second for each Odor source.
+    ///
+    pub fn rate(mut self, rate: f32) -> Self {
+        assert!(rate >= 0.);
+
+        self.rate = rate;
+
+        self
+    }
+
+    ///
+    /// Concentration for a half-maximal receptor response.
+    ///
+    pub fn saturation(mut self, saturation: f32) -> Self {
+        assert!(saturation > 0.);
+
+        self.saturation = saturation;
+
+        self
+    }
+
+    ///
+    /// Uniform flow in world units per second.
+    ///
+    pub fn flow(mut self, vx: f32, vy: f32) -> Self {
+        self.flow = Some(Point(vx, vy));
+
+        self
+    }
+
+    ///
+    /// Intermittent source emitting for duration out of every period.
+    ///
+    pub fn puff(
+        mut self,
+        pos: impl Into<Point>,
+        kind: OdorKind,
+        rate: f32,
+        period: impl Into<Seconds>,
+        duration: impl Into<Seconds>
+    ) -> Self {
+        let period: Seconds = period.into();
+        let period: Ticks = period.into();
+        let duration: Seconds = duration.into();
+        let duration: Ticks = duration.into();
+
+        self.puffs.push(OdorPuff {
+            pos: pos.into(),
+            kind,
+            rate,
+            period: period.ticks(),
+            duration: duration.ticks(),
+        });
+
+        self
+    }
+}
+
+impl Plugin for OdorFieldPlugin {
+    fn build(&self, app: &mut App) {
+        assert!(app.contains_resource::<World>(), "OdorFieldPlugin requires World");
+
+        let mut field = OdorField::new(app.resource::<World>(), self);
+
+        if let Some(flow) = app.get_resource::<FlowField>() {
+            field.set_flow(|p| flow.velocity(p));
+        }
+
+        app.insert_resource(field);
+
+        app.system(PreTick, update_odor_field);
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use essay_ecs::{core::error::Result, prelude::Res};
+    use mind_ecs::MindApp;
+
+    use crate::world::{OdorFeatures, OdorKind, OdorPlugin, WorldPlugin};
+
+    use super::{OdorField, OdorFieldPlugin};
+
+    #[test]
+    fn odor_field_diffuse_wall() -> Result<()> {
+        let mut app = MindApp::test();
+        app.plugin(WorldPlugin::new(9, 5).wall((5, 0), (1, 5)));
+
+        let mut odor = OdorPlugin::new();
+        odor.odor(2, 2, OdorKind::FoodA);
+        app.plugin(odor);
+
+        app.plugin(OdorFieldPlugin::new().scale(1));
+
+        for _ in 0..20 {
+            app.tick()?;
+        }
+
+        let near = app.eval(|x: Res<OdorField>| x.concentration(OdorKind::FoodA, (2.5, 2.5)))?;
+        let far = app.eval(|x: Res<OdorField>| x.concentration(OdorKind::FoodA, (4.5, 2.5)))?;
+        let behind = app.eval(|x: Res<OdorField>| x.concentration(OdorKind::FoodA, (7.5, 2.5)))?;
+
+        assert!(near > far);
+        assert!(far > 0.);
+        assert_eq!(0., behind);
+
+        Ok(())
+    }
+
+    #[test]
+    fn odor_field_mixture_features() -> Result<()> {
+        let mut app = MindApp::test();
+        app.plugin(WorldPlugin::new(9, 5));
+
+        let mixture = [(OdorKind::FoodA, 1.), (OdorKind::AvoidA, 0.5)];
+
+        let mut odor = OdorPlugin::new();
+        odor.mixture(2, 2, 3, &mixture);
+        app.plugin(odor);
+
+        app.plugin(OdorFieldPlugin::new().scale(1));
+
+        app.tick()?;
+
+        // the plume carries the source's mixed features
+        let features = app.eval(|x: Res<OdorField>| x.features(OdorKind::FoodA))?;
+        assert_eq!(OdorFeatures::mixture(&mixture), features);
+
+        Ok(())
+    }
+}
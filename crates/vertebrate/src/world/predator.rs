@@ -0,0 +1,349 @@
+use essay_ecs::{
+    app::{App, Plugin, Startup},
+    core::{entity::EntityId, Commands, Component, Query, Res, ResMut},
+    prelude::{Event, Events},
+};
+use mind_ecs::{PreTick, Tick};
+
+use crate::{
+    body::{Animal, Body},
+    util::{Heading, Point, Seconds, Ticks},
+    world::World,
+};
+
+fn update_predator(
+    predators: Query<(EntityId, &mut Predator)>,
+    body: Res<Body>,
+    animals: Query<&Body>,
+    world: Res<World>,
+    mut command: Commands,
+) {
+    // prey are the primary animal and the animal entities still alive
+    let prey: Vec<Point> = Some(body.get()).into_iter()
+        .chain(animals.iter())
+        .filter(|body| ! body.is_dead())
+        .map(|body| body.pos())
+        .collect();
+
+    for (id, predator) in predators.iter() {
+        if ! predator.update(&prey, world.get()) {
+            command.entity(id).despawn();
+        }
+    }
+}
+
+fn update_catch(
+    predators: Query<&Predator>,
+    mut body: ResMut<Body>,
+    animals: Query<(&Animal, &mut Body)>,
+    mut events: ResMut<Events<PredatorEvent>>,
+) {
+    if ! body.is_dead() && is_catch(&predators, body.get()) {
+        body.set_dead();
+        events.send(PredatorEvent::Catch);
+    }
+
+    for (animal, body) in animals.iter() {
+        if ! body.is_dead() && is_catch(&predators, body) {
+            body.set_dead();
+            events.send(PredatorEvent::CatchAnimal(*animal));
+        }
+    }
+}
+
+fn is_catch(predators: &Query<&Predator>, body: &Body) -> bool {
+    predators.iter().any(|predator| predator.is_catch && predator.is_contact(body))
+}
+
+///
+/// Predator is a dark moving cylinder for escape experiments. It's
+/// rendered into the retina, so an approaching predator looms.
+///
+#[derive(Component, Clone, Debug)]
+pub struct Predator {
+    pos: Point,
+    radius: f32,
+    speed: f32,
+    trajectory: Trajectory,
+
+    dir: (f32, f32),
+    delay: usize,
+    lifetime: usize,
+    ticks: usize,
+
+    is_catch: bool,
+}
+
+impl Predator {
+    pub const HEIGHT : f32 = 1.;
+
+    fn new(pos: Point) -> Self {
+        let lifetime: Ticks = Seconds(60.).into();
+
+        Self {
+            pos,
+            radius: 0.5,
+            speed: 2.,
+            trajectory: Trajectory::Approach,
+
+            dir: (0., 0.),
+            delay: 0,
+            lifetime: lifetime.ticks(),
+            ticks: 0,
+
+            is_catch: false,
+        }
+    }
+
+    #[inline]
+    pub fn pos(&self) -> Point {
+        self.pos
+    }
+
+    #[inline]
+    pub fn radius(&self) -> f32 {
+        self.radius
+    }
+
+    #[inline]
+    pub fn trajectory(&self) -> Trajectory {
+        self.trajectory
+    }
+
+    /// Predator is visible after its start delay
+    #[inline]
+    pub fn is_active(&self) -> bool {
+        self.ticks >= self.delay
+    }
+
+    pub fn is_contact(&self, body: &Body) -> bool {
+        self.is_active()
+            && self.pos.dist(body.pos()) < self.radius + 0.5 * body.len()
+    }
+
+    // nearest living animal
+    fn target(&self, prey: &[Point]) -> Option<Point> {
+        prey.iter()
+            .min_by(|a, b| self.pos.dist(**a).total_cmp(&self.pos.dist(**b)))
+            .copied()
+    }
+
+    ///
+    /// Returns false when the predator has left the world or expired.
+    ///
+    fn update(&mut self, prey: &[Point], world: &World) -> bool {
+        self.ticks += 1;
+
+        if self.ticks == self.delay.max(1) {
+            // approach aims at the nearest animal's position when it starts
+            self.dir = match self.trajectory {
+                Trajectory::Sweep(dir) => {
+                    let (dy, dx) = dir.sin_cos();
+                    (dx, dy)
+                }
+                _ => self.target(prey).map_or((0., 0.), |pos| direction(self.pos, pos)),
+            };
+        }
+
+        if ! self.is_active() {
+            return true;
+        }
+
+        if self.ticks > self.delay + self.lifetime {
+            return false;
+        }
+
+        if let Trajectory::Chase = self.trajectory {
+            if let Some(pos) = self.target(prey) {
+                self.dir = direction(self.pos, pos);
+            }
+        }
+
+        let step = self.speed / Ticks::TICKS_PER_SECOND as f32;
+        let (dx, dy) = self.dir;
+
+        self.pos = Point(self.pos.x() + step * dx, self.pos.y() + step * dy);
+
+        let (width, height) = world.extent();
+        let margin = 2. * self.radius + 1.;
+
+        -margin < self.pos.x() && self.pos.x() < width as f32 + margin
+            && -margin < self.pos.y() && self.pos.y() < height as f32 + margin
+    }
+}
+
+fn direction(from: Point, to: Point) -> (f32, f32) {
+    let (dx, dy) = (to.x() - from.x(), to.y() - from.y());
+    let len = dx.hypot(dy);
+
+    if len > 0. {
+        (dx / len, dy / len)
+    } else {
+        (0., 0.)
+    }
+}
+
+///
+/// Predator movement. Approach heads in a straight line toward the
+/// nearest animal's position at the start, Sweep crosses the arena along
+/// a fixed heading, and Chase turns toward the nearest animal each tick.
+///
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Trajectory {
+    Approach,
+    Sweep(Heading),
+    Chase,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Event)]
+pub enum PredatorEvent {
+    Catch,
+    CatchAnimal(Animal),
+}
+
+pub struct PredatorPlugin {
+    predators: Vec<Predator>,
+}
+
+impl PredatorPlugin {
+    pub fn new() -> Self {
+        Self {
+            predators: Vec::new(),
+        }
+    }
+
+    pub fn predator(&mut self, x: f32, y: f32) -> &mut Self {
+        self.predators.push(Predator::new(Point(x, y)));
+
+        self
+    }
+
+    pub fn trajectory(&mut self, trajectory: Trajectory) -> &mut Self {
+        self.last().trajectory = trajectory;
+
+        self
+    }
+
+    pub fn radius(&mut self, radius: f32) -> &mut Self {
+        assert!(radius > 0.);
+
+        self.last().radius = radius;
+
+        self
+    }
+
+    ///
+    /// Speed in world units per second.
+    ///
+    pub fn speed(&mut self, speed: f32) -> &mut Self {
+        assert!(speed >= 0.);
+
+        self.last().speed = speed;
+
+        self
+    }
+
+    ///
+    /// Delay before the predator appears and starts moving.
+    ///
+    pub fn delay(&mut self, delay: impl Into<Ticks>) -> &mut Self {
+        self.last().delay = delay.into().ticks();
+
+        self
+    }
+
+    pub fn lifetime(&mut self, lifetime: impl Into<Ticks>) -> &mut Self {
+        self.last().lifetime = lifetime.into().ticks();
+
+        self
+    }
+
+    ///
+    /// The predator catches, killing the animal on contact.
+    ///
+    pub fn catch(&mut self, is_catch: bool) -> &mut Self {
+        self.last().is_catch = is_catch;
+
+        self
+    }
+
+    fn last(&mut self) -> &mut Predator {
+        self.predators.last_mut().expect("PredatorPlugin requires a predator")
+    }
+}
+
+impl Plugin for PredatorPlugin {
+    fn build(&self, app: &mut App) {
+        assert!(app.contains_resource::<World>(), "PredatorPlugin requires World");
+        assert!(app.contains_resource::<Body>(), "PredatorPlugin requires Body");
+
+        let mut predators = self.predators.clone();
+
+        app.system(Startup, move |mut cmd: Commands| {
+            for predator in predators.drain(..) {
+                cmd.spawn(predator);
+            }
+        });
+
+        app.event::<PredatorEvent>();
+
+        app.system(PreTick, update_predator);
+        app.system(Tick, update_catch);
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use essay_ecs::{app::Startup, core::{error::Result, Commands, Query}, prelude::Res};
+    use mind_ecs::MindApp;
+
+    use crate::{body::{Animal, Body, BodyPlugin}, util::Point, world::WorldPlugin};
+
+    use super::{PredatorPlugin, Trajectory};
+
+    #[test]
+    fn predator_catch() -> Result<()> {
+        let mut app = MindApp::test();
+        app.plugin(WorldPlugin::new(7, 13));
+        app.plugin(BodyPlugin::new());
+
+        let mut predator = PredatorPlugin::new();
+        predator.predator(3.5, 3.5).trajectory(Trajectory::Chase).speed(5.).catch(true);
+        app.plugin(predator);
+
+        for _ in 0..20 {
+            app.tick()?;
+        }
+
+        assert!(app.eval(|x: Res<Body>| x.is_dead())?);
+
+        Ok(())
+    }
+
+    #[test]
+    fn predator_catch_animal() -> Result<()> {
+        let mut app = MindApp::test();
+        app.plugin(WorldPlugin::new(7, 13));
+        app.plugin(BodyPlugin::new());
+
+        app.system(Startup, |mut cmd: Commands| {
+            cmd.spawn((Animal::new(1), Body::new(Point(3.5, 5.5))));
+        });
+
+        let mut predator = PredatorPlugin::new();
+        predator.predator(3.5, 3.5).trajectory(Trajectory::Chase).speed(5.).catch(true);
+        app.plugin(predator);
+
+        for _ in 0..5 {
+            app.tick()?;
+        }
+
+        // the spawned animal is nearer, so it's chased and caught first
+        assert!(app.eval(|q: Query<(&Animal, &Body)>| {
+            q.iter().all(|(_, body)| body.is_dead())
+        })?);
+        assert!(! app.eval(|x: Res<Body>| x.is_dead())?);
+
+        Ok(())
+    }
+}
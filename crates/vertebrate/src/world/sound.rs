@@ -0,0 +1,281 @@
+use std::sync::{Arc, Mutex};
+
+use audio::AudioSource;
+use essay_ecs::{
+    app::{App, Plugin, Startup},
+    core::{Commands, Component, Query},
+};
+use mind_ecs::PreTick;
+
+use crate::util::{Point, Ticks};
+
+use super::World;
+
+fn update_sound(
+    sounds: Query<&mut SoundSource>,
+) {
+    for sound in sounds.iter() {
+        sound.update();
+    }
+}
+
+///
+/// SoundSource is a world entity emitting an audio generator, such as a
+/// tone or white noise. Each tick the source fills a block of samples,
+/// and the pressure at a point falls off with the inverse distance.
+///
+/// An optional delay, duration and period gate the source into pulses
+/// for startle and prepulse inhibition experiments.
+///
+#[derive(Component)]
+pub struct SoundSource {
+    pos: Point,
+    amplitude: f32,
+
+    // AudioSource is Send but not Sync
+    source: Mutex<Box<dyn AudioSource>>,
+
+    delay: usize,
+    duration: Option<usize>,
+    period: Option<usize>,
+    ticks: usize,
+
+    samples: Vec<f32>,
+}
+
+impl SoundSource {
+    pub const SAMPLE_RATE : u32 = 8000;
+    pub const SAMPLES_PER_TICK : usize = (Self::SAMPLE_RATE as usize) / Ticks::TICKS_PER_SECOND;
+
+    // minimum distance for attenuation, avoiding the singularity
+    const MIN_DIST : f32 = 0.5;
+
+    fn new(pos: Point, mut source: Box<dyn AudioSource>) -> Self {
+        source.reset(Some(Self::SAMPLE_RATE));
+
+        Self {
+            pos,
+            amplitude: 1.,
+            source: Mutex::new(source),
+
+            delay: 0,
+            duration: None,
+            period: None,
+            ticks: 0,
+
+            samples: Vec::new(),
+        }
+    }
+
+    #[inline]
+    pub fn pos(&self) -> Point {
+        self.pos
+    }
+
+    #[inline]
+    pub fn amplitude(&self) -> f32 {
+        self.amplitude
+    }
+
+    ///
+    /// The source is sounding in the current tick.
+    ///
+    pub fn is_active(&self) -> bool {
+        if self.ticks < self.delay {
+            return false;
+        }
+
+        let mut time = self.ticks - self.delay;
+
+        if let Some(period) = self.period {
+            time %= period;
+        }
+
+        match self.duration {
+            Some(duration) => time < duration,
+            None => true,
+        }
+    }
+
+    ///
+    /// Pressure gain at a position from inverse distance attenuation.
+    ///
+    pub fn gain(&self, pos: impl Into<Point>) -> f32 {
+        self.amplitude / self.pos.dist(pos.into()).max(Self::MIN_DIST)
+    }
+
+    ///
+    /// Samples emitted in the current tick, empty when silent.
+    ///
+    #[inline]
+    pub fn samples(&self) -> &Vec<f32> {
+        &self.samples
+    }
+
+    fn update(&mut self) {
+        self.samples.clear();
+
+        if self.is_active() {
+            let source = self.source.get_mut().unwrap();
+
+            for _ in 0..Self::SAMPLES_PER_TICK {
+                self.samples.push(source.next().unwrap_or(0.));
+            }
+        }
+
+        self.ticks += 1;
+    }
+}
+
+type SourceFactory = Arc<dyn Fn() -> Box<dyn AudioSource> + Send + Sync>;
+
+#[derive(Clone)]
+struct SoundItem {
+    pos: Point,
+    source: SourceFactory,
+    amplitude: f32,
+    delay: usize,
+    duration: Option<usize>,
+    period: Option<usize>,
+}
+
+impl SoundItem {
+    fn create_sound(&self) -> SoundSource {
+        let mut sound = SoundSource::new(self.pos, (self.source)());
+
+        sound.amplitude = self.amplitude;
+        sound.delay = self.delay;
+        sound.duration = self.duration;
+        sound.period = self.period;
+
+        sound
+    }
+}
+
+pub struct SoundPlugin {
+    sounds: Vec<SoundItem>,
+}
+
+impl SoundPlugin {
+    pub fn new() -> Self {
+        Self {
+            sounds: Vec::new(),
+        }
+    }
+
+    ///
+    /// Adds a sound at (x, y). The source function creates the audio
+    /// generator, for example `|| audio::sine(400.)`.
+    ///
+    pub fn sound(
+        &mut self,
+        x: f32,
+        y: f32,
+        source: impl Fn() -> Box<dyn AudioSource> + Send + Sync + 'static
+    ) -> &mut Self {
+        self.sounds.push(SoundItem {
+            pos: Point(x, y),
+            source: Arc::new(source),
+            amplitude: 1.,
+            delay: 0,
+            duration: None,
+            period: None,
+        });
+
+        self
+    }
+
+    pub fn amplitude(&mut self, amplitude: f32) -> &mut Self {
+        assert!(amplitude >= 0.);
+
+        self.last().amplitude = amplitude;
+
+        self
+    }
+
+    ///
+    /// Delay before the sound starts.
+    ///
+    pub fn delay(&mut self, delay: impl Into<Ticks>) -> &mut Self {
+        self.last().delay = delay.into().ticks();
+
+        self
+    }
+
+    ///
+    /// Length of each pulse. Without a duration the sound is continuous.
+    ///
+    pub fn duration(&mut self, duration: impl Into<Ticks>) -> &mut Self {
+        let duration = duration.into().ticks();
+        assert!(duration > 0);
+
+        self.last().duration = Some(duration);
+
+        self
+    }
+
+    ///
+    /// Repeats the pulse with the period.
+    ///
+    pub fn period(&mut self, period: impl Into<Ticks>) -> &mut Self {
+        let period = period.into().ticks();
+        assert!(period > 0);
+
+        self.last().period = Some(period);
+
+        self
+    }
+
+    fn last(&mut self) -> &mut SoundItem {
+        self.sounds.last_mut().expect("SoundPlugin requires a sound")
+    }
+}
+
+impl Plugin for SoundPlugin {
+    fn build(&self, app: &mut App) {
+        assert!(app.contains_resource::<World>(), "SoundPlugin requires World");
+
+        let mut sounds: Vec<SoundSource> = self.sounds.iter()
+            .map(|item| item.create_sound())
+            .collect();
+
+        app.system(Startup, move |mut cmd: Commands| {
+            for sound in sounds.drain(..) {
+                cmd.spawn(sound);
+            }
+        });
+
+        app.system(PreTick, update_sound);
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::util::Ticks;
+
+    use super::{SoundPlugin, SoundSource};
+
+    #[test]
+    fn sound_pulse() {
+        let mut plugin = SoundPlugin::new();
+        plugin.sound(1., 1., || audio::sine(400.))
+            .amplitude(2.)
+            .delay(Ticks(2))
+            .duration(Ticks(1))
+            .period(Ticks(4));
+
+        let mut sound = plugin.sounds[0].create_sound();
+
+        let mut active = Vec::new();
+        for _ in 0..8 {
+            sound.update();
+            active.push(! sound.samples().is_empty());
+        }
+
+        assert_eq!(vec![false, false, true, false, false, false, true, false], active);
+
+        assert_eq!(SoundSource::SAMPLES_PER_TICK, 800);
+        assert_eq!(4., sound.gain((1., 1.5)));
+        assert_eq!(0.5, sound.gain((5., 1.)));
+    }
+}
@@ -0,0 +1,225 @@
+use std::f32::consts::TAU;
+
+use essay_ecs::{
+    app::{App, Plugin},
+    core::ResMut,
+};
+use mind_ecs::PreTick;
+
+use crate::util::{Point, Seconds, Ticks};
+
+use super::World;
+
+fn update_temperature(mut temperature: ResMut<Temperature>) {
+    temperature.update();
+}
+
+///
+/// Temperature is the water temperature in degrees C, a base temperature
+/// plus linear and radial gradients and gaussian hot and cold spots. An
+/// optional cycle varies the whole field over time.
+///
+#[derive(Clone, Debug)]
+pub struct Temperature {
+    base: f32,
+    sources: Vec<TemperatureSource>,
+
+    cycle: Option<(usize, f32)>,
+    offset: f32,
+    ticks: usize,
+}
+
+impl Temperature {
+    pub fn temperature(&self, pos: impl Into<Point>) -> f32 {
+        let pos = pos.into();
+
+        self.sources.iter().fold(self.base + self.offset, |t, source| {
+            t + source.delta(pos)
+        })
+    }
+
+    #[inline]
+    pub fn base(&self) -> f32 {
+        self.base
+    }
+
+    ///
+    /// Current offset from the time cycle.
+    ///
+    #[inline]
+    pub fn offset(&self) -> f32 {
+        self.offset
+    }
+
+    #[inline]
+    pub fn sources(&self) -> &Vec<TemperatureSource> {
+        &self.sources
+    }
+
+    pub fn add(&mut self, source: TemperatureSource) {
+        self.sources.push(source);
+    }
+
+    pub fn clear(&mut self) {
+        self.sources.clear();
+    }
+
+    fn update(&mut self) {
+        self.ticks += 1;
+
+        if let Some((period, amplitude)) = self.cycle {
+            let phase = (self.ticks % period) as f32 / period as f32;
+
+            self.offset = amplitude * (TAU * phase).sin();
+        }
+    }
+}
+
+///
+/// Linear adds gradient · pos, Radial changes by slope per unit distance
+/// from the center, and Spot is a gaussian hot spot for positive delta or
+/// a cold spot for negative delta, with radius r as its width.
+///
+#[derive(Clone, Debug)]
+pub enum TemperatureSource {
+    Linear(Point),
+    Radial { center: Point, slope: f32 },
+    Spot { center: Point, r: f32, delta: f32 },
+}
+
+impl TemperatureSource {
+    pub fn delta(&self, pos: Point) -> f32 {
+        match self {
+            TemperatureSource::Linear(gradient) => gradient.dot(pos),
+            TemperatureSource::Radial { center, slope } => slope * pos.dist(*center),
+            TemperatureSource::Spot { center, r, delta } => {
+                let d = pos.dist(*center) / r;
+
+                delta * (-0.5 * d * d).exp()
+            }
+        }
+    }
+}
+
+pub struct TemperaturePlugin {
+    base: f32,
+    sources: Vec<TemperatureSource>,
+    cycle: Option<(Ticks, f32)>,
+}
+
+impl TemperaturePlugin {
+    pub fn new() -> Self {
+        Self {
+            base: 25.,
+            sources: Vec::new(),
+            cycle: None,
+        }
+    }
+
+    ///
+    /// Base temperature in degrees C.
+    ///
+    pub fn base(mut self, base: f32) -> Self {
+        self.base = base;
+
+        self
+    }
+
+    ///
+    /// Linear gradient in degrees per world unit.
+    ///
+    pub fn linear(mut self, gx: f32, gy: f32) -> Self {
+        self.sources.push(TemperatureSource::Linear(Point(gx, gy)));
+
+        self
+    }
+
+    pub fn radial(mut self, center: impl Into<Point>, slope: f32) -> Self {
+        self.sources.push(TemperatureSource::Radial { center: center.into(), slope });
+
+        self
+    }
+
+    pub fn spot(mut self, center: impl Into<Point>, r: f32, delta: f32) -> Self {
+        assert!(r > 0.);
+
+        self.sources.push(TemperatureSource::Spot { center: center.into(), r, delta });
+
+        self
+    }
+
+    ///
+    /// Sinusoidal variation of the whole field over the period.
+    ///
+    pub fn cycle(mut self, period: impl Into<Seconds>, amplitude: f32) -> Self {
+        let period: Seconds = period.into();
+        let period: Ticks = period.into();
+        assert!(period.ticks() > 0);
+
+        self.cycle = Some((period, amplitude));
+
+        self
+    }
+
+    fn create_temperature(&self) -> Temperature {
+        Temperature {
+            base: self.base,
+            sources: self.sources.clone(),
+            cycle: self.cycle.map(|(period, amplitude)| (period.ticks(), amplitude)),
+            offset: 0.,
+            ticks: 0,
+        }
+    }
+}
+
+impl Plugin for TemperaturePlugin {
+    fn build(&self, app: &mut App) {
+        assert!(app.contains_resource::<World>(), "TemperaturePlugin requires World");
+
+        app.insert_resource(self.create_temperature());
+
+        app.system(PreTick, update_temperature);
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::util::Seconds;
+
+    use super::TemperaturePlugin;
+
+    #[test]
+    fn temperature_field() {
+        let field = TemperaturePlugin::new()
+            .base(20.)
+            .linear(1., 0.)
+            .create_temperature();
+
+        assert_eq!(20., field.temperature((0., 3.)));
+        assert_eq!(25., field.temperature((5., 3.)));
+
+        let field = TemperaturePlugin::new()
+            .base(20.)
+            .spot([5., 5.], 1., -4.)
+            .radial([0., 0.], 0.5)
+            .create_temperature();
+
+        assert!((field.temperature((5., 5.)) - (16. + 0.5 * 50f32.sqrt())).abs() < 1e-4);
+        assert!((field.temperature((2., 0.)) - 21.).abs() < 1e-3);
+    }
+
+    #[test]
+    fn temperature_cycle() {
+        let mut field = TemperaturePlugin::new()
+            .base(20.)
+            .cycle(Seconds(4.), 2.)
+            .create_temperature();
+
+        // quarter period of 40 ticks
+        for _ in 0..10 {
+            field.update();
+        }
+
+        assert!((field.temperature((1., 1.)) - 22.).abs() < 1e-4);
+    }
+}
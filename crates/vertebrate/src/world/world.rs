@@ -2,12 +2,17 @@ use std::ops::{Index, IndexMut};
 
 use crate::{util::Point, world::Food};
 
+use super::Obstacle;
+
 pub struct World {
     width: usize,
     height: usize,
     cells: Vec<Wall>,
 
     base_food: Option<Food>,
+    obstacles: Vec<Obstacle>,
+
+    update_count: usize,
 }
 
 impl World {
@@ -21,6 +26,8 @@ impl World {
             height,
             cells: values,
             base_food: None,
+            obstacles: Vec::new(),
+            update_count: 1,
         }
     }
 
@@ -39,6 +46,15 @@ impl World {
         self.height
     }
 
+    ///
+    /// Incremented on every cell change, so consumers that cache the
+    /// world, like the retina form and UI image, can rebuild.
+    ///
+    #[inline]
+    pub fn update_count(&self) -> usize {
+        self.update_count
+    }
+
     #[inline]
     pub fn base_food(&self) -> &Option<Food> {
         &self.base_food
@@ -47,6 +63,31 @@ impl World {
     pub(crate) fn set_base_food(&mut self, food: Option<Food>) {
         self.base_food = food;
     }
+
+    #[inline]
+    pub fn obstacles(&self) -> &Vec<Obstacle> {
+        &self.obstacles
+    }
+
+    pub fn add_obstacle(&mut self, obstacle: Obstacle) {
+        self.obstacles.push(obstacle);
+        self.update_count += 1;
+    }
+
+    pub fn clear_obstacles(&mut self) {
+        self.obstacles.clear();
+        self.update_count += 1;
+    }
+
+    ///
+    /// Sets a cell, only invalidating cached views when it changes.
+    ///
+    pub(super) fn set_cell(&mut self, pos: (usize, usize), cell: Wall) {
+        if self[pos] != cell {
+            self[pos] = cell;
+            self.update_count += 1;
+        }
+    }
 }
 
 impl Index<(usize, usize)> for World {
@@ -114,6 +155,7 @@ impl World {
         }
 
         self[(x.floor() as usize, y.floor() as usize)].is_collide()
+            || self.obstacles.iter().any(|o| o.contains(Point(x, y)))
     }
 
     pub fn light(&self, pt: impl Into<Point>) -> f32 {
@@ -154,7 +196,7 @@ impl World {
     }
 }
 
-#[derive(Copy, Clone, Debug)]
+#[derive(Copy, Clone, Debug, PartialEq)]
 pub enum Wall {
     Empty,
     Food,
@@ -179,7 +221,7 @@ impl WorldType for Wall {
     }
 }
 
-#[derive(Clone, Copy, PartialEq)]
+#[derive(Clone, Copy, Debug, PartialEq)]
 pub enum FloorType {
     Light,
     Dark,
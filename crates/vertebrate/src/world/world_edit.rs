@@ -0,0 +1,202 @@
+use essay_ecs::{app::event::InEvent, core::ResMut, prelude::Event};
+use log::warn;
+
+use crate::util::Point;
+
+use super::{FloorType, Obstacle, Wall, World, WorldHex, WorldHexTrait};
+
+pub(super) fn update_world_edit(
+    mut world: ResMut<World>,
+    mut edits: InEvent<WorldEdit>,
+) {
+    for edit in edits.iter() {
+        world.edit(edit);
+    }
+}
+
+///
+/// Runtime change to the World's cells, like opening a door in a maze or
+/// swapping light and dark floors for reversal learning. Edits apply at
+/// the start of the next tick, and consumers that cache the world watch
+/// World::update_count. Cells outside the world are skipped with a
+/// warning.
+///
+#[derive(Clone, Debug, Event)]
+pub enum WorldEdit {
+    Set((usize, usize), Wall),
+    Wall((usize, usize), (usize, usize)),
+    Clear((usize, usize), (usize, usize)),
+    Floor((usize, usize), (usize, usize), FloorType),
+    SwapFloor,
+    Obstacle(Obstacle),
+    ClearObstacles,
+}
+
+impl World {
+    pub fn edit(&mut self, edit: &WorldEdit) {
+        match edit {
+            WorldEdit::Set(pos, cell) => {
+                if pos.0 < self.width() && pos.1 < self.height() {
+                    self.set_cell(*pos, *cell);
+                } else {
+                    warn!("WorldEdit::Set {:?} is outside the world {:?}", pos, self.extent());
+                }
+            }
+            WorldEdit::Wall(pos, extent) => {
+                self.fill(*pos, *extent, Wall::Wall);
+            }
+            WorldEdit::Clear(pos, extent) => {
+                self.fill(*pos, *extent, Wall::Empty);
+            }
+            WorldEdit::Floor(pos, extent, floor) => {
+                let cell = match floor {
+                    FloorType::Light => Wall::FloorLight,
+                    FloorType::Dark => Wall::FloorDark,
+                };
+
+                self.fill(*pos, *extent, cell);
+            }
+            WorldEdit::SwapFloor => {
+                for j in 0..self.height() {
+                    for i in 0..self.width() {
+                        let cell = self[(i, j)];
+
+                        match cell {
+                            Wall::FloorLight => self.set_cell((i, j), Wall::FloorDark),
+                            Wall::FloorDark => self.set_cell((i, j), Wall::FloorLight),
+                            _ => {},
+                        }
+                    }
+                }
+            }
+            WorldEdit::Obstacle(obstacle) => {
+                self.add_obstacle(obstacle.clone());
+            }
+            WorldEdit::ClearObstacles => {
+                self.clear_obstacles();
+            }
+        }
+    }
+
+    fn fill(&mut self, pos: (usize, usize), extent: (usize, usize), cell: Wall) {
+        // clamp the rectangle to the world
+        let x1 = pos.0.saturating_add(extent.0).min(self.width());
+        let y1 = pos.1.saturating_add(extent.1).min(self.height());
+
+        if x1 < pos.0.saturating_add(extent.0) || y1 < pos.1.saturating_add(extent.1) {
+            warn!("WorldEdit {:?} extent {:?} is clipped to the world {:?}", pos, extent, self.extent());
+        }
+
+        for j in pos.1..y1 {
+            for i in pos.0..x1 {
+                self.set_cell((i, j), cell);
+            }
+        }
+    }
+}
+
+pub(super) fn update_world_hex_edit<K: WorldHexTrait>(
+    mut hex: ResMut<WorldHex<K>>,
+    mut edits: InEvent<WorldHexEdit<K>>,
+) {
+    for edit in edits.iter() {
+        hex.edit(edit);
+    }
+}
+
+///
+/// Runtime change to a WorldHex layer, like moving a landmark place.
+/// Edits apply at the start of the next tick and invalidate the layer's
+/// cached views through WorldHex::update_count.
+///
+#[derive(Clone, Event)]
+pub enum WorldHexEdit<K: WorldHexTrait> {
+    Set((usize, usize), K),
+    Circle(Point, f32, K),
+}
+
+impl<K: WorldHexTrait> WorldHex<K> {
+    pub fn edit(&mut self, edit: &WorldHexEdit<K>) {
+        match edit {
+            WorldHexEdit::Set(pos, kind) => {
+                if pos.0 < self.width() && pos.1 < self.height() {
+                    self[*pos] = kind.clone();
+                } else {
+                    warn!("WorldHexEdit::Set {:?} is outside the world {:?}", pos, (self.width(), self.height()));
+                }
+            }
+            WorldHexEdit::Circle(pos, r, kind) => {
+                self.circle(*pos, *r, kind.clone());
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::world::{FloorType, OdorKind, Wall, World, WorldHex};
+
+    use super::{WorldEdit, WorldHexEdit};
+
+    #[test]
+    fn world_edit() {
+        let mut world = World::new(4, 3);
+        world.edit(&WorldEdit::Wall((1, 0), (1, 3)));
+
+        assert!(world.is_collide((1.5, 1.5)));
+
+        let count = world.update_count();
+        world.edit(&WorldEdit::Clear((1, 1), (1, 1)));
+
+        assert!(! world.is_collide((1.5, 1.5)));
+        assert!(world.is_collide((1.5, 0.5)));
+        assert!(world.update_count() > count);
+
+        world.edit(&WorldEdit::Floor((2, 0), (2, 1), FloorType::Dark));
+        world.edit(&WorldEdit::SwapFloor);
+
+        assert!(matches!(world[(3, 0)], Wall::FloorLight));
+        assert!(matches!(world[(3, 1)], Wall::Empty));
+    }
+
+    #[test]
+    fn world_edit_out_of_range() {
+        let mut world = World::new(4, 3);
+
+        // clipped to the world
+        world.edit(&WorldEdit::Wall((3, 1), (5, 5)));
+
+        assert!(world.is_collide((3.5, 2.5)));
+        assert!(! world.is_collide((2.5, 2.5)));
+
+        // entirely outside is skipped
+        let count = world.update_count();
+        world.edit(&WorldEdit::Clear((10, 10), (2, 2)));
+        world.edit(&WorldEdit::Set((4, 0), Wall::Wall));
+
+        assert_eq!(count, world.update_count());
+
+        // an unchanged cell keeps the cached views
+        world.edit(&WorldEdit::Set((3, 2), Wall::Wall));
+        world.edit(&WorldEdit::Clear((0, 0), (1, 1)));
+
+        assert_eq!(count, world.update_count());
+    }
+
+    #[test]
+    fn world_hex_edit() {
+        let mut hex = WorldHex::new(4, 3, OdorKind::None);
+
+        let count = hex.update_count();
+        hex.edit(&WorldHexEdit::Set((2, 1), OdorKind::FoodA));
+
+        assert_eq!(OdorKind::FoodA, hex[(2, 1)]);
+        assert!(hex.update_count() > count);
+
+        // outside the layer is skipped
+        let count = hex.update_count();
+        hex.edit(&WorldHexEdit::Set((9, 9), OdorKind::FoodA));
+
+        assert_eq!(count, hex.update_count());
+    }
+}
@@ -1,10 +1,11 @@
 use std::{collections::HashMap, hash::Hash, ops::{Deref, DerefMut, Index, IndexMut}};
 
 use essay_ecs::app::{App, Plugin};
+use mind_ecs::PreTick;
 
 use crate::util::Point;
 
-use super::World;
+use super::{world_edit::{update_world_hex_edit, WorldHexEdit}, World};
 
 #[derive(Clone)]
 pub struct WorldHex<T: WorldHexTrait> {
@@ -168,6 +169,9 @@ impl<T: WorldHexTrait> DerefMut for WorldHexPlugin<T> {
 impl<T: WorldHexTrait> Plugin for WorldHexPlugin<T> {
     fn build(&self, app: &mut App) {
         app.insert_resource(self.world.clone());
+
+        app.event::<WorldHexEdit<T>>();
+        app.system(PreTick, update_world_hex_edit::<T>);
     }
 }
 
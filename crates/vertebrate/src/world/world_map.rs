@@ -0,0 +1,235 @@
+use std::{fs, path::Path};
+
+use super::{Wall, WorldHex, WorldHexTrait};
+
+///
+/// WorldMap is a grid of map symbols loaded from an ASCII or PNG file,
+/// used to build a World and its WorldHex place layers for standard
+/// assays like T-mazes and open fields.
+///
+/// The first text line (or image row) is the top of the world, so it
+/// has the largest y, matching the north-up display.
+///
+#[derive(Clone, Debug)]
+pub struct WorldMap {
+    width: usize,
+    height: usize,
+
+    cells: Vec<char>,
+}
+
+impl WorldMap {
+    // default image palette for from_image
+    pub const PALETTE : [([u8; 3], char); 5] = [
+        ([0, 0, 0], '#'),
+        ([255, 255, 255], '.'),
+        ([0, 255, 0], 'F'),
+        ([255, 255, 0], 'L'),
+        ([128, 128, 128], 'D'),
+    ];
+
+    ///
+    /// Loads a PNG map with the default palette, or an ASCII map for
+    /// any other extension.
+    ///
+    pub fn load(path: impl AsRef<Path>) -> Result<Self, String> {
+        let path = path.as_ref();
+
+        match path.extension().and_then(|e| e.to_str()) {
+            Some("png") => Self::from_image(path, &Self::PALETTE),
+            _ => {
+                let text = fs::read_to_string(path)
+                    .map_err(|err| format!("can't read map {:?}: {}", path, err))?;
+
+                Self::from_ascii(&text)
+            }
+        }
+    }
+
+    ///
+    /// Parses an ASCII map, one character per cell. Leading and trailing
+    /// blank lines and trailing whitespace are ignored, so empty cells
+    /// should be '.', and all rows must have the same width.
+    ///
+    pub fn from_ascii(text: &str) -> Result<Self, String> {
+        let rows: Vec<Vec<char>> = text.lines()
+            .map(|line| line.trim_end().chars().collect())
+            .skip_while(|row: &Vec<char>| row.is_empty())
+            .collect();
+
+        let n_rows = rows.iter().rposition(|row| ! row.is_empty()).map_or(0, |n| n + 1);
+        let rows = &rows[..n_rows];
+
+        if rows.is_empty() {
+            return Err(String::from("map is empty"));
+        }
+
+        let width = rows[0].len();
+        let height = rows.len();
+
+        let mut cells = Vec::new();
+        cells.resize(width * height, '.');
+
+        for (j, row) in rows.iter().enumerate() {
+            if row.len() != width {
+                return Err(format!(
+                    "map row {} has width {}, expected {}", j + 1, row.len(), width
+                ));
+            }
+
+            let y = height - 1 - j;
+
+            for (x, ch) in row.iter().enumerate() {
+                cells[y * width + x] = *ch;
+            }
+        }
+
+        Ok(Self { width, height, cells })
+    }
+
+    ///
+    /// Loads an image map, one pixel per cell, assigning each pixel the
+    /// symbol of the nearest palette color.
+    ///
+    pub fn from_image(
+        path: impl AsRef<Path>,
+        palette: &[([u8; 3], char)]
+    ) -> Result<Self, String> {
+        let path = path.as_ref();
+        assert!(palette.len() > 0);
+
+        let image = image::open(path)
+            .map_err(|err| format!("can't read map {:?}: {}", path, err))?
+            .to_rgb8();
+
+        let (width, height) = (image.width() as usize, image.height() as usize);
+
+        if width == 0 || height == 0 {
+            return Err(format!("map {:?} is empty", path));
+        }
+
+        let mut cells = Vec::new();
+        cells.resize(width * height, '.');
+
+        for (i, j, pixel) in image.enumerate_pixels() {
+            let y = height - 1 - j as usize;
+
+            cells[y * width + i as usize] = nearest(palette, pixel.0);
+        }
+
+        Ok(Self { width, height, cells })
+    }
+
+    #[inline]
+    pub fn extent(&self) -> (usize, usize) {
+        (self.width, self.height)
+    }
+
+    #[inline]
+    pub fn width(&self) -> usize {
+        self.width
+    }
+
+    #[inline]
+    pub fn height(&self) -> usize {
+        self.height
+    }
+
+    #[inline]
+    pub fn get(&self, x: usize, y: usize) -> char {
+        assert!(x < self.width);
+        assert!(y < self.height);
+
+        self.cells[y * self.width + x]
+    }
+
+    ///
+    /// Default world legend: '#' wall, 'F' food, 'L' light floor and
+    /// 'D' dark floor. Any other symbol is an empty cell.
+    ///
+    pub fn wall(symbol: char) -> Wall {
+        match symbol {
+            '#' => Wall::Wall,
+            'F' => Wall::Food,
+            'L' => Wall::FloorLight,
+            'D' => Wall::FloorDark,
+            _ => Wall::Empty,
+        }
+    }
+
+    pub(super) fn cells(&self) -> impl Iterator<Item=((usize, usize), char)> + '_ {
+        self.cells.iter().enumerate()
+            .map(|(i, ch)| ((i % self.width, i / self.width), *ch))
+    }
+}
+
+impl<T: WorldHexTrait> WorldHex<T> {
+    ///
+    /// Fills place kinds from a map. Cells where kind returns None are
+    /// unchanged.
+    ///
+    pub fn fill_map(&mut self, map: &WorldMap, kind: impl Fn(char) -> Option<T>) {
+        assert!(map.width() <= self.width(), "map width {} is larger than the world", map.width());
+        assert!(map.height() <= self.height(), "map height {} is larger than the world", map.height());
+
+        for ((x, y), symbol) in map.cells() {
+            if let Some(value) = kind(symbol) {
+                self[(x, y)] = value;
+            }
+        }
+    }
+}
+
+fn nearest(palette: &[([u8; 3], char)], rgb: [u8; 3]) -> char {
+    let dist = |color: &[u8; 3]| -> i32 {
+        color.iter().zip(rgb.iter())
+            .map(|(a, b)| (*a as i32 - *b as i32).pow(2))
+            .sum()
+    };
+
+    palette.iter()
+        .min_by_key(|(color, _)| dist(color))
+        .map(|(_, ch)| *ch)
+        .unwrap()
+}
+
+#[cfg(test)]
+mod test {
+    use crate::world::{OdorKind, WorldHex};
+
+    use super::WorldMap;
+
+    #[test]
+    fn ascii_map() {
+        let map = WorldMap::from_ascii("
+            ###
+            #.F
+            LD.
+        ".replace(' ', "").as_str()).unwrap();
+
+        assert_eq!((3, 3), map.extent());
+        assert_eq!('L', map.get(0, 0));
+        assert_eq!('D', map.get(1, 0));
+        assert_eq!('F', map.get(2, 1));
+        assert_eq!('#', map.get(1, 2));
+
+        assert!(WorldMap::from_ascii("##\n#").is_err());
+        assert!(WorldMap::from_ascii("\n\n").is_err());
+    }
+
+    #[test]
+    fn hex_fill_map() {
+        let map = WorldMap::from_ascii("a.\n.b").unwrap();
+
+        let mut hex = WorldHex::new(2, 2, OdorKind::None);
+        hex.fill_map(&map, |ch| match ch {
+            'a' => Some(OdorKind::FoodA),
+            'b' => Some(OdorKind::AvoidA),
+            _ => None,
+        });
+
+        assert_eq!(OdorKind::FoodA, hex[(0, 1)]);
+        assert_eq!(OdorKind::AvoidA, hex[(1, 0)]);
+        assert_eq!(OdorKind::None, hex[(0, 0)]);
+    }
+}
@@ -1,11 +1,11 @@
 use essay_plot::api::{Color, Colors};
 use log::LevelFilter;
 use vertebrate::{
-    body::BodyEat, builder::AnimalBuilder, hind_brain::{
+    body::BodyEat, builder::{AnimalBuilder, Scenario}, hind_brain::{
         r1_thigmotaxis::{Thigmotaxis, ThigmotaxisStrategy}, ArtrR2, AvoidHerePlugin, EatStrategy, HindAvoid, HindEat, HindMove, MoveKind, Serotonin
     }, hypothalamus::{
         Dwell, Forage, HypEat, Motive, MotiveTrait, Sleep, Wake
-    }, mid_brain::tectum::OrientTectum, olfactory::{odor_place::OdorPlacePlugin, olfactory_bulb::OlfactoryBulb}, retina::Retina, ui::{
+    }, mid_brain::tectum::OrientTectum, olfactory::{odor_place::OdorPlacePlugin, olfactory_bulb::OlfactoryBulb}, retina::{Retina, RetinaStrategy}, ui::{
         ui_attention::UiAttentionPlugin, ui_body::UiBodyPlugin, ui_emoji::Emoji, ui_heatmap::UiHeatmapPlugin, 
         ui_homunculus::{Orient, UiHomunculusPlugin}, 
         ui_lateral_line::UiLateralLinePlugin, ui_motive::UiMotivePlugin, ui_radar::UiRadarPlugin, ui_retina::UiRetinaPlugin, ui_run_control::UiRunControl, ui_trail::UiTrailPlugin, 
@@ -16,7 +16,7 @@ use vertebrate::{
     }
 };
 use essay_ecs::prelude::App;
-use mind_ecs::TickSchedulePlugin;
+use mind_ecs::{HeadlessRunner, SeedPlugin, TickSchedulePlugin};
 use ui_graphics::ui_canvas::{UiBuilder, UiSubBuilder};
 
 // 
@@ -28,14 +28,18 @@ pub fn main() {
 
     app.plugin(TickSchedulePlugin::new().ticks(2));
 
-    let (w, h) = (21, 13);
-    
-    app.plugin(world_thigmotaxis(w, h)
-    );
+    // essay --seed <seed> replays a run
+    if let Some(seed) = arg_value("--seed") {
+        app.plugin(SeedPlugin::new().seed(seed.parse().expect("--seed expects an integer")));
+    } else {
+        app.plugin(SeedPlugin::new());
+    }
 
-    let mut place = WorldHexPlugin::<PlaceKind>::new(w, h);
-    place.circle((w as f32 - 7., 4.), 3., PlaceKind::AvoidA);
-    app.plugin(place);
+    // essay --scenario <file.toml> replaces the built-in arena and animal
+    let mut animal = match arg_value("--scenario") {
+        Some(path) => scenario_world(&mut app, &path),
+        None => default_world(&mut app),
+    };
 
     app.plugin(OdorPlacePlugin::<PlaceKind>::new()
         .add(PlaceKind::FoodA, "a")
@@ -50,6 +54,36 @@ pub fn main() {
         .avoid(PlaceKind::AvoidB, true)
     );
 
+    if headless_ticks().is_some() {
+        animal.retina().strategy(RetinaStrategy::RayCast);
+    }
+
+    animal.build(&mut app);
+
+    // essay --headless <ticks> runs without a window
+    if let Some(n_ticks) = headless_ticks() {
+        let mut runner = HeadlessRunner::new(app);
+        let ticks = runner.run_for(n_ticks).unwrap();
+        log::info!("headless run complete: {} ticks", ticks);
+        return;
+    }
+
+    ui_builder(&mut app);
+    //app.plugin(UiRetinaPlugin::new()); // ((2.0, 0.0), [0.5, 0.5])));
+
+    app.run().unwrap();
+}
+
+fn default_world(app: &mut App) -> AnimalBuilder {
+    let (w, h) = (21, 13);
+    
+    app.plugin(world_thigmotaxis(w, h)
+    );
+
+    let mut place = WorldHexPlugin::<PlaceKind>::new(w, h);
+    place.circle((w as f32 - 7., 4.), 3., PlaceKind::AvoidA);
+    app.plugin(place);
+
     let mut food = FoodPlugin::new();
     food.gen_count(2).gen_radius(3.).gen_value(Seconds(240.)).gen_kind(FoodKind::Plain);
     food.base_food(FoodKind::None);
@@ -101,12 +135,53 @@ pub fn main() {
     // animal.hind_eat();
     animal.hyp_forage().enable(false);
 
-    animal.build(&mut app);
+    animal
+}
 
-    ui_builder(&mut app);
-    //app.plugin(UiRetinaPlugin::new()); // ((2.0, 0.0), [0.5, 0.5])));
+fn scenario_world(app: &mut App, path: &str) -> AnimalBuilder {
+    let scenario = match Scenario::load(path) {
+        Ok(scenario) => scenario,
+        Err(err) => panic!("{}", err),
+    };
+
+    scenario.build_world(app);
+
+    scenario.build_places(app, |name| match name {
+        "none" => Some(PlaceKind::None),
+        "food_a" => Some(PlaceKind::FoodA),
+        "food_b" => Some(PlaceKind::FoodB),
+        "avoid_a" => Some(PlaceKind::AvoidA),
+        "avoid_b" => Some(PlaceKind::AvoidB),
+        "other_a" => Some(PlaceKind::OtherA),
+        "other_b" => Some(PlaceKind::OtherB),
+        _ => None,
+    }).unwrap();
+
+    scenario.animal()
+}
 
-    app.run().unwrap();
+fn headless_ticks() -> Option<u64> {
+    if std::env::args().any(|arg| arg == "--headless") {
+        let ticks = arg_value("--headless")
+            .map(|v| v.parse::<u64>().expect("--headless expects a tick count"))
+            .unwrap_or(Seconds(600.).ticks() as u64);
+
+        Some(ticks)
+    } else {
+        None
+    }
+}
+
+fn arg_value(name: &str) -> Option<String> {
+    let mut args = std::env::args().skip(1);
+
+    while let Some(arg) = args.next() {
+        if arg == name {
+            return args.next().filter(|v| ! v.starts_with("--"));
+        }
+    }
+
+    None
 }
 
 pub struct Dummy;